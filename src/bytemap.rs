@@ -0,0 +1,399 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+use vortex_layout::LayoutRef;
+
+use crate::footer::{self, EOF_SIZE, VerifierLimits, read_raw_footer};
+use crate::size::format_bytes;
+use crate::{DocumentFormat, model, open_vortex_file, print_json, truncate_string};
+
+/// Width of the text bar chart and the file strip
+const BAR_WIDTH: usize = 60;
+
+/// What a physical region of the file holds
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RegionKind {
+    /// A data segment of a column
+    Segment {
+        id: u32,
+        column: String,
+        chunk: Option<usize>,
+        path: String,
+    },
+    /// Gap between regions that can be explained by the next region's alignment
+    Padding,
+    /// Gap between regions that no segment or flatbuffer refers to
+    Unreferenced,
+    /// A flatbuffer referenced by the postscript (dtype, layout, statistics or footer)
+    Flatbuffer(&'static str),
+    Postscript,
+    Eof,
+}
+
+impl RegionKind {
+    fn name(&self) -> &'static str {
+        match self {
+            RegionKind::Segment { .. } => "segment",
+            RegionKind::Padding => "padding",
+            RegionKind::Unreferenced => "unreferenced",
+            RegionKind::Flatbuffer(_) => "flatbuffer",
+            RegionKind::Postscript => "postscript",
+            RegionKind::Eof => "eof",
+        }
+    }
+
+    /// Label used to group regions in the summary chart
+    fn label(&self) -> String {
+        match self {
+            RegionKind::Segment { column, .. } => column.clone(),
+            RegionKind::Flatbuffer(name) => format!("<{} flatbuffer>", name),
+            other => format!("<{}>", other.name()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Region {
+    offset: u64,
+    length: u64,
+    alignment: u64,
+    kind: RegionKind,
+}
+
+/// Column, chunk and layout path of a data segment
+#[derive(Clone, Debug)]
+pub struct SegmentTag {
+    pub column: String,
+    pub chunk: Option<usize>,
+    pub path: String,
+}
+
+/// Tag every segment of a layout subtree with the column and chunk it belongs to
+fn tag_segments(
+    layout: &LayoutRef,
+    column: &str,
+    chunk: Option<usize>,
+    path: &str,
+    tags: &mut BTreeMap<u32, SegmentTag>,
+) {
+    for segment_id in layout.segment_ids() {
+        tags.entry(*segment_id).or_insert_with(|| SegmentTag {
+            column: column.to_string(),
+            chunk,
+            path: path.to_string(),
+        });
+    }
+
+    let is_chunked = layout.encoding().to_string() == "vortex.chunked";
+    for idx in 0..layout.nchildren() {
+        let Ok(child) = layout.child(idx) else {
+            continue;
+        };
+        let child_chunk = if is_chunked { Some(idx) } else { chunk };
+        let child_path = format!("{}/{}", path, layout.child_type(idx).name());
+        tag_segments(&child, column, child_chunk, &child_path, tags);
+    }
+}
+
+/// Tag every segment of a file with the top-level column and chunk it belongs to
+pub fn segment_tags(layout: &LayoutRef) -> BTreeMap<u32, SegmentTag> {
+    let mut tags = BTreeMap::new();
+    if layout.encoding().to_string() == "vortex.struct" {
+        for idx in 0..layout.nchildren() {
+            if let Ok(child) = layout.child(idx) {
+                let name = layout.child_type(idx).name().to_string();
+                tag_segments(&child, &name, None, "", &mut tags);
+            }
+        }
+    }
+
+    // Segments outside the struct columns, such as the struct validity, belong to the root
+    tag_segments(layout, "<root>", None, "", &mut tags);
+    tags
+}
+
+/// Build the ordered list of regions covering the whole file
+async fn file_regions(path: &Path, limits: &VerifierLimits) -> Result<(u64, Vec<Region>)> {
+    let raw = read_raw_footer(path, limits).await?;
+    let vortex_file = open_vortex_file(path).await?;
+    let tags = segment_tags(vortex_file.footer().layout());
+
+    let mut regions: Vec<Region> = vortex_file
+        .footer()
+        .segment_map()
+        .iter()
+        .enumerate()
+        .map(|(idx, spec)| {
+            let tag = tags.get(&(idx as u32)).cloned().unwrap_or(SegmentTag {
+                column: "<unknown>".to_string(),
+                chunk: None,
+                path: String::new(),
+            });
+            Region {
+                offset: spec.offset,
+                length: spec.length as u64,
+                alignment: *spec.alignment as u64,
+                kind: RegionKind::Segment {
+                    id: idx as u32,
+                    column: tag.column,
+                    chunk: tag.chunk,
+                    path: tag.path,
+                },
+            }
+        })
+        .collect();
+
+    for segment in &raw.segments {
+        let alignment = footer::alignment(segment.alignment_exponent).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Malformed {} segment: alignment exponent {} is out of range",
+                segment.name,
+                segment.alignment_exponent
+            )
+        })?;
+        regions.push(Region {
+            offset: segment.offset,
+            length: segment.length as u64,
+            alignment,
+            kind: RegionKind::Flatbuffer(segment.name),
+        });
+    }
+    regions.push(Region {
+        offset: raw.postscript_offset,
+        length: raw.eof.postscript_size as u64,
+        alignment: 1,
+        kind: RegionKind::Postscript,
+    });
+    regions.push(Region {
+        offset: raw.file_size - EOF_SIZE,
+        length: EOF_SIZE,
+        alignment: 1,
+        kind: RegionKind::Eof,
+    });
+    regions.sort_by_key(|r| r.offset);
+
+    // Fill the gaps between regions
+    let mut filled = Vec::with_capacity(regions.len() * 2);
+    let mut position = 0;
+    for region in regions {
+        if region.offset > position {
+            let gap = region.offset - position;
+            filled.push(Region {
+                offset: position,
+                length: gap,
+                alignment: 1,
+                kind: if gap < region.alignment {
+                    RegionKind::Padding
+                } else {
+                    RegionKind::Unreferenced
+                },
+            });
+        }
+        position = position.max(region.offset + region.length);
+        filled.push(region);
+    }
+
+    Ok((raw.file_size, filled))
+}
+
+fn region_json(region: &Region) -> serde_json::Value {
+    let mut region_json = serde_json::json!({
+        "offset": region.offset,
+        "length": region.length,
+        "kind": region.kind.name(),
+    });
+
+    match &region.kind {
+        RegionKind::Segment {
+            id,
+            column,
+            chunk,
+            path,
+        } => {
+            region_json["segment_id"] = serde_json::json!(id);
+            region_json["column"] = serde_json::json!(column);
+            region_json["chunk"] = serde_json::json!(chunk);
+            region_json["path"] = serde_json::json!(path);
+            region_json["alignment"] = serde_json::json!(region.alignment);
+        }
+        RegionKind::Flatbuffer(name) => {
+            region_json["flatbuffer"] = serde_json::json!(name);
+            region_json["alignment"] = serde_json::json!(region.alignment);
+        }
+        _ => {}
+    }
+
+    region_json
+}
+
+/// Number of times consecutive data segments switch from one column to another
+fn column_switches(regions: &[Region]) -> usize {
+    let columns: Vec<&String> = regions
+        .iter()
+        .filter_map(|r| match &r.kind {
+            RegionKind::Segment { column, .. } => Some(column),
+            _ => None,
+        })
+        .collect();
+    columns.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+/// Render the file as a single line, one symbol per slice of bytes
+///
+/// Each slice shows the label holding most of its bytes.
+fn file_strip(file_size: u64, regions: &[Region], symbols: &BTreeMap<String, char>) -> String {
+    let slice = file_size.div_ceil(BAR_WIDTH as u64).max(1);
+    let mut strip = String::with_capacity(BAR_WIDTH);
+
+    for idx in 0..BAR_WIDTH as u64 {
+        let (start, end) = (idx * slice, ((idx + 1) * slice).min(file_size));
+        if start >= end {
+            break;
+        }
+
+        let mut bytes_by_label: BTreeMap<String, u64> = BTreeMap::new();
+        for region in regions {
+            let overlap =
+                (region.offset + region.length).min(end) as i128 - region.offset.max(start) as i128;
+            if overlap > 0 {
+                *bytes_by_label.entry(region.kind.label()).or_default() += overlap as u64;
+            }
+        }
+
+        let symbol = bytes_by_label
+            .into_iter()
+            .max_by_key(|(_, bytes)| *bytes)
+            .and_then(|(label, _)| symbols.get(&label).copied())
+            .unwrap_or(' ');
+        strip.push(symbol);
+    }
+
+    strip
+}
+
+pub async fn show_bytemap(
+    path: &Path,
+    format: DocumentFormat,
+    verbose: bool,
+    limits: &VerifierLimits,
+) -> Result<()> {
+    let (file_size, regions) = file_regions(path, limits).await?;
+
+    // Total bytes per label, in order of first appearance in the file
+    let mut summary: Vec<(String, u64, usize)> = Vec::new();
+    for region in &regions {
+        let label = region.kind.label();
+        match summary.iter_mut().find(|(l, _, _)| *l == label) {
+            Some((_, bytes, count)) => {
+                *bytes += region.length;
+                *count += 1;
+            }
+            None => summary.push((label, region.length, 1)),
+        }
+    }
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let bytemap_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
+                "file": path.display().to_string(),
+                "file_size": file_size,
+                "column_switches": column_switches(&regions),
+                "summary": summary
+                    .iter()
+                    .map(|(label, bytes, count)| serde_json::json!({
+                        "name": label,
+                        "bytes": bytes,
+                        "regions": count,
+                    }))
+                    .collect::<Vec<_>>(),
+                "regions": regions.iter().map(region_json).collect::<Vec<_>>(),
+            });
+            print_json(&bytemap_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            const SYMBOLS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+            // Columns get letters, everything else a fixed symbol
+            let mut symbols = BTreeMap::new();
+            let mut letters = SYMBOLS.chars();
+            for (label, _, _) in &summary {
+                let symbol = match label.as_str() {
+                    "<padding>" => '.',
+                    "<unreferenced>" => '?',
+                    "<postscript>" | "<eof>" => '#',
+                    l if l.ends_with(" flatbuffer>") => '=',
+                    _ => letters.next().unwrap_or('*'),
+                };
+                symbols.insert(label.clone(), symbol);
+            }
+
+            println!("=== Vortex File Byte Map ===");
+            println!("File: {}", path.display());
+            println!("File size: {}", format_bytes(file_size));
+            println!(
+                "Column switches between consecutive segments: {}",
+                column_switches(&regions)
+            );
+
+            println!("\n--- Regions by Content ---");
+            for (label, bytes, count) in &summary {
+                let width = if file_size == 0 {
+                    0
+                } else {
+                    (*bytes as f64 / file_size as f64 * BAR_WIDTH as f64).ceil() as usize
+                };
+                println!(
+                    "{} {:<30} {:>12} {:>6} {:>7.2}% {}",
+                    symbols[label],
+                    truncate_string(label, 30),
+                    format_bytes(*bytes),
+                    count,
+                    *bytes as f64 * 100.0 / file_size.max(1) as f64,
+                    "█".repeat(width)
+                );
+            }
+
+            println!("\n--- File Layout (start to end) ---");
+            println!("|{}|", file_strip(file_size, &regions, &symbols));
+
+            if verbose {
+                println!("\n--- Regions ---");
+                println!(
+                    "{:>14} {:>12} {:<12} {:<30} {:>6}  {}",
+                    "Offset", "Length", "Kind", "Column", "Chunk", "Path"
+                );
+                println!("{}", "-".repeat(100));
+                for region in &regions {
+                    let (column, chunk, path) = match &region.kind {
+                        RegionKind::Segment {
+                            column,
+                            chunk,
+                            path,
+                            ..
+                        } => (
+                            column.clone(),
+                            chunk.map(|c| c.to_string()).unwrap_or_default(),
+                            path.clone(),
+                        ),
+                        RegionKind::Flatbuffer(name) => {
+                            (name.to_string(), String::new(), String::new())
+                        }
+                        _ => (String::new(), String::new(), String::new()),
+                    };
+                    println!(
+                        "{:>14} {:>12} {:<12} {:<30} {:>6}  {}",
+                        region.offset,
+                        region.length,
+                        region.kind.name(),
+                        truncate_string(&column, 30),
+                        chunk,
+                        path
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use parquet::arrow::ParquetRecordBatchStreamBuilder;
+use std::path::Path;
+use std::time::Instant;
+use vortex_array::ArrayRef;
+use vortex_array::arrow::FromArrowArray;
+use vortex_array::stream::ArrayStreamAdapter;
+use vortex_dtype::DType;
+use vortex_dtype::arrow::FromArrowType;
+use vortex_error::vortex_err;
+use vortex_file::WriteOptionsSessionExt;
+
+use crate::strategy::{WriteOptions, build_write_strategy};
+use crate::{create_session, open_vortex_file};
+
+/// Convert a Parquet file into a Vortex file, streaming one batch at a time
+pub async fn run_convert(input: &Path, output: &Path, options: WriteOptions) -> Result<()> {
+    let start = Instant::now();
+
+    let parquet_file = tokio::fs::File::open(input)
+        .await
+        .context(format!("Failed to open Parquet file: {}", input.display()))?;
+    let input_size = parquet_file.metadata().await?.len();
+
+    let builder = ParquetRecordBatchStreamBuilder::new(parquet_file)
+        .await
+        .context(format!(
+            "Failed to read Parquet metadata: {}",
+            input.display()
+        ))?;
+    let row_groups = builder.metadata().num_row_groups();
+    let dtype = DType::from_arrow(builder.schema().as_ref());
+
+    let batches = builder
+        .with_batch_size(options.input_chunk_rows())
+        .build()?
+        .map(|batch| {
+            batch
+                .map(|batch| ArrayRef::from_arrow(batch, false))
+                .map_err(|e| vortex_err!("Failed to read Parquet batch: {}", e))
+        });
+
+    let session = create_session();
+    let mut output_file = tokio::fs::File::create(output).await.context(format!(
+        "Failed to create output file: {}",
+        output.display()
+    ))?;
+
+    session
+        .write_options()
+        .with_strategy(build_write_strategy(&options))
+        .write(&mut output_file, ArrayStreamAdapter::new(dtype, batches))
+        .await
+        .context("Failed to write Vortex file")?;
+
+    let output_size = tokio::fs::metadata(output).await?.len();
+    let row_count = open_vortex_file(output).await?.row_count();
+
+    println!("=== Parquet to Vortex Conversion ===");
+    println!("Input: {} ({} row groups)", input.display(), row_groups);
+    println!("Output: {}", output.display());
+    println!("Rows: {}", row_count);
+    println!("Compression: {:?}", options.compression);
+    println!(
+        "Stats layouts: {}",
+        if options.no_stats { "no" } else { "yes" }
+    );
+    println!("Parquet size: {} bytes", input_size);
+    println!("Vortex size: {} bytes", output_size);
+    if output_size > 0 {
+        println!("Size ratio: {:.2}x", input_size as f64 / output_size as f64);
+    }
+    println!("Elapsed: {:.2}s", start.elapsed().as_secs_f64());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use clap::Parser;
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+    use vortex_array::Array;
+
+    #[derive(Parser)]
+    struct Args {
+        #[command(flatten)]
+        options: WriteOptions,
+    }
+
+    fn parse_options(args: &[&str]) -> std::result::Result<WriteOptions, clap::Error> {
+        Args::try_parse_from(std::iter::once("convert").chain(args.iter().copied()))
+            .map(|args| args.options)
+    }
+
+    fn write_parquet(path: &Path, rows: i64) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..rows)),
+                Arc::new(Float64Array::from_iter(
+                    (0..rows).map(|i| (i % 7 != 0).then_some(i as f64 / 2.0)),
+                )),
+            ],
+        )
+        .unwrap();
+
+        let file = std::fs::File::create(path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    /// Length of every chunk scanned from a Vortex file
+    async fn chunk_lens(path: &Path) -> Vec<usize> {
+        let stream = open_vortex_file(path)
+            .await
+            .unwrap()
+            .scan()
+            .unwrap()
+            .into_array_stream()
+            .unwrap();
+        stream.map(|chunk| chunk.unwrap().len()).collect().await
+    }
+
+    #[tokio::test]
+    async fn conversion_preserves_rows_and_dtype() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.parquet");
+        let output = dir.path().join("out.vortex");
+        write_parquet(&input, 1050);
+
+        for args in [&[][..], &["--compression", "compact", "--no-stats"][..]] {
+            run_convert(&input, &output, parse_options(args).unwrap())
+                .await
+                .unwrap();
+
+            let vortex_file = open_vortex_file(&output).await.unwrap();
+            assert_eq!(vortex_file.row_count(), 1050);
+            let fields = vortex_file.dtype().as_struct_fields_opt().unwrap();
+            let names: Vec<String> = fields.names().iter().map(|n| n.to_string()).collect();
+            assert_eq!(names, vec!["id", "value"]);
+            assert!(!fields.field("id").unwrap().is_nullable());
+            assert!(fields.field("value").unwrap().is_nullable());
+        }
+    }
+
+    #[tokio::test]
+    async fn chunk_rows_bounds_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.parquet");
+        let output = dir.path().join("out.vortex");
+        write_parquet(&input, 1050);
+
+        let options = parse_options(&["--chunk-rows", "100"]).unwrap();
+        run_convert(&input, &output, options).await.unwrap();
+
+        let mut expected = vec![100; 10];
+        expected.push(50);
+        assert_eq!(chunk_lens(&output).await, expected);
+    }
+
+    #[test]
+    fn chunk_rows_is_validated() {
+        assert_eq!(parse_options(&[]).unwrap().chunk_rows, None);
+        assert_eq!(
+            parse_options(&["--chunk-rows", "100"]).unwrap().chunk_rows,
+            Some(100)
+        );
+        assert!(parse_options(&["--chunk-rows", "0"]).is_err());
+        assert!(parse_options(&["--chunk-rows", "100", "--chunk-bytes", "1024"]).is_err());
+    }
+}
@@ -0,0 +1,548 @@
+use anyhow::{Context, Result};
+use async_walkdir::WalkDir;
+use futures::StreamExt;
+use glob::{MatchOptions, Pattern};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use vortex_dtype::DType;
+
+use crate::encoding::{EncodingHistogram, column_encodings};
+use crate::size::format_bytes;
+use crate::{
+    DocumentFormat, find_vortex_files, model, open_vortex_file, print_json, truncate_string,
+};
+
+/// Number of files opened at the same time
+pub const MAX_CONCURRENT_FILES: usize = 32;
+
+/// Which command a dataset report is produced for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DatasetView {
+    Metadata,
+    Schema,
+    Layout,
+    /// Top-level encodings, read from the first `sample_chunks` chunks of each column if set
+    Encoding {
+        sample_chunks: Option<usize>,
+    },
+    Inspect,
+}
+
+fn is_pattern(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// Whether a command input names several files: a directory or a glob
+pub fn is_dataset(path: &Path) -> bool {
+    is_pattern(path) || path.is_dir()
+}
+
+/// Files matching a glob, found by walking the directory before its first wildcard
+async fn glob_files(pattern: &Path) -> Result<Vec<PathBuf>> {
+    let matcher = Pattern::new(&pattern.to_string_lossy())
+        .context(format!("Invalid glob: {}", pattern.display()))?;
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::default()
+    };
+
+    let base: PathBuf = pattern
+        .components()
+        .take_while(|c| !is_pattern(Path::new(c.as_os_str())))
+        .collect();
+    let relative = base.as_os_str().is_empty();
+    let walk_root = if relative { PathBuf::from(".") } else { base };
+
+    let mut files = Vec::new();
+    let mut entries = WalkDir::new(&walk_root);
+    while let Some(entry) = entries.next().await {
+        let entry = entry.context(format!("Failed to walk directory: {}", walk_root.display()))?;
+        if !entry.file_type().await?.is_file() {
+            continue;
+        }
+
+        let mut path = entry.path();
+        if relative {
+            path = path
+                .components()
+                .skip_while(|c| *c == Component::CurDir)
+                .collect();
+        }
+        if matcher.matches_path_with(&path, options) {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The files of a dataset given as a directory, a glob or a single file
+pub async fn dataset_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let files = if path.is_file() {
+        vec![path.to_path_buf()]
+    } else if is_pattern(path) {
+        glob_files(path).await?
+    } else {
+        find_vortex_files(path).await?
+    };
+
+    if files.is_empty() {
+        anyhow::bail!("No Vortex files found: {}", path.display());
+    }
+    Ok(files)
+}
+
+/// What is known about a single file of a dataset
+#[derive(Debug)]
+struct FileSummary {
+    path: PathBuf,
+    file_size: u64,
+    row_count: u64,
+    dtype: DType,
+    layout_encoding: String,
+    segment_count: usize,
+    has_statistics: bool,
+    /// Top-level encodings of all columns, only scanned for the encoding view
+    encodings: Option<EncodingHistogram>,
+}
+
+async fn summarize_file(path: PathBuf, view: DatasetView) -> Result<FileSummary> {
+    let vortex_file = open_vortex_file(&path).await?;
+    let file_size = tokio::fs::metadata(&path).await?.len();
+
+    let encodings = if let DatasetView::Encoding { sample_chunks } = view {
+        let mut histogram = EncodingHistogram::default();
+        for (_, column) in column_encodings(&vortex_file, sample_chunks).await? {
+            histogram.merge(&column);
+        }
+        Some(histogram)
+    } else {
+        None
+    };
+
+    Ok(FileSummary {
+        file_size,
+        row_count: vortex_file.row_count(),
+        dtype: vortex_file.dtype().clone(),
+        layout_encoding: vortex_file.footer().layout().encoding().to_string(),
+        segment_count: vortex_file.footer().segment_map().len(),
+        has_statistics: vortex_file.file_stats().is_some(),
+        encodings,
+        path,
+    })
+}
+
+/// Files sharing the same dtype
+#[derive(Debug)]
+struct SchemaVariant {
+    dtype: DType,
+    files: usize,
+    row_count: u64,
+    file_size: u64,
+}
+
+fn field_count(dtype: &DType) -> Option<usize> {
+    dtype
+        .as_struct_fields_opt()
+        .map(|fields| fields.names().len())
+}
+
+fn dtype_summary(dtype: &DType) -> String {
+    match field_count(dtype) {
+        Some(fields) => format!("Struct with {} fields", fields),
+        None => format!("{}", dtype),
+    }
+}
+
+/// Summarize every file, sorted by path, along with the files that could not be read
+async fn summarize_files(
+    files: Vec<PathBuf>,
+    view: DatasetView,
+) -> (Vec<FileSummary>, Vec<(PathBuf, String)>) {
+    let mut results: Vec<(PathBuf, Result<FileSummary>)> = futures::stream::iter(files)
+        .map(|path| async move {
+            let result = summarize_file(path.clone(), view).await;
+            (path, result)
+        })
+        .buffer_unordered(MAX_CONCURRENT_FILES)
+        .collect()
+        .await;
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut summaries = Vec::with_capacity(results.len());
+    let mut failures = Vec::new();
+    for (path, result) in results {
+        match result {
+            Ok(summary) => summaries.push(summary),
+            Err(e) => failures.push((path, format!("{:#}", e))),
+        }
+    }
+    (summaries, failures)
+}
+
+/// Group files by dtype, numbering variants in order of first appearance
+///
+/// Returns the variants and the index of the variant of each file.
+fn schema_variants(summaries: &[FileSummary]) -> (Vec<SchemaVariant>, Vec<usize>) {
+    let mut variants: Vec<SchemaVariant> = Vec::new();
+    let mut file_variants = Vec::with_capacity(summaries.len());
+    for summary in summaries {
+        let idx = match variants.iter().position(|v| v.dtype == summary.dtype) {
+            Some(idx) => idx,
+            None => {
+                variants.push(SchemaVariant {
+                    dtype: summary.dtype.clone(),
+                    files: 0,
+                    row_count: 0,
+                    file_size: 0,
+                });
+                variants.len() - 1
+            }
+        };
+        let variant = &mut variants[idx];
+        variant.files += 1;
+        variant.row_count += summary.row_count;
+        variant.file_size += summary.file_size;
+        file_variants.push(idx);
+    }
+    (variants, file_variants)
+}
+
+/// Print a per-file table and a rollup for every Vortex file of a directory or glob
+pub async fn show_dataset(input: &Path, format: DocumentFormat, view: DatasetView) -> Result<()> {
+    let files = dataset_files(input).await?;
+    let file_count = files.len();
+
+    let (summaries, failures) = summarize_files(files, view).await;
+    let (variants, file_variants) = schema_variants(&summaries);
+
+    let mut layouts: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut encodings = EncodingHistogram::default();
+    for summary in &summaries {
+        *layouts.entry(summary.layout_encoding.as_str()).or_default() += 1;
+        if let Some(histogram) = &summary.encodings {
+            encodings.merge(histogram);
+        }
+    }
+
+    let total_rows: u64 = summaries.iter().map(|s| s.row_count).sum();
+    let total_bytes: u64 = summaries.iter().map(|s| s.file_size).sum();
+    let display_path = |path: &Path| {
+        let relative = if is_pattern(input) {
+            path
+        } else {
+            path.strip_prefix(input).unwrap_or(path)
+        };
+        relative.display().to_string()
+    };
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let files_json: Vec<_> = summaries
+                .iter()
+                .zip(&file_variants)
+                .map(|(summary, variant)| {
+                    let mut file_json = serde_json::json!({
+                        "file": summary.path.display().to_string(),
+                        "row_count": summary.row_count,
+                        "file_size": summary.file_size,
+                        "schema_variant": variant + 1,
+                    });
+                    if matches!(view, DatasetView::Layout | DatasetView::Inspect) {
+                        file_json["layout"] = serde_json::json!(summary.layout_encoding);
+                        file_json["segments"] = serde_json::json!(summary.segment_count);
+                    }
+                    if view == DatasetView::Inspect {
+                        file_json["statistics"] = serde_json::json!(summary.has_statistics);
+                    }
+                    if let Some(histogram) = &summary.encodings {
+                        file_json["encodings"] = histogram.to_json();
+                    }
+                    file_json
+                })
+                .collect();
+
+            let mut rollup_json = serde_json::json!({
+                "files": summaries.len(),
+                "failed_files": failures.len(),
+                "row_count": total_rows,
+                "file_size": total_bytes,
+                "schema_variants": variants
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, variant)| serde_json::json!({
+                        "variant": idx + 1,
+                        "files": variant.files,
+                        "row_count": variant.row_count,
+                        "file_size": variant.file_size,
+                        "dtype": model::dtype_json(&variant.dtype),
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            if matches!(view, DatasetView::Layout | DatasetView::Inspect) {
+                rollup_json["layouts"] = serde_json::json!(layouts);
+            }
+            if let DatasetView::Encoding { sample_chunks } = view {
+                rollup_json["sample_chunks"] = serde_json::json!(sample_chunks);
+                rollup_json["encodings"] = encodings.to_json();
+            }
+
+            let dataset_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
+                "input": input.display().to_string(),
+                "files": files_json,
+                "errors": failures
+                    .iter()
+                    .map(|(path, error)| serde_json::json!({
+                        "file": path.display().to_string(),
+                        "error": error,
+                    }))
+                    .collect::<Vec<_>>(),
+                "rollup": rollup_json,
+            });
+            print_json(&dataset_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex Dataset ===");
+            println!("Input: {}", input.display());
+            println!("Files: {}", file_count);
+
+            println!("\n--- Files ---");
+            let extra_header = match view {
+                DatasetView::Metadata | DatasetView::Schema => format!("{:>7}", "Fields"),
+                DatasetView::Layout => format!("{:<16} {:>9}", "Layout", "Segments"),
+                DatasetView::Inspect => {
+                    format!("{:<16} {:>9} {:<6}", "Layout", "Segments", "Stats")
+                }
+                DatasetView::Encoding { .. } => "Encodings".to_string(),
+            };
+            println!(
+                "{:<50} {:>14} {:>12} {:>7} {}",
+                "File", "Rows", "Size", "Schema", extra_header
+            );
+            println!("{}", "-".repeat(120));
+
+            for (summary, variant) in summaries.iter().zip(&file_variants) {
+                let extra = match view {
+                    DatasetView::Metadata | DatasetView::Schema => format!(
+                        "{:>7}",
+                        field_count(&summary.dtype)
+                            .map(|c| c.to_string())
+                            .unwrap_or_else(|| "-".to_string())
+                    ),
+                    DatasetView::Layout => format!(
+                        "{:<16} {:>9}",
+                        summary.layout_encoding, summary.segment_count
+                    ),
+                    DatasetView::Inspect => format!(
+                        "{:<16} {:>9} {:<6}",
+                        summary.layout_encoding,
+                        summary.segment_count,
+                        if summary.has_statistics { "yes" } else { "no" }
+                    ),
+                    DatasetView::Encoding { .. } => summary
+                        .encodings
+                        .as_ref()
+                        .map(|h| h.short_summary())
+                        .unwrap_or_default(),
+                };
+                println!(
+                    "{:<50} {:>14} {:>12} {:>7} {}",
+                    truncate_string(&display_path(&summary.path), 50),
+                    summary.row_count,
+                    format_bytes(summary.file_size),
+                    format!("v{}", variant + 1),
+                    extra
+                );
+            }
+
+            if !failures.is_empty() {
+                println!("\n--- Errors ---");
+                for (path, error) in &failures {
+                    println!("{}: {}", display_path(path), error);
+                }
+            }
+
+            println!("\n--- Rollup ---");
+            println!("Files: {} ({} failed)", summaries.len(), failures.len());
+            println!("Total rows: {}", total_rows);
+            println!("Total size: {}", format_bytes(total_bytes));
+
+            println!("\nSchema variants: {}", variants.len());
+            for (idx, variant) in variants.iter().enumerate() {
+                println!(
+                    "  v{}: {} files, {} rows, {} ({})",
+                    idx + 1,
+                    variant.files,
+                    variant.row_count,
+                    format_bytes(variant.file_size),
+                    dtype_summary(&variant.dtype)
+                );
+
+                if view == DatasetView::Schema
+                    && let Some(fields) = variant.dtype.as_struct_fields_opt()
+                {
+                    for (name, dtype) in fields.names().iter().zip(fields.fields()) {
+                        println!("      {:<40} {}", truncate_string(name, 40), dtype);
+                    }
+                }
+            }
+
+            if matches!(view, DatasetView::Layout | DatasetView::Inspect) {
+                println!("\nRoot layouts:");
+                for (layout, files) in &layouts {
+                    println!("  {:<30} {} files", layout, files);
+                }
+            }
+
+            if let DatasetView::Encoding { sample_chunks } = view {
+                match sample_chunks {
+                    Some(n) => {
+                        println!("\nTop-level encodings (first {} chunks of each column):", n)
+                    }
+                    None => println!("\nTop-level encodings:"),
+                }
+                println!("{:<30} {:>10} {:>14}", "Encoding", "Chunks", "Bytes");
+                println!("{}", "-".repeat(56));
+                for (encoding, count) in encodings.sorted() {
+                    println!(
+                        "{:<30} {:>10} {:>14}",
+                        encoding,
+                        count.chunks,
+                        format_bytes(count.bytes)
+                    );
+                }
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        anyhow::bail!("Failed to read {} of {} files", failures.len(), file_count);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+    use std::sync::Arc;
+
+    use crate::testing::write_vortex_file;
+
+    async fn write_ids(path: &Path, rows: i64) {
+        let ids: ArrayRef = Arc::new(Int64Array::from_iter_values(0..rows));
+        let batch = RecordBatch::try_from_iter([("id", ids)]).unwrap();
+        write_vortex_file(path, vec![batch]).await.unwrap();
+    }
+
+    async fn write_ids_and_names(path: &Path, rows: i64) {
+        let ids: ArrayRef = Arc::new(Int64Array::from_iter_values(0..rows));
+        let names: ArrayRef = Arc::new(StringArray::from_iter_values(
+            (0..rows).map(|i| format!("name-{}", i)),
+        ));
+        let batch = RecordBatch::try_from_iter([("id", ids), ("name", names)]).unwrap();
+        write_vortex_file(path, vec![batch]).await.unwrap();
+    }
+
+    /// A dataset with two schema variants, one file of which is in a subdirectory
+    async fn write_dataset(dir: &Path) {
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        write_ids(&dir.join("a.vortex"), 10).await;
+        write_ids(&dir.join("b.vortex"), 20).await;
+        write_ids_and_names(&dir.join("c.vortex"), 30).await;
+        write_ids(&dir.join("sub").join("d.vortex"), 40).await;
+        std::fs::write(dir.join("notes.txt"), "not a vortex file").unwrap();
+    }
+
+    async fn glob_names(dir: &Path, pattern: &str) -> Vec<String> {
+        glob_files(&dir.join(pattern))
+            .await
+            .unwrap()
+            .iter()
+            .map(|path| path.strip_prefix(dir).unwrap().display().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn globs_do_not_cross_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        write_dataset(dir.path()).await;
+
+        assert_eq!(
+            glob_names(dir.path(), "*.vortex").await,
+            vec!["a.vortex", "b.vortex", "c.vortex"]
+        );
+        assert_eq!(
+            glob_names(dir.path(), "*").await,
+            vec!["a.vortex", "b.vortex", "c.vortex", "notes.txt"]
+        );
+        assert_eq!(
+            glob_names(dir.path(), "*/*.vortex").await,
+            vec!["sub/d.vortex"]
+        );
+        assert_eq!(
+            glob_names(dir.path(), "**/*.vortex").await,
+            vec!["a.vortex", "b.vortex", "c.vortex", "sub/d.vortex"]
+        );
+        assert_eq!(
+            glob_names(dir.path(), "[ab].vortex").await,
+            vec!["a.vortex", "b.vortex"]
+        );
+        assert!(glob_names(dir.path(), "*.parquet").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn files_are_grouped_by_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        write_dataset(dir.path()).await;
+
+        let files = dataset_files(&dir.path().join("**/*.vortex"))
+            .await
+            .unwrap();
+        let (summaries, failures) = summarize_files(files, DatasetView::Schema).await;
+        assert!(failures.is_empty());
+
+        let (variants, file_variants) = schema_variants(&summaries);
+        assert_eq!(file_variants, vec![0, 0, 1, 0]);
+        assert_eq!(
+            variants
+                .iter()
+                .map(|v| (v.files, v.row_count, field_count(&v.dtype)))
+                .collect::<Vec<_>>(),
+            vec![(3, 70, Some(1)), (1, 30, Some(2))]
+        );
+        assert_eq!(
+            variants.iter().map(|v| v.file_size).sum::<u64>(),
+            summaries.iter().map(|s| s.file_size).sum::<u64>()
+        );
+    }
+
+    #[tokio::test]
+    async fn unreadable_files_fail_the_report() {
+        let dir = tempfile::tempdir().unwrap();
+        write_dataset(dir.path()).await;
+
+        for view in [
+            DatasetView::Metadata,
+            DatasetView::Schema,
+            DatasetView::Layout,
+            DatasetView::Inspect,
+            DatasetView::Encoding {
+                sample_chunks: Some(1),
+            },
+        ] {
+            show_dataset(dir.path(), DocumentFormat::Json, view)
+                .await
+                .unwrap();
+        }
+
+        std::fs::write(dir.path().join("broken.vortex"), "not a vortex file").unwrap();
+        let err = show_dataset(dir.path(), DocumentFormat::Text, DatasetView::Metadata)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Failed to read 1 of 5 files");
+    }
+}
@@ -0,0 +1,982 @@
+use anyhow::{Context, Result};
+use arrow::array::ArrayRef as ArrowArrayRef;
+use arrow::compute::cast;
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::row::{OwnedRow, RowConverter, Rows, SortField};
+use arrow::util::display::array_value_to_string;
+use futures::{Stream, StreamExt};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use vortex::expr::{root, select};
+use vortex_array::ArrayRef;
+use vortex_dtype::{FieldName, FieldNames};
+use vortex_file::VortexFile;
+
+use crate::encoding::{EncodingHistogram, column_encodings};
+use crate::rows::to_record_batch;
+use crate::schema_check::{FieldChange, diff_dtypes};
+use crate::size::{ColumnSize, column_sizes, format_bytes};
+use crate::{
+    DocumentFormat, column_layout_encodings, model, open_vortex_file, print_json, truncate_string,
+};
+
+/// How far to compare two files, each level including the previous ones
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiffLevel {
+    Schema,
+    Layout,
+    Data,
+}
+
+/// Data layout, top-level encodings and compressed size of a column in each file
+#[derive(Debug)]
+struct ColumnLayoutDiff {
+    name: String,
+    layout_a: Option<String>,
+    layout_b: Option<String>,
+    encodings_a: Option<EncodingHistogram>,
+    encodings_b: Option<EncodingHistogram>,
+    bytes_a: Option<u64>,
+    bytes_b: Option<u64>,
+}
+
+/// Number of chunks using each encoding
+fn encoding_chunks(histogram: &Option<EncodingHistogram>) -> Vec<(&str, usize)> {
+    histogram
+        .iter()
+        .flat_map(|h| &h.entries)
+        .map(|(encoding, count)| (encoding.as_str(), count.chunks))
+        .collect()
+}
+
+/// Encodings used by chunks of the first histogram but none of the second
+fn encodings_only_in(
+    histogram: &Option<EncodingHistogram>,
+    other: &Option<EncodingHistogram>,
+) -> Vec<String> {
+    let Some(histogram) = histogram else {
+        return Vec::new();
+    };
+    histogram
+        .entries
+        .keys()
+        .filter(|encoding| {
+            other
+                .as_ref()
+                .is_none_or(|other| !other.entries.contains_key(*encoding))
+        })
+        .cloned()
+        .collect()
+}
+
+impl ColumnLayoutDiff {
+    fn encodings_changed(&self) -> bool {
+        encoding_chunks(&self.encodings_a) != encoding_chunks(&self.encodings_b)
+    }
+
+    fn status(&self) -> &'static str {
+        match (&self.layout_a, &self.layout_b) {
+            (Some(_), None) => "only in A",
+            (None, Some(_)) => "only in B",
+            (a, b) if a != b => "layout changed",
+            _ if self.encodings_changed() => "encodings changed",
+            _ if self.bytes_a != self.bytes_b => "size changed",
+            _ => "same",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LayoutDiff {
+    columns: Vec<ColumnLayoutDiff>,
+    /// Number of chunks of each column read for the encodings, all chunks if unset
+    sample_chunks: Option<usize>,
+}
+
+async fn layout_diff(
+    file_a: &VortexFile,
+    file_b: &VortexFile,
+    sample_chunks: Option<usize>,
+) -> Result<LayoutDiff> {
+    let layouts_a = column_layout_encodings(file_a.footer().layout());
+    let layouts_b = column_layout_encodings(file_b.footer().layout());
+    let sizes_a = column_sizes(file_a);
+    let sizes_b = column_sizes(file_b);
+    let encodings_a = column_encodings(file_a, sample_chunks)
+        .await
+        .context("Failed to read the encodings of file A")?;
+    let encodings_b = column_encodings(file_b, sample_chunks)
+        .await
+        .context("Failed to read the encodings of file B")?;
+
+    // Columns of A in file order, then the columns only in B
+    let mut names: Vec<String> = sizes_a.iter().map(|c| c.name.clone()).collect();
+    for column in &sizes_b {
+        if !names.contains(&column.name) {
+            names.push(column.name.clone());
+        }
+    }
+
+    let lookup = |layouts: &[(String, String)], name: &str| {
+        layouts
+            .iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, layout)| layout.clone())
+    };
+    let histogram = |encodings: &[(String, EncodingHistogram)], name: &str| {
+        encodings
+            .iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, histogram)| histogram.clone())
+    };
+    let columns = names
+        .into_iter()
+        .map(|name| {
+            let size = |sizes: &[ColumnSize]| {
+                sizes
+                    .iter()
+                    .find(|c| c.name == name)
+                    .map(|c| c.compressed_bytes)
+            };
+            ColumnLayoutDiff {
+                // Files without a struct layout have a single `<root>` column
+                layout_a: lookup(&layouts_a, &name).or_else(|| {
+                    size(&sizes_a).map(|_| file_a.footer().layout().encoding().to_string())
+                }),
+                layout_b: lookup(&layouts_b, &name).or_else(|| {
+                    size(&sizes_b).map(|_| file_b.footer().layout().encoding().to_string())
+                }),
+                encodings_a: histogram(&encodings_a, &name),
+                encodings_b: histogram(&encodings_b, &name),
+                bytes_a: size(&sizes_a),
+                bytes_b: size(&sizes_b),
+                name,
+            }
+        })
+        .collect();
+
+    Ok(LayoutDiff {
+        columns,
+        sample_chunks,
+    })
+}
+
+/// A difference found while comparing rows
+#[derive(Debug)]
+enum RowDifference {
+    /// Values of a row that differ, as (column, value in A, value in B)
+    Changed {
+        row: String,
+        values: Vec<(String, String, String)>,
+    },
+    Added {
+        row: String,
+    },
+    Removed {
+        row: String,
+    },
+}
+
+impl RowDifference {
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            RowDifference::Changed { row, values } => serde_json::json!({
+                "kind": "changed",
+                "row": row,
+                "values": values
+                    .iter()
+                    .map(|(column, a, b)| serde_json::json!({
+                        "column": column,
+                        "a": a,
+                        "b": b,
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+            RowDifference::Added { row } => serde_json::json!({ "kind": "added", "row": row }),
+            RowDifference::Removed { row } => serde_json::json!({ "kind": "removed", "row": row }),
+        }
+    }
+}
+
+/// Outcome of comparing the rows of two files
+#[derive(Debug, Default)]
+struct DataDiff {
+    columns: Vec<String>,
+    key: Option<String>,
+    rows_a: u64,
+    rows_b: u64,
+    unchanged: u64,
+    changed: u64,
+    added: u64,
+    removed: u64,
+    duplicate_keys: u64,
+    changed_by_column: Vec<u64>,
+    samples: Vec<RowDifference>,
+    sample_limit: usize,
+}
+
+impl DataDiff {
+    /// Whether every row of A matches a single row of B and the reverse
+    fn is_identical(&self) -> bool {
+        self.changed == 0
+            && self.added == 0
+            && self.removed == 0
+            && self.duplicate_keys == 0
+            && self.rows_a == self.rows_b
+    }
+
+    fn sample(&mut self, difference: RowDifference) {
+        if self.samples.len() < self.sample_limit {
+            self.samples.push(difference);
+        }
+    }
+
+    /// Compare a row of each file, recording the columns that differ
+    fn compare(
+        &mut self,
+        label: impl FnOnce() -> String,
+        a: &ConvertedBatch,
+        row_a: usize,
+        b: &ConvertedBatch,
+        row_b: usize,
+    ) {
+        let changed: Vec<usize> = (0..a.rows.len())
+            .filter(|&idx| a.rows[idx].row(row_a) != b.rows[idx].row(row_b))
+            .collect();
+
+        if changed.is_empty() {
+            self.unchanged += 1;
+            return;
+        }
+
+        self.changed += 1;
+        for &idx in &changed {
+            self.changed_by_column[idx] += 1;
+        }
+        if self.samples.len() < self.sample_limit {
+            let values = changed
+                .iter()
+                .map(|&idx| {
+                    (
+                        self.columns[idx].clone(),
+                        a.value(idx, row_a),
+                        b.value(idx, row_b),
+                    )
+                })
+                .collect();
+            self.sample(RowDifference::Changed {
+                row: label(),
+                values,
+            });
+        }
+    }
+}
+
+/// A record batch cast to the compared types, with the row format of each column
+struct ConvertedBatch {
+    columns: Vec<ArrowArrayRef>,
+    rows: Vec<Rows>,
+}
+
+impl ConvertedBatch {
+    fn len(&self) -> usize {
+        self.columns.first().map(|c| c.len()).unwrap_or(0)
+    }
+
+    fn value(&self, column: usize, row: usize) -> String {
+        array_value_to_string(&self.columns[column], row).unwrap_or_else(|e| format!("<{}>", e))
+    }
+}
+
+/// Compares batches of both files through a shared row format
+struct RowComparator {
+    schema: SchemaRef,
+    converters: Vec<RowConverter>,
+}
+
+impl RowComparator {
+    fn try_new(schema: SchemaRef) -> Result<Self> {
+        let converters = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                Ok(RowConverter::new(vec![SortField::new(
+                    field.data_type().clone(),
+                )])?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { schema, converters })
+    }
+
+    /// Read the next non-empty batch of a scan, cast to the compared types
+    async fn next_batch<S, E>(&self, stream: &mut S) -> Result<Option<ConvertedBatch>>
+    where
+        S: Stream<Item = std::result::Result<ArrayRef, E>> + Unpin,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        while let Some(chunk) = stream.next().await {
+            let batch = to_record_batch(chunk?)?;
+            if batch.num_rows() == 0 {
+                continue;
+            }
+
+            let columns = self
+                .schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    let column = batch
+                        .column_by_name(field.name())
+                        .ok_or_else(|| anyhow::anyhow!("Column not found: {}", field.name()))?;
+                    cast(column, field.data_type())
+                        .context(format!("Cannot compare column {}", field.name()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let rows = self
+                .converters
+                .iter()
+                .zip(&columns)
+                .map(|(converter, column)| {
+                    Ok(converter.convert_columns(std::slice::from_ref(column))?)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            return Ok(Some(ConvertedBatch { columns, rows }));
+        }
+        Ok(None)
+    }
+}
+
+/// Compare rows in order: row N of A against row N of B
+async fn diff_rows_in_order<A, B, E>(
+    comparator: &RowComparator,
+    stream_a: &mut A,
+    stream_b: &mut B,
+    diff: &mut DataDiff,
+) -> Result<()>
+where
+    A: Stream<Item = std::result::Result<ArrayRef, E>> + Unpin,
+    B: Stream<Item = std::result::Result<ArrayRef, E>> + Unpin,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut a: Option<(ConvertedBatch, usize)> = None;
+    let mut b: Option<(ConvertedBatch, usize)> = None;
+    let mut position = 0u64;
+
+    loop {
+        if a.as_ref()
+            .is_none_or(|(batch, offset)| *offset == batch.len())
+        {
+            a = comparator
+                .next_batch(stream_a)
+                .await?
+                .map(|batch| (batch, 0));
+        }
+        if b.as_ref()
+            .is_none_or(|(batch, offset)| *offset == batch.len())
+        {
+            b = comparator
+                .next_batch(stream_b)
+                .await?
+                .map(|batch| (batch, 0));
+        }
+        let (Some((batch_a, offset_a)), Some((batch_b, offset_b))) = (&mut a, &mut b) else {
+            break;
+        };
+
+        let len = (batch_a.len() - *offset_a).min(batch_b.len() - *offset_b);
+        for idx in 0..len {
+            let row = position + idx as u64;
+            diff.compare(
+                || format!("row {}", row),
+                batch_a,
+                *offset_a + idx,
+                batch_b,
+                *offset_b + idx,
+            );
+        }
+        *offset_a += len;
+        *offset_b += len;
+        position += len as u64;
+    }
+
+    // Rows past the end of the shorter file
+    if diff.rows_a > position {
+        diff.removed = diff.rows_a - position;
+        diff.sample(RowDifference::Removed {
+            row: format!("rows {}..{}", position, diff.rows_a),
+        });
+    }
+    if diff.rows_b > position {
+        diff.added = diff.rows_b - position;
+        diff.sample(RowDifference::Added {
+            row: format!("rows {}..{}", position, diff.rows_b),
+        });
+    }
+
+    Ok(())
+}
+
+/// Compare rows matched by the value of a key column
+///
+/// The rows of A are held in memory while B is streamed.
+async fn diff_rows_by_key<A, B, E>(
+    comparator: &RowComparator,
+    key: usize,
+    stream_a: &mut A,
+    stream_b: &mut B,
+    diff: &mut DataDiff,
+) -> Result<()>
+where
+    A: Stream<Item = std::result::Result<ArrayRef, E>> + Unpin,
+    B: Stream<Item = std::result::Result<ArrayRef, E>> + Unpin,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let key_label = |batch: &ConvertedBatch, row: usize| {
+        format!(
+            "{}={}",
+            comparator.schema.field(key).name(),
+            batch.value(key, row)
+        )
+    };
+
+    let mut batches_a = Vec::new();
+    let mut rows_by_key: HashMap<OwnedRow, (usize, usize)> = HashMap::new();
+    while let Some(batch) = comparator.next_batch(stream_a).await? {
+        for row in 0..batch.len() {
+            let owned = batch.rows[key].row(row).owned();
+            if rows_by_key.contains_key(&owned) {
+                diff.duplicate_keys += 1;
+            } else {
+                rows_by_key.insert(owned, (batches_a.len(), row));
+            }
+        }
+        batches_a.push(batch);
+    }
+
+    let mut matched = BTreeSet::new();
+    while let Some(batch_b) = comparator.next_batch(stream_b).await? {
+        for row_b in 0..batch_b.len() {
+            let owned = batch_b.rows[key].row(row_b).owned();
+            match rows_by_key.get(&owned) {
+                None => {
+                    diff.added += 1;
+                    diff.sample(RowDifference::Added {
+                        row: key_label(&batch_b, row_b),
+                    });
+                }
+                Some(&(batch_idx, row_a)) => {
+                    if !matched.insert((batch_idx, row_a)) {
+                        diff.duplicate_keys += 1;
+                        continue;
+                    }
+                    diff.compare(
+                        || key_label(&batch_b, row_b),
+                        &batches_a[batch_idx],
+                        row_a,
+                        &batch_b,
+                        row_b,
+                    );
+                }
+            }
+        }
+    }
+
+    let mut unmatched: Vec<&(usize, usize)> = rows_by_key
+        .values()
+        .filter(|position| !matched.contains(*position))
+        .collect();
+    unmatched.sort();
+    diff.removed = unmatched.len() as u64;
+    for &&(batch_idx, row) in unmatched.iter().take(diff.sample_limit) {
+        diff.sample(RowDifference::Removed {
+            row: key_label(&batches_a[batch_idx], row),
+        });
+    }
+
+    Ok(())
+}
+
+async fn data_diff(
+    file_a: &VortexFile,
+    file_b: &VortexFile,
+    key: Option<&str>,
+    sample_limit: usize,
+) -> Result<DataDiff> {
+    let (Some(fields_a), Some(fields_b)) = (
+        file_a.dtype().as_struct_fields_opt(),
+        file_b.dtype().as_struct_fields_opt(),
+    ) else {
+        anyhow::bail!("Data diff requires files with a struct dtype");
+    };
+
+    // Compare the columns present in both files, in the order of A
+    let columns: Vec<String> = fields_a
+        .names()
+        .iter()
+        .filter(|name| fields_b.field(name.as_ref()).is_some())
+        .map(|name| name.to_string())
+        .collect();
+    if columns.is_empty() {
+        anyhow::bail!("The files have no column in common");
+    }
+    let key = match key {
+        Some(key) => Some(
+            columns
+                .iter()
+                .position(|c| c == key)
+                .ok_or_else(|| anyhow::anyhow!("Key column not found in both files: {}", key))?,
+        ),
+        None => None,
+    };
+
+    let arrow_schema = file_a.dtype().to_arrow_schema()?;
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|name| Ok(arrow_schema.field_with_name(name)?.clone()))
+            .collect::<Result<Vec<_>>>()?,
+    ));
+    let comparator = RowComparator::try_new(schema)?;
+
+    let names: FieldNames = columns
+        .iter()
+        .map(|c| FieldName::from(c.as_str()))
+        .collect();
+    let stream_a = file_a
+        .scan()?
+        .with_projection(select(names.clone(), root()))
+        .into_array_stream()
+        .context("Failed to scan file A")?;
+    let stream_b = file_b
+        .scan()?
+        .with_projection(select(names, root()))
+        .into_array_stream()
+        .context("Failed to scan file B")?;
+    let mut stream_a = std::pin::pin!(stream_a);
+    let mut stream_b = std::pin::pin!(stream_b);
+
+    let mut diff = DataDiff {
+        changed_by_column: vec![0; columns.len()],
+        key: key.map(|idx| columns[idx].clone()),
+        columns,
+        rows_a: file_a.row_count(),
+        rows_b: file_b.row_count(),
+        sample_limit,
+        ..DataDiff::default()
+    };
+
+    match key {
+        Some(key) => {
+            diff_rows_by_key(&comparator, key, &mut stream_a, &mut stream_b, &mut diff).await?
+        }
+        None => diff_rows_in_order(&comparator, &mut stream_a, &mut stream_b, &mut diff).await?,
+    }
+
+    Ok(diff)
+}
+
+fn optional_bytes(bytes: Option<u64>) -> String {
+    bytes.map(format_bytes).unwrap_or_else(|| "-".to_string())
+}
+
+/// Compare two Vortex files by schema, then layout and encodings, then data
+pub async fn run_diff(
+    path_a: &Path,
+    path_b: &Path,
+    level: DiffLevel,
+    key: Option<&str>,
+    sample_limit: usize,
+    sample_chunks: Option<usize>,
+    format: DocumentFormat,
+) -> Result<()> {
+    let file_a = open_vortex_file(path_a).await?;
+    let file_b = open_vortex_file(path_b).await?;
+    let size_a = tokio::fs::metadata(path_a).await?.len();
+    let size_b = tokio::fs::metadata(path_b).await?.len();
+
+    let schema_changes = diff_dtypes(file_a.dtype(), file_b.dtype());
+    let layout = if level >= DiffLevel::Layout {
+        Some(layout_diff(&file_a, &file_b, sample_chunks).await?)
+    } else {
+        None
+    };
+    let data = if level >= DiffLevel::Data {
+        Some(data_diff(&file_a, &file_b, key, sample_limit).await?)
+    } else {
+        None
+    };
+
+    let identical = schema_changes.is_empty() && data.as_ref().is_none_or(DataDiff::is_identical);
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let mut diff_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
+                "a": { "file": path_a.display().to_string(), "row_count": file_a.row_count(), "file_size": size_a },
+                "b": { "file": path_b.display().to_string(), "row_count": file_b.row_count(), "file_size": size_b },
+                "identical": identical,
+                "schema": schema_changes.iter().map(FieldChange::to_json).collect::<Vec<_>>(),
+            });
+
+            if let Some(layout) = &layout {
+                diff_json["layout"] = serde_json::json!({
+                    "columns": layout
+                        .columns
+                        .iter()
+                        .map(|c| serde_json::json!({
+                            "name": c.name,
+                            "status": c.status(),
+                            "layout_a": c.layout_a,
+                            "layout_b": c.layout_b,
+                            "encodings_a": c.encodings_a.as_ref().map(EncodingHistogram::to_json),
+                            "encodings_b": c.encodings_b.as_ref().map(EncodingHistogram::to_json),
+                            "encodings_only_in_a": encodings_only_in(&c.encodings_a, &c.encodings_b),
+                            "encodings_only_in_b": encodings_only_in(&c.encodings_b, &c.encodings_a),
+                            "bytes_a": c.bytes_a,
+                            "bytes_b": c.bytes_b,
+                        }))
+                        .collect::<Vec<_>>(),
+                    "sample_chunks": layout.sample_chunks,
+                });
+            }
+
+            if let Some(data) = &data {
+                diff_json["data"] = serde_json::json!({
+                    "columns": data.columns,
+                    "key": data.key,
+                    "unchanged_rows": data.unchanged,
+                    "changed_rows": data.changed,
+                    "added_rows": data.added,
+                    "removed_rows": data.removed,
+                    "duplicate_keys": data.duplicate_keys,
+                    "changed_values_by_column": data
+                        .columns
+                        .iter()
+                        .zip(&data.changed_by_column)
+                        .filter(|(_, count)| **count > 0)
+                        .map(|(name, count)| serde_json::json!({ "column": name, "rows": count }))
+                        .collect::<Vec<_>>(),
+                    "samples": data.samples.iter().map(RowDifference::to_json).collect::<Vec<_>>(),
+                });
+            }
+
+            print_json(&diff_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex File Diff ===");
+            println!(
+                "A: {} ({} rows, {})",
+                path_a.display(),
+                file_a.row_count(),
+                format_bytes(size_a)
+            );
+            println!(
+                "B: {} ({} rows, {})",
+                path_b.display(),
+                file_b.row_count(),
+                format_bytes(size_b)
+            );
+
+            println!("\n--- Schema ---");
+            if schema_changes.is_empty() {
+                println!("Identical");
+            } else {
+                for change in &schema_changes {
+                    println!("  {}", change);
+                }
+            }
+
+            if let Some(layout) = &layout {
+                println!("\n--- Layout and Encodings ---");
+                println!(
+                    "{:<30} {:<18} {:<18} {:>12} {:>12}  {}",
+                    "Column", "Layout A", "Layout B", "Size A", "Size B", "Change"
+                );
+                println!("{}", "-".repeat(110));
+                for column in &layout.columns {
+                    println!(
+                        "{:<30} {:<18} {:<18} {:>12} {:>12}  {}",
+                        truncate_string(&column.name, 30),
+                        column.layout_a.as_deref().unwrap_or("-"),
+                        column.layout_b.as_deref().unwrap_or("-"),
+                        optional_bytes(column.bytes_a),
+                        optional_bytes(column.bytes_b),
+                        column.status()
+                    );
+                }
+
+                let changed: Vec<&ColumnLayoutDiff> = layout
+                    .columns
+                    .iter()
+                    .filter(|c| c.encodings_changed())
+                    .collect();
+                match layout.sample_chunks {
+                    Some(n) => {
+                        println!("\nTop-level encodings (first {} chunks of each column):", n)
+                    }
+                    None => println!("\nTop-level encodings:"),
+                }
+                if changed.is_empty() {
+                    println!("  Same in every column");
+                }
+                let summary = |histogram: &Option<EncodingHistogram>| {
+                    histogram
+                        .as_ref()
+                        .map(EncodingHistogram::summary)
+                        .unwrap_or_else(|| "-".to_string())
+                };
+                for column in changed {
+                    println!("  {}", column.name);
+                    println!("    A: {}", summary(&column.encodings_a));
+                    println!("    B: {}", summary(&column.encodings_b));
+                }
+            }
+
+            if let Some(data) = &data {
+                println!("\n--- Data ---");
+                match &data.key {
+                    Some(key) => println!("Matching rows by key: {}", key),
+                    None => println!("Matching rows by position"),
+                }
+                println!("Compared columns: {}", data.columns.len());
+                println!("Unchanged rows: {}", data.unchanged);
+                println!("Changed rows: {}", data.changed);
+                println!("Added rows (only in B): {}", data.added);
+                println!("Removed rows (only in A): {}", data.removed);
+                if data.duplicate_keys > 0 {
+                    println!("Duplicate keys skipped: {}", data.duplicate_keys);
+                }
+
+                if data.changed > 0 {
+                    println!("\nChanged rows by column:");
+                    for (name, count) in data.columns.iter().zip(&data.changed_by_column) {
+                        if *count > 0 {
+                            println!("  {:<40} {}", truncate_string(name, 40), count);
+                        }
+                    }
+                }
+
+                if !data.samples.is_empty() {
+                    println!("\nSample of differences:");
+                    for sample in &data.samples {
+                        match sample {
+                            RowDifference::Changed { row, values } => {
+                                println!("  ~ {}", row);
+                                for (column, a, b) in values {
+                                    println!(
+                                        "      {}: {} -> {}",
+                                        column,
+                                        truncate_string(a, 40),
+                                        truncate_string(b, 40)
+                                    );
+                                }
+                            }
+                            RowDifference::Added { row } => println!("  + {}", row),
+                            RowDifference::Removed { row } => println!("  - {}", row),
+                        }
+                    }
+                }
+            }
+
+            println!();
+            if identical {
+                println!("Result: identical");
+            } else {
+                println!("Result: files differ");
+            }
+        }
+    }
+
+    if !identical {
+        anyhow::bail!("Files differ");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field};
+    use vortex_array::arrow::FromArrowArray;
+    use vortex_error::VortexResult;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]))
+    }
+
+    fn batch(ids: &[i64], names: &[&str]) -> ArrayRef {
+        let batch = RecordBatch::try_new(
+            schema(),
+            vec![
+                Arc::new(Int64Array::from(ids.to_vec())),
+                Arc::new(StringArray::from(names.to_vec())),
+            ],
+        )
+        .unwrap();
+        ArrayRef::from_arrow(batch, false)
+    }
+
+    fn stream(batches: Vec<ArrayRef>) -> impl Stream<Item = VortexResult<ArrayRef>> + Unpin {
+        futures::stream::iter(batches.into_iter().map(VortexResult::Ok))
+    }
+
+    fn new_diff(rows_a: u64, rows_b: u64) -> DataDiff {
+        DataDiff {
+            columns: vec!["id".to_string(), "name".to_string()],
+            changed_by_column: vec![0; 2],
+            rows_a,
+            rows_b,
+            sample_limit: 10,
+            ..DataDiff::default()
+        }
+    }
+
+    /// Differences as (unchanged, changed, added, removed, duplicate keys)
+    fn counts(diff: &DataDiff) -> (u64, u64, u64, u64, u64) {
+        (
+            diff.unchanged,
+            diff.changed,
+            diff.added,
+            diff.removed,
+            diff.duplicate_keys,
+        )
+    }
+
+    fn samples(diff: &DataDiff) -> Vec<String> {
+        diff.samples
+            .iter()
+            .map(|sample| match sample {
+                RowDifference::Changed { row, values } => {
+                    let values: Vec<String> = values
+                        .iter()
+                        .map(|(column, a, b)| format!("{}: {} -> {}", column, a, b))
+                        .collect();
+                    format!("~ {} {}", row, values.join(", "))
+                }
+                RowDifference::Added { row } => format!("+ {}", row),
+                RowDifference::Removed { row } => format!("- {}", row),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rows_in_order_across_batch_boundaries() {
+        let comparator = RowComparator::try_new(schema()).unwrap();
+        let mut a = stream(vec![
+            batch(&[0, 1, 2], &["a", "b", "ç"]),
+            batch(&[], &[]),
+            batch(&[3, 4], &["d", "e"]),
+        ]);
+        let mut b = stream(vec![
+            batch(&[0], &["a"]),
+            batch(&[1, 2, 3, 4, 5], &["b", "日本", "d", "e", "f"]),
+        ]);
+
+        let mut diff = new_diff(5, 6);
+        diff_rows_in_order(&comparator, &mut a, &mut b, &mut diff)
+            .await
+            .unwrap();
+
+        assert_eq!(counts(&diff), (4, 1, 1, 0, 0));
+        assert_eq!(diff.changed_by_column, vec![0, 1]);
+        assert_eq!(
+            samples(&diff),
+            vec!["~ row 2 name: ç -> 日本", "+ rows 5..6"]
+        );
+        assert!(!diff.is_identical());
+    }
+
+    #[tokio::test]
+    async fn trailing_rows_of_a_are_removed() {
+        let comparator = RowComparator::try_new(schema()).unwrap();
+        let mut a = stream(vec![
+            batch(&[0, 1], &["a", "b"]),
+            batch(&[2, 3], &["c", "d"]),
+        ]);
+        let mut b = stream(vec![batch(&[0, 1, 2], &["a", "b", "c"])]);
+
+        let mut diff = new_diff(4, 3);
+        diff_rows_in_order(&comparator, &mut a, &mut b, &mut diff)
+            .await
+            .unwrap();
+
+        assert_eq!(counts(&diff), (3, 0, 0, 1, 0));
+        assert_eq!(samples(&diff), vec!["- rows 3..4"]);
+    }
+
+    #[tokio::test]
+    async fn identical_rows_in_different_batches() {
+        let comparator = RowComparator::try_new(schema()).unwrap();
+        let mut a = stream(vec![batch(&[0, 1, 2, 3], &["a", "b", "c", "d"])]);
+        let mut b = stream(vec![
+            batch(&[0], &["a"]),
+            batch(&[1, 2], &["b", "c"]),
+            batch(&[3], &["d"]),
+        ]);
+
+        let mut diff = new_diff(4, 4);
+        diff_rows_in_order(&comparator, &mut a, &mut b, &mut diff)
+            .await
+            .unwrap();
+
+        assert_eq!(counts(&diff), (4, 0, 0, 0, 0));
+        assert!(diff.samples.is_empty());
+        assert!(diff.is_identical());
+    }
+
+    #[tokio::test]
+    async fn rows_by_key() {
+        let comparator = RowComparator::try_new(schema()).unwrap();
+        // Key 3 is duplicated in A and missing from B, key 4 is missing from A
+        // and key 1 is duplicated in B
+        let mut a = stream(vec![
+            batch(&[1, 2], &["a", "b"]),
+            batch(&[3, 3], &["c", "c2"]),
+        ]);
+        let mut b = stream(vec![
+            batch(&[2], &["bé"]),
+            batch(&[4, 1, 1], &["d", "a", "a"]),
+        ]);
+
+        let mut diff = new_diff(4, 4);
+        diff.key = Some("id".to_string());
+        diff_rows_by_key(&comparator, 0, &mut a, &mut b, &mut diff)
+            .await
+            .unwrap();
+
+        assert_eq!(counts(&diff), (1, 1, 1, 1, 2));
+        assert_eq!(diff.changed_by_column, vec![0, 1]);
+        assert_eq!(
+            samples(&diff),
+            vec!["~ id=2 name: b -> bé", "+ id=4", "- id=3"]
+        );
+        assert!(!diff.is_identical());
+    }
+
+    #[tokio::test]
+    async fn samples_are_limited() {
+        let comparator = RowComparator::try_new(schema()).unwrap();
+        let ids: Vec<i64> = (0..20).collect();
+        let mut a = stream(vec![batch(&ids, &["a"; 20])]);
+        let mut b = stream(vec![
+            batch(&ids[..10], &["b"; 10]),
+            batch(&ids[10..], &["b"; 10]),
+        ]);
+
+        let mut diff = new_diff(20, 20);
+        diff.sample_limit = 3;
+        diff_rows_by_key(&comparator, 0, &mut a, &mut b, &mut diff)
+            .await
+            .unwrap();
+
+        assert_eq!(counts(&diff), (0, 20, 0, 0, 0));
+        assert_eq!(diff.samples.len(), 3);
+    }
+}
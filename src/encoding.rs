@@ -0,0 +1,932 @@
+use anyhow::{Context, Result};
+use arrow::array::{RecordBatch, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+use vortex::expr::root;
+use vortex::mask::Mask;
+use vortex_array::arrays::DictArray;
+use vortex_array::{Array, ArrayRef, ArrayVisitor, IntoArray};
+use vortex_file::{SegmentSpec, VortexFile};
+use vortex_layout::{LayoutReader, LayoutRef};
+
+use crate::rows::BatchWriter;
+use crate::size::{collect_segment_ids, format_bytes, segments_bytes};
+use crate::{DocumentFormat, OutputFormat, open_vortex_file, print_json, truncate_string};
+
+/// Helper function to get children arrays
+fn get_array_children(array: &ArrayRef) -> Vec<ArrayRef> {
+    array.children()
+}
+
+/// Get compact description for specific encodings
+fn get_encoding_description(encoding_id: &str, array: &ArrayRef) -> String {
+    match encoding_id {
+        "vortex.zstd" => " [Zstd]".to_string(),
+        "vortex.dict" => {
+            if let Some(dict_array) = array.as_any().downcast_ref::<DictArray>() {
+                format!(
+                    " [Dict: {} values, {} codes]",
+                    dict_array.values().len(),
+                    dict_array.codes().len()
+                )
+            } else {
+                " [Dict]".to_string()
+            }
+        }
+        "vortex.runend" => {
+            if let Some(rle_array) = array.as_any().downcast_ref::<vortex_runend::RunEndArray>() {
+                format!(" [RLE: {} runs]", rle_array.ends().len())
+            } else {
+                " [RLE]".to_string()
+            }
+        }
+        "vortex.sparse" => " [Sparse]".to_string(),
+        "vortex.alp" => " [ALP float compression]".to_string(),
+        "vortex.alprd" => " [ALP-RD]".to_string(),
+        "vortex.pco" => " [PCO quantile compression]".to_string(),
+        "vortex.for" => " [Frame-of-Reference]".to_string(),
+        "fastlanes.bitpacked" => " [Bit-packed]".to_string(),
+        "vortex.delta" => " [Delta]".to_string(),
+        "vortex.fsst" => " [FSST string compression]".to_string(),
+        "vortex.sequence" => " [Sequence]".to_string(),
+        "vortex.constant" => " [Constant]".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// A chunk of a column as stored in the file
+///
+/// Zoned and chunked layouts are walked through, as are the codes of dict layouts,
+/// any other layout (flat, ...) holds the data of one chunk.
+#[derive(Clone)]
+pub struct LayoutChunk {
+    /// First row of the column covered by the chunk
+    pub row_offset: u64,
+    pub rows: u64,
+    /// On-disk length of the segments of the chunk
+    ///
+    /// The values of a dict layout are stored once for all of its chunks and are
+    /// counted with the first one.
+    pub bytes: u64,
+    pub layout: LayoutRef,
+    /// Values of the dict layout whose codes the chunk holds
+    pub dict_values: Option<LayoutRef>,
+}
+
+/// Collect the chunks of a column's layout tree, in row order
+pub fn layout_chunks(layout: &LayoutRef, segment_map: &[SegmentSpec]) -> Vec<LayoutChunk> {
+    let mut chunks = Vec::new();
+    collect_layout_chunks(layout, 0, None, segment_map, &mut chunks);
+    chunks
+}
+
+fn layout_bytes(layout: &LayoutRef, segment_map: &[SegmentSpec]) -> u64 {
+    let mut segment_ids = BTreeSet::new();
+    collect_segment_ids(layout, &mut segment_ids);
+    segments_bytes(&segment_ids, segment_map)
+}
+
+fn collect_layout_chunks(
+    layout: &LayoutRef,
+    row_offset: u64,
+    dict_values: Option<&LayoutRef>,
+    segment_map: &[SegmentSpec],
+    chunks: &mut Vec<LayoutChunk>,
+) {
+    match layout.encoding().to_string().as_str() {
+        // The zones table describes the data child, it holds no rows of the column
+        "vortex.stats" | "vortex.zoned" if layout.nchildren() > 0 => {
+            if let Ok(data) = layout.child(0) {
+                collect_layout_chunks(&data, row_offset, dict_values, segment_map, chunks);
+            }
+        }
+        "vortex.chunked" => {
+            let mut child_offset = row_offset;
+            for idx in 0..layout.nchildren() {
+                if let Ok(child) = layout.child(idx) {
+                    collect_layout_chunks(&child, child_offset, dict_values, segment_map, chunks);
+                    child_offset += child.row_count();
+                }
+            }
+        }
+        // The codes are chunked like any other column and share the values child
+        "vortex.dict" => {
+            let child = |name: &str| {
+                (0..layout.nchildren())
+                    .find(|&idx| layout.child_type(idx).name().to_string() == name)
+                    .and_then(|idx| layout.child(idx).ok())
+            };
+            if let (Some(values), Some(codes)) = (child("values"), child("codes")) {
+                let first = chunks.len();
+                collect_layout_chunks(&codes, row_offset, Some(&values), segment_map, chunks);
+                if let Some(chunk) = chunks.get_mut(first) {
+                    chunk.bytes += layout_bytes(&values, segment_map);
+                }
+            }
+        }
+        _ => {
+            chunks.push(LayoutChunk {
+                row_offset,
+                rows: layout.row_count(),
+                bytes: layout_bytes(layout, segment_map),
+                layout: layout.clone(),
+                dict_values: dict_values.cloned(),
+            });
+        }
+    }
+}
+
+/// Layout of each top-level column, or of the whole file as `<root>` if it is not a struct
+pub fn column_layouts(vortex_file: &VortexFile) -> Vec<(String, LayoutRef)> {
+    let layout = vortex_file.footer().layout();
+    if layout.encoding().to_string() != "vortex.struct" {
+        return vec![("<root>".to_string(), layout.clone())];
+    }
+
+    (0..layout.nchildren())
+        .filter_map(|idx| {
+            let child = layout.child(idx).ok()?;
+            Some((layout.child_type(idx).name().to_string(), child))
+        })
+        .collect()
+}
+
+/// Read the array of a layout chunk as it is encoded in the file, without decompressing it
+///
+/// The codes of a dict layout are read together with its values into a dict array.
+pub async fn read_chunk(vortex_file: &VortexFile, chunk: &LayoutChunk) -> Result<ArrayRef> {
+    let array = read_layout(vortex_file, &chunk.layout, chunk.rows).await?;
+    match &chunk.dict_values {
+        Some(values) => {
+            let values = read_layout(vortex_file, values, values.row_count()).await?;
+            Ok(DictArray::try_new(array, values)?.into_array())
+        }
+        None => Ok(array),
+    }
+}
+
+async fn read_layout(vortex_file: &VortexFile, layout: &LayoutRef, rows: u64) -> Result<ArrayRef> {
+    let reader = layout.new_reader("chunk".into(), vortex_file.segment_source())?;
+    Ok(reader
+        .projection_evaluation(&(0..rows), &root(), Mask::new_true(rows as usize))?
+        .await?)
+}
+
+/// Collect the ids of every encoding used in an array tree
+fn collect_encodings(array: &ArrayRef, encodings: &mut BTreeSet<String>) {
+    encodings.insert(array.encoding_id().to_string());
+    for child in array.children() {
+        collect_encodings(&child, encodings);
+    }
+}
+
+/// Recursively analyzes and displays the encoding tree of an array
+fn analyze_encoding_tree(array: &ArrayRef, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let encoding_id = array.encoding_id();
+
+    // Build compact encoding description
+    let encoding_desc = get_encoding_description(encoding_id.as_ref(), array);
+
+    // Display encoding information in one line
+    println!(
+        "{}└─ {} ({} bytes){}",
+        indent,
+        encoding_id,
+        array.nbytes(),
+        encoding_desc
+    );
+
+    // Check for child arrays (nested encodings)
+    let children = get_array_children(array);
+
+    if !children.is_empty() {
+        for child in children {
+            analyze_encoding_tree(&child, depth + 1);
+        }
+    }
+}
+
+/// Number of chunks and bytes using an encoding
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EncodingCount {
+    pub chunks: usize,
+    pub bytes: u64,
+}
+
+/// Histogram of the encodings used across chunks, keyed by encoding id
+#[derive(Clone, Debug, Default)]
+pub struct EncodingHistogram {
+    pub entries: BTreeMap<String, EncodingCount>,
+}
+
+impl EncodingHistogram {
+    pub fn add(&mut self, encoding: &str, bytes: u64) {
+        let entry = self.entries.entry(encoding.to_string()).or_default();
+        entry.chunks += 1;
+        entry.bytes += bytes;
+    }
+
+    pub fn merge(&mut self, other: &EncodingHistogram) {
+        for (encoding, count) in &other.entries {
+            let entry = self.entries.entry(encoding.clone()).or_default();
+            entry.chunks += count.chunks;
+            entry.bytes += count.bytes;
+        }
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.entries.values().map(|c| c.bytes).sum()
+    }
+
+    /// Entries sorted by number of chunks, most used first
+    pub fn sorted(&self) -> Vec<(&String, &EncodingCount)> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|a, b| b.1.chunks.cmp(&a.1.chunks).then(a.0.cmp(b.0)));
+        entries
+    }
+
+    /// One-line summary such as `vortex.dict x12 (1.20 MiB), vortex.fsst x3 (300 B)`
+    pub fn summary(&self) -> String {
+        self.sorted()
+            .iter()
+            .map(|(encoding, count)| {
+                format!(
+                    "{} x{} ({})",
+                    encoding,
+                    count.chunks,
+                    format_bytes(count.bytes)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Encoding ids without their namespace, most used first, e.g. `dict, fsst`
+    pub fn short_summary(&self) -> String {
+        self.sorted()
+            .iter()
+            .map(|(encoding, _)| encoding.rsplit('.').next().unwrap_or(encoding))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.sorted()
+                .iter()
+                .map(|(encoding, count)| {
+                    serde_json::json!({
+                        "encoding": encoding,
+                        "chunks": count.chunks,
+                        "bytes": count.bytes,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Encodings of a single column aggregated over its layout chunks
+#[derive(Clone, Debug, Default)]
+struct ColumnEncodings {
+    name: String,
+    /// Number of chunks of the column, including those not read
+    chunks: usize,
+    chunks_read: usize,
+    rows_read: u64,
+    /// Top-level encoding of each chunk, with the chunk's on-disk bytes
+    top_level: EncodingHistogram,
+    /// Every encoding appearing in the chunk trees, with the bytes of the chunks using it
+    all: EncodingHistogram,
+    /// Array of the first chunk, kept for the detailed encoding tree
+    first_chunk: Option<ArrayRef>,
+}
+
+impl ColumnEncodings {
+    fn add(&mut self, array: &ArrayRef, chunk: &LayoutChunk) {
+        self.chunks_read += 1;
+        self.rows_read += chunk.rows;
+        self.top_level
+            .add(array.encoding_id().as_ref(), chunk.bytes);
+
+        let mut encodings = BTreeSet::new();
+        collect_encodings(array, &mut encodings);
+        for encoding in encodings {
+            self.all.add(&encoding, chunk.bytes);
+        }
+    }
+}
+
+/// Find columns that use a specific encoding anywhere in their chunks
+fn find_columns_with_encoding(
+    columns: &[ColumnEncodings],
+    target_encoding: &str,
+) -> Vec<(String, EncodingCount)> {
+    columns
+        .iter()
+        .filter_map(|column| {
+            let count = column.all.entries.get(target_encoding)?;
+            Some((column.name.clone(), *count))
+        })
+        .collect()
+}
+
+/// Read the layout chunks of each column one at a time, aggregating their encodings
+///
+/// Chunks are read as encoded in the file and not decompressed. Only the first
+/// `sample_chunks` chunks of each column are read, and the first chunk is kept when
+/// `keep_first` is set.
+async fn scan_encodings(
+    vortex_file: &VortexFile,
+    sample_chunks: Option<usize>,
+    keep_first: bool,
+) -> Result<Vec<ColumnEncodings>> {
+    let segment_map = vortex_file.footer().segment_map();
+    let mut columns = Vec::new();
+
+    for (name, layout) in column_layouts(vortex_file) {
+        let chunks = layout_chunks(&layout, segment_map);
+        let mut column = ColumnEncodings {
+            chunks: chunks.len(),
+            ..Default::default()
+        };
+
+        for chunk in chunks.iter().take(sample_chunks.unwrap_or(usize::MAX)) {
+            let array = read_chunk(vortex_file, chunk).await.context(format!(
+                "Failed to read the chunk at row {} of column {}",
+                chunk.row_offset, name
+            ))?;
+            column.add(&array, chunk);
+            if keep_first && column.first_chunk.is_none() {
+                column.first_chunk = Some(array);
+            }
+        }
+
+        column.name = name;
+        columns.push(column);
+    }
+
+    Ok(columns)
+}
+
+/// Top-level encodings of each column over its first `sample_chunks` layout chunks
+pub async fn column_encodings(
+    vortex_file: &VortexFile,
+    sample_chunks: Option<usize>,
+) -> Result<Vec<(String, EncodingHistogram)>> {
+    Ok(scan_encodings(vortex_file, sample_chunks, false)
+        .await?
+        .into_iter()
+        .map(|column| (column.name, column.top_level))
+        .collect())
+}
+
+fn matching_columns_json(matches: &[(String, EncodingCount)]) -> serde_json::Value {
+    serde_json::Value::Array(
+        matches
+            .iter()
+            .map(|(name, count)| {
+                serde_json::json!({
+                    "name": name,
+                    "chunks": count.chunks,
+                    "bytes": count.bytes,
+                })
+            })
+            .collect(),
+    )
+}
+
+pub async fn show_encoding(
+    path: &Path,
+    format: DocumentFormat,
+    verbose: bool,
+    sample_chunks: Option<usize>,
+    find: Option<&str>,
+) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let dtype = vortex_file.dtype();
+    let root_layout = vortex_file.footer().layout().encoding().to_string();
+    let columns = scan_encodings(&vortex_file, sample_chunks, verbose).await?;
+    let zstd_columns: Vec<String> = find_columns_with_encoding(&columns, "vortex.zstd")
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let columns_json: Vec<_> = columns
+                .iter()
+                .map(|column| {
+                    serde_json::json!({
+                        "name": column.name,
+                        "chunks": column.chunks,
+                        "chunks_read": column.chunks_read,
+                        "rows_read": column.rows_read,
+                        "bytes": column.top_level.total_bytes(),
+                        "encodings": column.top_level.to_json(),
+                        "all_encodings": column.all.to_json(),
+                    })
+                })
+                .collect();
+
+            let mut encoding_info = serde_json::json!({
+                "file": path.display().to_string(),
+                "row_count": vortex_file.row_count(),
+                "root_layout": root_layout,
+                "sample_chunks": sample_chunks,
+                "columns": columns_json,
+                "zstd_compressed_columns": zstd_columns,
+            });
+
+            if let Some(target) = find {
+                let matches = find_columns_with_encoding(&columns, target);
+                encoding_info["find"] = serde_json::json!({
+                    "encoding": target,
+                    "columns": matching_columns_json(&matches),
+                });
+            }
+
+            print_json(&encoding_info, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex File Encoding Inspection ===");
+            println!("File: {}", path.display());
+            println!();
+
+            println!("File Information:");
+            println!("  Rows: {}", vortex_file.row_count());
+            println!("  DType: {}", dtype);
+            println!("  Root Layout: {}", root_layout);
+            if let Some(limit) = sample_chunks {
+                println!("  Chunks read: first {} of each column", limit);
+            }
+            println!();
+
+            println!("Columns ({} total):\n", columns.len());
+            for (idx, column) in columns.iter().enumerate() {
+                let last = idx == columns.len() - 1;
+                let prefix = if last { "└─" } else { "├─" };
+
+                if column.top_level.entries.is_empty() {
+                    println!("{} {}", prefix, column.name);
+                } else {
+                    println!(
+                        "{} {} -> {}",
+                        prefix,
+                        column.name,
+                        column.top_level.summary()
+                    );
+                    let continuation = if last { " " } else { "│" };
+                    println!("{}    uses: {}", continuation, column.all.summary());
+                    if column.chunks_read < column.chunks {
+                        println!(
+                            "{}    read {} of {} chunks",
+                            continuation, column.chunks_read, column.chunks
+                        );
+                    }
+                }
+            }
+
+            if let Some(target) = find {
+                let matches = find_columns_with_encoding(&columns, target);
+
+                println!("\n--- Columns Using {} ---", target);
+                if matches.is_empty() {
+                    println!("No columns use {}", target);
+                } else {
+                    println!("{:<40} {:>8} {:>14}", "Column", "Chunks", "Bytes");
+                    println!("{}", "-".repeat(64));
+                    for (name, count) in &matches {
+                        println!(
+                            "{:<40} {:>8} {:>14}",
+                            truncate_string(name, 40),
+                            count.chunks,
+                            format_bytes(count.bytes)
+                        );
+                    }
+                }
+            } else {
+                println!("\n--- Compression Summary ---");
+                if !zstd_columns.is_empty() {
+                    println!("Zstd compressed columns: {}", zstd_columns.join(", "));
+                } else {
+                    println!("No zstd compressed columns found");
+                }
+            }
+
+            // Show the detailed encoding tree of the first chunk of each column
+            if verbose {
+                println!("\n--- Detailed Encoding Tree (first chunk of each column) ---");
+                for column in &columns {
+                    if let Some(chunk) = &column.first_chunk {
+                        println!("Column [{}]:", column.name);
+                        analyze_encoding_tree(chunk, 1);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Top-level encoding of one layout chunk of a column
+#[derive(Clone, Debug)]
+struct MatrixCell {
+    row_offset: u64,
+    rows: u64,
+    encoding: String,
+    /// On-disk length of the chunk's segments
+    bytes: u64,
+}
+
+impl MatrixCell {
+    /// Encoding id without its namespace, e.g. `dict` for `vortex.dict`
+    fn short_encoding(&self) -> &str {
+        self.encoding.rsplit('.').next().unwrap_or(&self.encoding)
+    }
+}
+
+/// One row of the encoding matrix, the layout chunks of a column
+#[derive(Debug)]
+struct MatrixColumn {
+    name: String,
+    cells: Vec<MatrixCell>,
+}
+
+/// Number of chunks per line of the text grid, wider matrices wrap onto further blocks
+const MATRIX_CHUNKS_PER_LINE: usize = 4;
+
+/// Read the layout chunks of each column, recording the top-level encoding of each one
+async fn scan_matrix(
+    vortex_file: &VortexFile,
+    sample_chunks: Option<usize>,
+) -> Result<Vec<MatrixColumn>> {
+    let segment_map = vortex_file.footer().segment_map();
+    let mut columns = Vec::new();
+
+    for (name, layout) in column_layouts(vortex_file) {
+        let mut cells = Vec::new();
+        for chunk in layout_chunks(&layout, segment_map)
+            .iter()
+            .take(sample_chunks.unwrap_or(usize::MAX))
+        {
+            let array = read_chunk(vortex_file, chunk).await.context(format!(
+                "Failed to read the chunk at row {} of column {}",
+                chunk.row_offset, name
+            ))?;
+            cells.push(MatrixCell {
+                row_offset: chunk.row_offset,
+                rows: chunk.rows,
+                encoding: array.encoding_id().to_string(),
+                bytes: chunk.bytes,
+            });
+        }
+        columns.push(MatrixColumn { name, cells });
+    }
+
+    Ok(columns)
+}
+
+/// Whether every column is split into chunks at the same rows
+fn is_aligned(columns: &[MatrixColumn]) -> bool {
+    let bounds = |column: &MatrixColumn| {
+        column
+            .cells
+            .iter()
+            .map(|cell| (cell.row_offset, cell.rows))
+            .collect::<Vec<_>>()
+    };
+    let mut columns = columns.iter().map(bounds);
+    columns
+        .next()
+        .is_none_or(|first| columns.all(|other| other == first))
+}
+
+/// The matrix as one row per column and chunk, for CSV and NDJSON output
+fn matrix_batch(columns: &[MatrixColumn]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("column", DataType::Utf8, false),
+        Field::new("chunk", DataType::UInt64, false),
+        Field::new("row_offset", DataType::UInt64, false),
+        Field::new("rows", DataType::UInt64, false),
+        Field::new("encoding", DataType::Utf8, false),
+        Field::new("bytes", DataType::UInt64, false),
+    ]);
+
+    let mut names = Vec::new();
+    let mut chunk_indices = Vec::new();
+    let mut row_offsets = Vec::new();
+    let mut rows = Vec::new();
+    let mut encodings = Vec::new();
+    let mut bytes = Vec::new();
+
+    for column in columns {
+        for (chunk_idx, cell) in column.cells.iter().enumerate() {
+            names.push(column.name.clone());
+            chunk_indices.push(chunk_idx as u64);
+            row_offsets.push(cell.row_offset);
+            rows.push(cell.rows);
+            encodings.push(cell.encoding.clone());
+            bytes.push(cell.bytes);
+        }
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(names)),
+            Arc::new(UInt64Array::from(chunk_indices)),
+            Arc::new(UInt64Array::from(row_offsets)),
+            Arc::new(UInt64Array::from(rows)),
+            Arc::new(StringArray::from(encodings)),
+            Arc::new(UInt64Array::from(bytes)),
+        ],
+    )?)
+}
+
+/// Show the top-level encoding of every layout chunk of every column as a grid
+pub async fn show_encoding_matrix(
+    path: &Path,
+    format: OutputFormat,
+    sample_chunks: Option<usize>,
+) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let columns = scan_matrix(&vortex_file, sample_chunks).await?;
+    let aligned = is_aligned(&columns);
+
+    match format {
+        OutputFormat::Json => {
+            let columns_json: Vec<_> = columns
+                .iter()
+                .map(|column| {
+                    serde_json::json!({
+                        "name": column.name,
+                        "chunks": column
+                            .cells
+                            .iter()
+                            .map(|cell| serde_json::json!({
+                                "row_offset": cell.row_offset,
+                                "rows": cell.rows,
+                                "encoding": cell.encoding,
+                                "bytes": cell.bytes,
+                            }))
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+
+            let matrix_json = serde_json::json!({
+                "file": path.display().to_string(),
+                "sample_chunks": sample_chunks,
+                "aligned_chunks": aligned,
+                "columns": columns_json,
+            });
+            print_json(&matrix_json, &DocumentFormat::Json)?;
+        }
+        OutputFormat::Csv | OutputFormat::Ndjson => {
+            let mut writer = BatchWriter::new(&format);
+            writer.write(&matrix_batch(&columns)?)?;
+            writer.finish()?;
+        }
+        OutputFormat::Text => {
+            const CELL_WIDTH: usize = 18;
+
+            let max_chunks = columns.iter().map(|c| c.cells.len()).max().unwrap_or(0);
+
+            println!("=== Vortex Encoding Matrix ===");
+            println!("File: {}", path.display());
+            println!("Chunks per column: up to {}", max_chunks);
+            if !aligned {
+                println!(
+                    "Chunk boundaries differ between columns, use -f csv for the rows of each chunk"
+                );
+            }
+            println!();
+
+            for block_start in (0..max_chunks).step_by(MATRIX_CHUNKS_PER_LINE) {
+                let block = block_start..(block_start + MATRIX_CHUNKS_PER_LINE).min(max_chunks);
+
+                let header: String = block
+                    .clone()
+                    .map(|idx| format!(" {:<CELL_WIDTH$}", format!("chunk {}", idx)))
+                    .collect();
+                println!("{:<30}{}", "Column", header);
+
+                // Rows are shared by all columns only when their chunks are aligned
+                if aligned && let Some(first) = columns.first() {
+                    let rows: String = first.cells[block.clone()]
+                        .iter()
+                        .map(|cell| format!(" {:<CELL_WIDTH$}", format!("{} rows", cell.rows)))
+                        .collect();
+                    println!("{:<30}{}", "", rows);
+                }
+                println!("{}", "-".repeat(30 + block.len() * (CELL_WIDTH + 1)));
+
+                for column in &columns {
+                    let cells: String = block
+                        .clone()
+                        .map(|idx| {
+                            let text = column
+                                .cells
+                                .get(idx)
+                                .map(|cell| {
+                                    format!(
+                                        "{} {}",
+                                        cell.short_encoding(),
+                                        format_bytes(cell.bytes)
+                                    )
+                                })
+                                .unwrap_or_default();
+                            format!(" {:<CELL_WIDTH$}", truncate_string(&text, CELL_WIDTH))
+                        })
+                        .collect();
+                    println!("{:<30}{}", truncate_string(&column.name, 30), cells);
+                }
+                println!();
+            }
+
+            // Flag columns whose encoding changes between chunks
+            let mixed: Vec<&str> = columns
+                .iter()
+                .filter(|column| {
+                    let mut encodings = column.cells.iter().map(|cell| &cell.encoding);
+                    encodings
+                        .next()
+                        .is_some_and(|first| encodings.any(|encoding| encoding != first))
+                })
+                .map(|column| column.name.as_str())
+                .collect();
+
+            if mixed.is_empty() {
+                println!("Every column uses the same encoding in all chunks");
+            } else {
+                println!("Columns with mixed encodings: {}", mixed.join(", "));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::{chunked_write_options, write_vortex_file_with};
+
+    /// 1000 rows in chunks of 100 rows, with a low-cardinality string column
+    async fn write_hosts(path: &Path) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt64, false),
+            Field::new("host", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(UInt64Array::from_iter_values(0..1000)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..1000).map(|i| format!("host-{}", i % 3)),
+                )),
+            ],
+        )
+        .unwrap();
+        write_vortex_file_with(path, vec![batch], &chunked_write_options(100))
+            .await
+            .unwrap();
+    }
+
+    fn column_layout(vortex_file: &VortexFile, name: &str) -> LayoutRef {
+        column_layouts(vortex_file)
+            .into_iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, layout)| layout)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn dict_codes_are_walked_chunk_by_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hosts.vortex");
+        write_hosts(&path).await;
+
+        let vortex_file = open_vortex_file(&path).await.unwrap();
+        let segment_map = vortex_file.footer().segment_map();
+        let chunks = layout_chunks(&column_layout(&vortex_file, "host"), segment_map);
+        assert_eq!(chunks.len(), 10);
+
+        for (idx, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.row_offset, idx as u64 * 100);
+            assert_eq!(chunk.rows, 100);
+            assert!(chunk.dict_values.is_some());
+            assert!(chunk.bytes > 0);
+
+            let array = read_chunk(&vortex_file, chunk).await.unwrap();
+            assert_eq!(array.encoding_id().as_ref(), "vortex.dict");
+            assert_eq!(array.len(), 100);
+        }
+    }
+    #[tokio::test]
+    async fn encodings_are_attributed_per_column_and_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hosts.vortex");
+        write_hosts(&path).await;
+        let vortex_file = open_vortex_file(&path).await.unwrap();
+
+        let columns = scan_encodings(&vortex_file, None, false).await.unwrap();
+        let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "host"]);
+
+        for column in &columns {
+            assert_eq!(column.chunks, 10);
+            assert_eq!(column.chunks_read, 10);
+            assert_eq!(column.rows_read, 1000);
+            let top_level_chunks: usize = column.top_level.entries.values().map(|c| c.chunks).sum();
+            assert_eq!(top_level_chunks, 10);
+        }
+
+        let host = &columns[1];
+        assert_eq!(host.top_level.entries["vortex.dict"].chunks, 10);
+        assert_eq!(host.all.entries["vortex.dict"].chunks, 10);
+        assert_eq!(
+            host.top_level.entries["vortex.dict"].bytes,
+            host.top_level.total_bytes()
+        );
+
+        let matches = find_columns_with_encoding(&columns, "vortex.dict");
+        let host_match = matches.iter().find(|(name, _)| name == "host").unwrap();
+        assert_eq!(host_match.1.chunks, 10);
+        assert!(find_columns_with_encoding(&columns, "vortex.unknown").is_empty());
+
+        // Sampling bounds the chunks read and the counts found
+        let sampled = scan_encodings(&vortex_file, Some(3), false).await.unwrap();
+        assert_eq!(sampled[1].chunks, 10);
+        assert_eq!(sampled[1].chunks_read, 3);
+        assert_eq!(sampled[1].rows_read, 300);
+        let matches = find_columns_with_encoding(&sampled, "vortex.dict");
+        let host_match = matches.iter().find(|(name, _)| name == "host").unwrap();
+        assert_eq!(host_match.1.chunks, 3);
+    }
+    #[tokio::test]
+    async fn matrix_has_a_cell_per_column_and_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hosts.vortex");
+        write_hosts(&path).await;
+        let vortex_file = open_vortex_file(&path).await.unwrap();
+
+        let columns = scan_matrix(&vortex_file, None).await.unwrap();
+        assert_eq!(columns.len(), 2);
+        assert!(is_aligned(&columns));
+
+        let host = &columns[1];
+        assert_eq!(host.name, "host");
+        assert_eq!(host.cells.len(), 10);
+        for (idx, cell) in host.cells.iter().enumerate() {
+            assert_eq!(cell.row_offset, idx as u64 * 100);
+            assert_eq!(cell.rows, 100);
+            assert_eq!(cell.short_encoding(), "dict");
+        }
+
+        let batch = matrix_batch(&columns).unwrap();
+        assert_eq!(batch.num_rows(), 20);
+        let encodings = batch
+            .column(4)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(encodings.value(10), "vortex.dict");
+
+        let sampled = scan_matrix(&vortex_file, Some(2)).await.unwrap();
+        assert!(sampled.iter().all(|column| column.cells.len() == 2));
+    }
+
+    #[test]
+    fn matrix_alignment() {
+        let column = |name: &str, rows: &[u64]| MatrixColumn {
+            name: name.to_string(),
+            cells: rows
+                .iter()
+                .scan(0, |offset, &rows| {
+                    let cell = MatrixCell {
+                        row_offset: *offset,
+                        rows,
+                        encoding: "vortex.flat".to_string(),
+                        bytes: 0,
+                    };
+                    *offset += rows;
+                    Some(cell)
+                })
+                .collect(),
+        };
+
+        assert!(is_aligned(&[]));
+        assert!(is_aligned(&[
+            column("a", &[10, 10]),
+            column("b", &[10, 10])
+        ]));
+        assert!(!is_aligned(&[column("a", &[10, 10]), column("b", &[20])]));
+    }
+}
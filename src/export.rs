@@ -0,0 +1,286 @@
+use anyhow::{Context, Result};
+use arrow::array::{AsArray, RecordBatch};
+use arrow::datatypes::{DataType, SchemaRef};
+use futures::StreamExt;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use vortex_array::arrow::IntoArrowArray;
+use vortex_file::VortexFile;
+
+use crate::open_vortex_file;
+
+/// File format to export a Vortex file to
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Parquet,
+    Arrow,
+    Csv,
+    Ndjson,
+}
+
+/// Writes record batches to the export file one at a time
+enum ExportWriter {
+    Parquet(ArrowWriter<File>),
+    Arrow(arrow::ipc::writer::FileWriter<BufWriter<File>>),
+    Csv(arrow::csv::Writer<BufWriter<File>>),
+    Ndjson(arrow::json::LineDelimitedWriter<BufWriter<File>>),
+}
+
+impl ExportWriter {
+    fn try_new(format: ExportFormat, file: File, schema: &SchemaRef) -> Result<Self> {
+        Ok(match format {
+            ExportFormat::Parquet => {
+                let props = WriterProperties::builder()
+                    .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                    .build();
+                ExportWriter::Parquet(ArrowWriter::try_new(file, schema.clone(), Some(props))?)
+            }
+            ExportFormat::Arrow => ExportWriter::Arrow(arrow::ipc::writer::FileWriter::try_new(
+                BufWriter::new(file),
+                schema,
+            )?),
+            ExportFormat::Csv => ExportWriter::Csv(arrow::csv::Writer::new(BufWriter::new(file))),
+            ExportFormat::Ndjson => {
+                ExportWriter::Ndjson(arrow::json::LineDelimitedWriter::new(BufWriter::new(file)))
+            }
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self {
+            ExportWriter::Parquet(writer) => writer.write(batch)?,
+            ExportWriter::Arrow(writer) => writer.write(batch)?,
+            ExportWriter::Csv(writer) => writer.write(batch)?,
+            ExportWriter::Ndjson(writer) => writer.write(batch)?,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            ExportWriter::Parquet(writer) => {
+                writer.close()?;
+            }
+            ExportWriter::Arrow(mut writer) => writer.finish()?,
+            ExportWriter::Csv(writer) => {
+                writer.into_inner().into_inner()?;
+            }
+            ExportWriter::Ndjson(mut writer) => {
+                writer.finish()?;
+                writer.into_inner().into_inner()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Export a Vortex file, converting one chunk at a time to Arrow
+///
+/// A partially written output file is removed when the export fails.
+pub async fn run_export(path: &Path, format: ExportFormat, output: &Path) -> Result<()> {
+    let start = Instant::now();
+    let vortex_file = open_vortex_file(path).await?;
+
+    if vortex_file.dtype().as_struct_fields_opt().is_none() {
+        anyhow::bail!("Only files with a struct dtype can be exported");
+    }
+
+    let file = File::create(output).context(format!(
+        "Failed to create output file: {}",
+        output.display()
+    ))?;
+
+    let (row_count, chunk_count) = match write_chunks(&vortex_file, format, file).await {
+        Ok(counts) => counts,
+        Err(e) => {
+            let _ = std::fs::remove_file(output);
+            return Err(e.context(format!("Failed to export to {}", output.display())));
+        }
+    };
+
+    let output_size = std::fs::metadata(output)?.len();
+
+    println!("=== Vortex Export ===");
+    println!("Input: {}", path.display());
+    println!("Output: {} ({:?})", output.display(), format);
+    println!("Rows: {}", row_count);
+    println!("Chunks: {}", chunk_count);
+    println!("Output size: {} bytes", output_size);
+    println!("Elapsed: {:.2}s", start.elapsed().as_secs_f64());
+
+    Ok(())
+}
+
+/// Write every chunk of a file to the export file, returning the row and chunk counts
+async fn write_chunks(
+    vortex_file: &VortexFile,
+    format: ExportFormat,
+    file: File,
+) -> Result<(usize, usize)> {
+    let schema = Arc::new(vortex_file.dtype().to_arrow_schema()?);
+    let target_type = DataType::Struct(schema.fields().clone());
+    let mut writer = ExportWriter::try_new(format, file, &schema)?;
+
+    let stream = vortex_file.scan()?.into_array_stream()?;
+    let mut stream = std::pin::pin!(stream);
+    let mut row_count = 0;
+    let mut chunk_count = 0;
+
+    while let Some(chunk) = stream.next().await {
+        let arrow_array = chunk?.into_arrow(&target_type)?;
+        let batch =
+            RecordBatch::try_new(schema.clone(), arrow_array.as_struct().columns().to_vec())?;
+
+        row_count += batch.num_rows();
+        chunk_count += 1;
+        writer.write(&batch)?;
+    }
+
+    writer.finish()?;
+
+    Ok((row_count, chunk_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, ArrayRef, Float64Array, Int64Array, StringArray, StructArray};
+    use arrow::compute::{cast, concat_batches};
+    use arrow::datatypes::{Field, Schema};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use crate::testing::write_vortex_file;
+
+    fn flat_batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("host", DataType::Utf8, true),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..100)),
+                Arc::new(StringArray::from_iter(
+                    (0..100).map(|i| (i % 5 != 0).then(|| format!("host-{}", i % 3))),
+                )),
+                Arc::new(Float64Array::from_iter(
+                    (0..100).map(|i| (i % 7 != 0).then_some(i as f64 * 1.5)),
+                )),
+            ],
+        )
+        .unwrap()
+    }
+
+    /// Assert that exported batches hold the same columns and values as the original
+    fn assert_round_trip(batches: &[RecordBatch], expected: &RecordBatch) {
+        let schema = batches[0].schema();
+        let actual = concat_batches(&schema, batches).unwrap();
+        assert_eq!(actual.num_rows(), expected.num_rows());
+
+        for (field, column) in expected.schema().fields().iter().zip(expected.columns()) {
+            let (index, actual_field) = actual.schema().column_with_name(field.name()).unwrap();
+            assert_eq!(actual_field.is_nullable(), field.is_nullable());
+
+            // Strings may come back as a view type, compare the values
+            let actual_column: ArrayRef = cast(actual.column(index), field.data_type()).unwrap();
+            assert_eq!(&actual_column, column, "column {}", field.name());
+        }
+    }
+
+    async fn export(
+        batch: &RecordBatch,
+        format: ExportFormat,
+        name: &str,
+    ) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("data.vortex");
+        write_vortex_file(&input, vec![batch.clone()])
+            .await
+            .unwrap();
+
+        let output = dir.path().join(name);
+        run_export(&input, format, &output).await.unwrap();
+        (dir, output)
+    }
+
+    #[tokio::test]
+    async fn parquet_round_trip() {
+        let batch = flat_batch();
+        let (_dir, output) = export(&batch, ExportFormat::Parquet, "data.parquet").await;
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&output).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+        assert_round_trip(&batches, &batch);
+    }
+
+    #[tokio::test]
+    async fn arrow_ipc_round_trip() {
+        let batch = flat_batch();
+        let (_dir, output) = export(&batch, ExportFormat::Arrow, "data.arrow").await;
+
+        let reader =
+            arrow::ipc::reader::FileReader::try_new(File::open(&output).unwrap(), None).unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+        assert_round_trip(&batches, &batch);
+    }
+
+    #[tokio::test]
+    async fn csv_rejects_nested_columns_and_removes_the_output() {
+        let point = StructArray::from(vec![
+            (
+                Arc::new(Field::new("x", DataType::Int64, false)),
+                Arc::new(Int64Array::from_iter_values(0..10)) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("y", DataType::Int64, false)),
+                Arc::new(Int64Array::from_iter_values(10..20)) as ArrayRef,
+            ),
+        ]);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("point", point.data_type().clone(), false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..10)),
+                Arc::new(point),
+            ],
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("nested.vortex");
+        write_vortex_file(&input, vec![batch]).await.unwrap();
+
+        let output = dir.path().join("nested.csv");
+        assert!(
+            run_export(&input, ExportFormat::Csv, &output)
+                .await
+                .is_err()
+        );
+        assert!(!output.exists());
+
+        // Formats with nested types still export them
+        let output = dir.path().join("nested.ndjson");
+        run_export(&input, ExportFormat::Ndjson, &output)
+            .await
+            .unwrap();
+        let first = std::fs::read_to_string(&output).unwrap();
+        let first: serde_json::Value = serde_json::from_str(first.lines().next().unwrap()).unwrap();
+        assert_eq!(
+            first,
+            serde_json::json!({ "id": 0, "point": { "x": 0, "y": 10 } })
+        );
+    }
+}
@@ -0,0 +1,635 @@
+use anyhow::{Context, Result};
+use datafusion::sql::sqlparser::ast::{self, BinaryOperator, UnaryOperator, Value};
+use datafusion::sql::sqlparser::dialect::GenericDialect;
+use datafusion::sql::sqlparser::parser::Parser;
+use futures::StreamExt;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::path::Path;
+use vortex::expr::{
+    ExprRef, and, eq, get_item, gt, gt_eq, is_null, lit, lt, lt_eq, not, not_eq, or, root, select,
+};
+use vortex::mask::Mask;
+use vortex_array::Array;
+use vortex_dtype::{DType, FieldName, FieldNames};
+use vortex_file::VortexFile;
+use vortex_layout::LayoutReader;
+use vortex_scalar::Scalar;
+
+use crate::encoding::{column_layouts, layout_chunks};
+use crate::rows::{BatchWriter, to_record_batch};
+use crate::{DocumentFormat, OutputFormat, open_vortex_file, print_json};
+
+/// A top-level conjunct of a predicate, with its source text
+#[derive(Clone, Debug)]
+pub struct Conjunct {
+    pub text: String,
+    pub expr: ExprRef,
+}
+
+/// A parsed `--where` predicate
+#[derive(Clone, Debug)]
+pub struct Predicate {
+    pub expr: ExprRef,
+    pub conjuncts: Vec<Conjunct>,
+    /// Top-level columns the predicate reads
+    pub columns: BTreeSet<String>,
+}
+
+/// Translates a SQL expression into a Vortex expression over the columns of a file
+struct PredicateBuilder<'a> {
+    dtype: &'a DType,
+    /// Top-level columns referenced so far
+    columns: RefCell<BTreeSet<String>>,
+}
+
+impl PredicateBuilder<'_> {
+    /// Dtype of a column reference, if the expression is one
+    fn column_dtype(&self, expr: &ast::Expr) -> Option<DType> {
+        let mut dtype = self.dtype.clone();
+        for ident in column_path(expr)? {
+            dtype = dtype.as_struct_fields_opt()?.field(ident.as_str())?;
+        }
+        Some(dtype)
+    }
+
+    fn expr(&self, expr: &ast::Expr) -> Result<ExprRef> {
+        Ok(match expr {
+            ast::Expr::Nested(inner) => self.expr(inner)?,
+            ast::Expr::BinaryOp { left, op, right } => match op {
+                BinaryOperator::And => and(self.expr(left)?, self.expr(right)?),
+                BinaryOperator::Or => or(self.expr(left)?, self.expr(right)?),
+                _ => self.comparison(left, op, right)?,
+            },
+            ast::Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => not(self.expr(expr)?),
+            ast::Expr::IsNull(inner) => is_null(self.operand(inner, None)?),
+            ast::Expr::IsNotNull(inner) => not(is_null(self.operand(inner, None)?)),
+            ast::Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let between = and(
+                    self.comparison(expr, &BinaryOperator::GtEq, low)?,
+                    self.comparison(expr, &BinaryOperator::LtEq, high)?,
+                );
+                if *negated { not(between) } else { between }
+            }
+            ast::Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let mut items = list
+                    .iter()
+                    .map(|item| self.comparison(expr, &BinaryOperator::Eq, item));
+                let first = items
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("IN list must not be empty"))??;
+                let any = items.try_fold(first, |acc, item| item.map(|item| or(acc, item)))?;
+                if *negated { not(any) } else { any }
+            }
+            ast::Expr::Identifier(_) | ast::Expr::CompoundIdentifier(_) => {
+                // A bare boolean column
+                if let Some(dtype) = self.column_dtype(expr)
+                    && !matches!(dtype, DType::Bool(_))
+                {
+                    anyhow::bail!("Column {} is not boolean: {}", expr, dtype);
+                }
+                self.operand(expr, None)?
+            }
+            ast::Expr::Value(_) => {
+                anyhow::bail!(
+                    "Predicate must compare a column, not a bare literal: {}",
+                    expr
+                )
+            }
+            _ => anyhow::bail!("Unsupported expression in predicate: {}", expr),
+        })
+    }
+
+    fn comparison(
+        &self,
+        left: &ast::Expr,
+        op: &BinaryOperator,
+        right: &ast::Expr,
+    ) -> Result<ExprRef> {
+        // Literals take the dtype of the column they are compared with
+        let left_dtype = self.column_dtype(left);
+        let right_dtype = self.column_dtype(right);
+        let left = self.operand(left, right_dtype.as_ref())?;
+        let right = self.operand(right, left_dtype.as_ref())?;
+
+        Ok(match op {
+            BinaryOperator::Eq => eq(left, right),
+            BinaryOperator::NotEq => not_eq(left, right),
+            BinaryOperator::Gt => gt(left, right),
+            BinaryOperator::GtEq => gt_eq(left, right),
+            BinaryOperator::Lt => lt(left, right),
+            BinaryOperator::LtEq => lt_eq(left, right),
+            _ => anyhow::bail!("Unsupported operator in predicate: {}", op),
+        })
+    }
+
+    /// A column reference or a literal, cast to `target` when given
+    fn operand(&self, expr: &ast::Expr, target: Option<&DType>) -> Result<ExprRef> {
+        if let Some(path) = column_path(expr) {
+            if self.column_dtype(expr).is_none() {
+                anyhow::bail!("Column not found: {}", path.join("."));
+            }
+            self.columns.borrow_mut().insert(path[0].clone());
+            return Ok(path
+                .into_iter()
+                .fold(root(), |child, name| get_item(name, child)));
+        }
+
+        match expr {
+            ast::Expr::Nested(inner) => self.operand(inner, target),
+            ast::Expr::Value(value) => Ok(lit(literal(&value.value, false, target)?)),
+            ast::Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            } => match expr.as_ref() {
+                ast::Expr::Value(value) => Ok(lit(literal(&value.value, true, target)?)),
+                _ => anyhow::bail!("Unsupported expression in predicate: -{}", expr),
+            },
+            _ => anyhow::bail!("Unsupported operand in predicate: {}", expr),
+        }
+    }
+}
+
+/// Field names of a (possibly nested) column reference
+fn column_path(expr: &ast::Expr) -> Option<Vec<String>> {
+    match expr {
+        ast::Expr::Identifier(ident) => Some(vec![ident.value.clone()]),
+        ast::Expr::CompoundIdentifier(idents) => {
+            Some(idents.iter().map(|i| i.value.clone()).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Convert a SQL literal into a scalar of the target dtype
+fn literal(value: &Value, negative: bool, target: Option<&DType>) -> Result<Scalar> {
+    let scalar = match value {
+        Value::Number(number, _) => {
+            let number = if negative {
+                format!("-{}", number)
+            } else {
+                number.clone()
+            };
+            let is_float = target.is_some_and(
+                |dtype| matches!(dtype, DType::Primitive(ptype, _) if ptype.is_float()),
+            );
+
+            if !is_float && let Ok(value) = number.parse::<i64>() {
+                Scalar::from(value)
+            } else {
+                Scalar::from(
+                    number
+                        .parse::<f64>()
+                        .context(format!("Invalid number: {}", number))?,
+                )
+            }
+        }
+        Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) if !negative => {
+            Scalar::from(s.as_str())
+        }
+        Value::Boolean(b) if !negative => Scalar::from(*b),
+        Value::Null if !negative => {
+            let dtype = target
+                .ok_or_else(|| anyhow::anyhow!("NULL literals must be compared with a column"))?;
+            return Ok(Scalar::null(dtype.as_nullable()));
+        }
+        _ => anyhow::bail!("Unsupported literal in predicate: {}", value),
+    };
+
+    match target {
+        Some(dtype) => scalar
+            .cast(dtype)
+            .context(format!("Cannot compare {} with a {} column", value, dtype)),
+        None => Ok(scalar),
+    }
+}
+
+/// Split a SQL expression on its top-level `AND`s
+fn split_conjuncts<'e>(expr: &'e ast::Expr, conjuncts: &mut Vec<&'e ast::Expr>) {
+    match expr {
+        ast::Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_conjuncts(left, conjuncts);
+            split_conjuncts(right, conjuncts);
+        }
+        ast::Expr::Nested(inner)
+            if matches!(
+                inner.as_ref(),
+                ast::Expr::BinaryOp {
+                    op: BinaryOperator::And,
+                    ..
+                }
+            ) =>
+        {
+            split_conjuncts(inner, conjuncts)
+        }
+        _ => conjuncts.push(expr),
+    }
+}
+
+/// Parse a SQL predicate such as `col > 10 AND host = 'a'` against a file dtype
+pub fn parse_predicate(sql: &str, dtype: &DType) -> Result<Predicate> {
+    let parsed = Parser::new(&GenericDialect {})
+        .try_with_sql(sql)
+        .and_then(|mut parser| parser.parse_expr())
+        .context(format!("Invalid predicate: {}", sql))?;
+
+    let builder = PredicateBuilder {
+        dtype,
+        columns: RefCell::new(BTreeSet::new()),
+    };
+    let mut conjunct_exprs = Vec::new();
+    split_conjuncts(&parsed, &mut conjunct_exprs);
+
+    let conjuncts = conjunct_exprs
+        .into_iter()
+        .map(|expr| {
+            Ok(Conjunct {
+                text: expr.to_string(),
+                expr: builder.expr(expr)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let expr = conjuncts
+        .iter()
+        .map(|conjunct| conjunct.expr.clone())
+        .reduce(and)
+        .expect("a predicate has at least one conjunct");
+
+    Ok(Predicate {
+        expr,
+        conjuncts,
+        columns: builder.columns.into_inner(),
+    })
+}
+
+/// Print the rows of a file matching a predicate, pushing the filter into the scan
+pub async fn run_filter(
+    path: &Path,
+    predicate: &str,
+    columns: &[String],
+    format: OutputFormat,
+) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let predicate = parse_predicate(predicate, vortex_file.dtype())?;
+
+    let mut scan = vortex_file.scan()?.with_filter(predicate.expr);
+    if !columns.is_empty() {
+        let fields = vortex_file
+            .dtype()
+            .as_struct_fields_opt()
+            .ok_or_else(|| anyhow::anyhow!("Column projection requires a struct file"))?;
+        if let Some(column) = columns
+            .iter()
+            .find(|column| fields.field(column.as_str()).is_none())
+        {
+            anyhow::bail!("Column not found: {}", column);
+        }
+
+        let names: FieldNames = columns
+            .iter()
+            .map(|c| FieldName::from(c.as_str()))
+            .collect();
+        scan = scan.with_projection(select(names, root()));
+    }
+
+    let stream = scan
+        .into_array_stream()
+        .context("Failed to scan Vortex file")?;
+    let mut stream = std::pin::pin!(stream);
+    let mut writer = BatchWriter::new(&format);
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if chunk.is_empty() {
+            continue;
+        }
+        writer.write(&to_record_batch(chunk)?)?;
+    }
+
+    writer.finish()
+}
+
+/// Row ranges of the chunks of the predicate columns
+///
+/// Columns chunked differently are split at each other's chunk boundaries, so that every range
+/// lies within a single chunk of each predicate column.
+fn chunk_ranges(vortex_file: &VortexFile, columns: &BTreeSet<String>) -> Vec<Range<u64>> {
+    let segment_map = vortex_file.footer().segment_map();
+    let mut boundaries = BTreeSet::from([0, vortex_file.row_count()]);
+    for (name, layout) in column_layouts(vortex_file) {
+        if !columns.contains(&name) && name != "<root>" {
+            continue;
+        }
+        for chunk in layout_chunks(&layout, segment_map) {
+            boundaries.insert(chunk.row_offset);
+            boundaries.insert(chunk.row_offset + chunk.rows);
+        }
+    }
+
+    boundaries
+        .iter()
+        .zip(boundaries.iter().skip(1))
+        .map(|(start, end)| *start..*end)
+        .collect()
+}
+
+/// Pruning outcome of a single chunk
+#[derive(Debug)]
+struct ChunkPruning {
+    rows: Range<u64>,
+    pruned: bool,
+    /// Conjuncts whose statistics alone rule out the chunk
+    pruned_by: Vec<String>,
+    /// Rows matching the predicate, for chunks that were not pruned
+    matched_rows: Option<u64>,
+}
+
+/// Whether the statistics of a row range rule out every row for an expression
+async fn pruned_by_stats(
+    reader: &dyn LayoutReader,
+    rows: &Range<u64>,
+    expr: &ExprRef,
+) -> Result<bool> {
+    let len = (rows.end - rows.start) as usize;
+    let mask = reader
+        .pruning_evaluation(rows, expr, Mask::new_true(len))?
+        .await?;
+    Ok(mask.all_false())
+}
+
+/// Count the rows of a range matching a predicate, reading only the predicate columns
+async fn count_matching_rows(
+    vortex_file: &VortexFile,
+    rows: &Range<u64>,
+    predicate: &Predicate,
+) -> Result<u64> {
+    let mut scan = vortex_file
+        .scan()?
+        .with_row_range(rows.clone())
+        .with_filter(predicate.expr.clone());
+    if vortex_file.dtype().as_struct_fields_opt().is_some() {
+        let names: FieldNames = predicate
+            .columns
+            .iter()
+            .map(|c| FieldName::from(c.as_str()))
+            .collect();
+        scan = scan.with_projection(select(names, root()));
+    }
+
+    let stream = scan.into_array_stream()?;
+    let mut stream = std::pin::pin!(stream);
+
+    let mut matched = 0;
+    while let Some(chunk) = stream.next().await {
+        matched += chunk?.len() as u64;
+    }
+    Ok(matched)
+}
+
+/// Report, for each chunk, whether the predicate is pruned by the file's statistics
+pub async fn explain_prune(path: &Path, predicate: &str, format: DocumentFormat) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let predicate = parse_predicate(predicate, vortex_file.dtype())?;
+    let ranges = chunk_ranges(&vortex_file, &predicate.columns);
+    let reader = vortex_file.layout_reader()?;
+
+    let mut chunks = Vec::with_capacity(ranges.len());
+    for rows in ranges {
+        let pruned = pruned_by_stats(reader.as_ref(), &rows, &predicate.expr).await?;
+
+        let mut pruned_by = Vec::new();
+        if pruned {
+            for conjunct in &predicate.conjuncts {
+                if pruned_by_stats(reader.as_ref(), &rows, &conjunct.expr).await? {
+                    pruned_by.push(conjunct.text.clone());
+                }
+            }
+        }
+
+        let matched_rows = if pruned {
+            None
+        } else {
+            Some(count_matching_rows(&vortex_file, &rows, &predicate).await?)
+        };
+
+        chunks.push(ChunkPruning {
+            rows,
+            pruned,
+            pruned_by,
+            matched_rows,
+        });
+    }
+
+    let pruned_count = chunks.iter().filter(|c| c.pruned).count();
+    let wasted_count = chunks.iter().filter(|c| c.matched_rows == Some(0)).count();
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let explain_json = serde_json::json!({
+                "file": path.display().to_string(),
+                "predicate": predicate.conjuncts.iter().map(|c| c.text.clone()).collect::<Vec<_>>().join(" AND "),
+                "chunks_total": chunks.len(),
+                "chunks_pruned": pruned_count,
+                "chunks_scanned_without_match": wasted_count,
+                "chunks": chunks
+                    .iter()
+                    .map(|c| serde_json::json!({
+                        "row_start": c.rows.start,
+                        "row_end": c.rows.end,
+                        "pruned": c.pruned,
+                        "pruned_by": c.pruned_by,
+                        "matched_rows": c.matched_rows,
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            print_json(&explain_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex Pruning Report ===");
+            println!("File: {}", path.display());
+            println!("Predicate:");
+            for conjunct in &predicate.conjuncts {
+                println!("  {}", conjunct.text);
+            }
+            println!();
+
+            println!(
+                "{:>6} {:>24} {:<8} {:>10}  {}",
+                "Chunk", "Rows", "Status", "Matched", "Reason"
+            );
+            println!("{}", "-".repeat(90));
+
+            for (idx, chunk) in chunks.iter().enumerate() {
+                let (status, reason) = if chunk.pruned {
+                    let reason = if chunk.pruned_by.is_empty() {
+                        "statistics rule out the combined predicate".to_string()
+                    } else {
+                        format!("statistics rule out {}", chunk.pruned_by.join(", "))
+                    };
+                    ("pruned", reason)
+                } else if chunk.matched_rows == Some(0) {
+                    (
+                        "scanned",
+                        "statistics could not rule out the chunk, no rows matched".to_string(),
+                    )
+                } else {
+                    ("scanned", String::new())
+                };
+
+                println!(
+                    "{:>6} {:>24} {:<8} {:>10}  {}",
+                    idx,
+                    format!("{}..{}", chunk.rows.start, chunk.rows.end),
+                    status,
+                    chunk
+                        .matched_rows
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    reason
+                );
+            }
+
+            println!();
+            println!(
+                "Pruned {} of {} chunks; {} scanned chunks had no matching rows",
+                pruned_count,
+                chunks.len(),
+                wasted_count
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vortex_dtype::{FieldName, FieldNames, PType, StructFields};
+
+    fn file_dtype() -> DType {
+        let fields = [
+            ("id", DType::Primitive(PType::I64, false.into())),
+            ("host", DType::Utf8(false.into())),
+            ("value", DType::Primitive(PType::F64, true.into())),
+            ("ok", DType::Bool(false.into())),
+        ];
+        let (names, dtypes): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+        DType::Struct(
+            StructFields::new(
+                names
+                    .into_iter()
+                    .map(FieldName::from)
+                    .collect::<FieldNames>(),
+                dtypes,
+            ),
+            false.into(),
+        )
+    }
+
+    fn column(name: &str) -> ExprRef {
+        get_item(name, root())
+    }
+
+    fn parse_err(sql: &str) -> String {
+        parse_predicate(sql, &file_dtype()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn conjuncts_fold_into_the_predicate() {
+        let predicate = parse_predicate(
+            "id > 10 AND (host = 'a' OR value IS NULL) AND ok",
+            &file_dtype(),
+        )
+        .unwrap();
+
+        let texts: Vec<&str> = predicate
+            .conjuncts
+            .iter()
+            .map(|c| c.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["id > 10", "(host = 'a' OR value IS NULL)", "ok"]
+        );
+        assert_eq!(
+            predicate.columns,
+            BTreeSet::from(["host", "id", "ok", "value"].map(String::from))
+        );
+
+        let expected = and(
+            and(
+                gt(column("id"), lit(10i64)),
+                or(eq(column("host"), lit("a")), is_null(column("value"))),
+            ),
+            column("ok"),
+        );
+        assert_eq!(format!("{:?}", predicate.expr), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn nested_ands_are_split() {
+        let predicate =
+            parse_predicate("(id > 1 AND id < 5) AND host <> 'b'", &file_dtype()).unwrap();
+        assert_eq!(predicate.conjuncts.len(), 3);
+        assert_eq!(predicate.conjuncts[2].text, "host <> 'b'");
+    }
+
+    #[test]
+    fn literals_take_the_column_dtype() {
+        let predicate = parse_predicate("host = 'it''s' AND value > 1", &file_dtype()).unwrap();
+        let expected = and(
+            eq(column("host"), lit("it's")),
+            gt(
+                column("value"),
+                lit(Scalar::from(1.0f64)
+                    .cast(&DType::Primitive(PType::F64, true.into()))
+                    .unwrap()),
+            ),
+        );
+        assert_eq!(format!("{:?}", predicate.expr), format!("{:?}", expected));
+
+        assert!(parse_err("id = 'abc'").contains("Cannot compare"));
+    }
+
+    #[test]
+    fn null_checks() {
+        let predicate =
+            parse_predicate("value IS NULL OR host IS NOT NULL", &file_dtype()).unwrap();
+        assert_eq!(predicate.conjuncts.len(), 1);
+        assert_eq!(
+            format!("{:?}", predicate.expr),
+            format!(
+                "{:?}",
+                or(is_null(column("value")), not(is_null(column("host"))))
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_predicates_are_rejected() {
+        assert!(parse_err("missing > 1").contains("Column not found: missing"));
+        assert!(parse_err("id > 1 AND missing.x = 2").contains("Column not found: missing.x"));
+        assert!(parse_err("true").contains("bare literal"));
+        assert!(parse_err("1 AND id > 1").contains("bare literal"));
+        assert!(parse_err("host").contains("not boolean"));
+        assert!(parse_err("id >").contains("Invalid predicate"));
+    }
+}
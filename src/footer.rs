@@ -0,0 +1,770 @@
+use flatbuffers::{InvalidFlatbuffer, VerifierOptions};
+use std::fmt;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use vortex_flatbuffers::dtype as fb_dtype;
+use vortex_flatbuffers::footer as fb_footer;
+use vortex_flatbuffers::layout as fb_layout;
+
+use crate::{DocumentFormat, print_json};
+
+/// Size of the end-of-file marker: version, postscript length and magic
+pub const EOF_SIZE: u64 = 8;
+
+/// Magic bytes at the very end of every Vortex file
+pub const MAGIC: &[u8; 4] = b"VTXF";
+
+/// File format version understood by this tool
+pub const SUPPORTED_VERSION: u16 = 1;
+
+/// Errors reading the EOF marker, postscript and footer of a file
+#[derive(Debug)]
+pub enum FooterError {
+    Io(std::io::Error),
+    /// The file is shorter than the EOF marker
+    TooSmall {
+        file_size: u64,
+    },
+    /// The last four bytes are not the Vortex magic
+    BadMagic {
+        found: [u8; 4],
+    },
+    UnsupportedVersion {
+        version: u16,
+    },
+    /// The postscript is missing, empty or lacks a required segment
+    BadPostscript {
+        reason: String,
+    },
+    /// A flatbuffer referenced by the postscript lies outside the file
+    OutOfRange {
+        segment: &'static str,
+        offset: u64,
+        end: u64,
+        limit: u64,
+    },
+    /// A flatbuffer failed verification
+    Verification {
+        flatbuffer: &'static str,
+        source: InvalidFlatbuffer,
+    },
+}
+
+impl fmt::Display for FooterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FooterError::Io(e) => write!(f, "Failed to read file: {}", e),
+            FooterError::TooSmall { file_size } => write!(
+                f,
+                "File too small to be a valid Vortex file ({} bytes)",
+                file_size
+            ),
+            FooterError::BadMagic { found } => write!(
+                f,
+                "Invalid magic bytes {:?}, not a Vortex file",
+                String::from_utf8_lossy(found)
+            ),
+            FooterError::UnsupportedVersion { version } => write!(
+                f,
+                "Unsupported file format version {} (supported: {})",
+                version, SUPPORTED_VERSION
+            ),
+            FooterError::BadPostscript { reason } => write!(f, "Invalid postscript: {}", reason),
+            FooterError::OutOfRange {
+                segment,
+                offset,
+                end,
+                limit,
+            } => write!(
+                f,
+                "The {} flatbuffer ({}..{}) extends beyond the data region ending at {}",
+                segment, offset, end, limit
+            ),
+            FooterError::Verification { flatbuffer, source } => {
+                write!(
+                    f,
+                    "The {} flatbuffer failed verification: {}",
+                    flatbuffer, source
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FooterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FooterError::Io(e) => Some(e),
+            FooterError::Verification { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FooterError {
+    fn from(e: std::io::Error) -> Self {
+        FooterError::Io(e)
+    }
+}
+
+/// Limits applied by the flatbuffer verifier when parsing untrusted files
+#[derive(clap::Args, Clone, Copy, Debug)]
+pub struct VerifierLimits {
+    /// Maximum nesting depth of flatbuffer tables
+    #[arg(long, default_value = "64")]
+    pub max_flatbuffer_depth: usize,
+
+    /// Maximum number of flatbuffer tables in a single flatbuffer
+    #[arg(long, default_value = "1000000")]
+    pub max_flatbuffer_tables: usize,
+}
+
+impl Default for VerifierLimits {
+    fn default() -> Self {
+        VerifierLimits {
+            max_flatbuffer_depth: 64,
+            max_flatbuffer_tables: 1_000_000,
+        }
+    }
+}
+
+impl VerifierLimits {
+    fn options(&self) -> VerifierOptions {
+        VerifierOptions {
+            max_depth: self.max_flatbuffer_depth,
+            max_tables: self.max_flatbuffer_tables,
+            ..Default::default()
+        }
+    }
+}
+
+/// The fixed-size marker at the end of the file
+#[derive(Clone, Copy, Debug)]
+pub struct Eof {
+    pub version: u16,
+    pub postscript_size: u16,
+}
+
+/// Location of a flatbuffer referenced by the postscript
+#[derive(Clone, Debug)]
+pub struct PostscriptSegment {
+    pub name: &'static str,
+    pub offset: u64,
+    pub length: u32,
+    pub alignment_exponent: u8,
+}
+
+impl PostscriptSegment {
+    /// End offset of the flatbuffer, an error if it overflows
+    pub fn end(&self) -> Result<u64, FooterError> {
+        self.offset
+            .checked_add(self.length as u64)
+            .ok_or(FooterError::OutOfRange {
+                segment: self.name,
+                offset: self.offset,
+                end: u64::MAX,
+                limit: u64::MAX,
+            })
+    }
+}
+
+/// The EOF marker, postscript and footer of a file, parsed with flatbuffer verification
+#[derive(Debug)]
+pub struct RawFooter {
+    pub file_size: u64,
+    pub eof: Eof,
+    pub postscript_offset: u64,
+    pub segments: Vec<PostscriptSegment>,
+    footer_bytes: Vec<u8>,
+    limits: VerifierLimits,
+}
+
+impl RawFooter {
+    /// The footer flatbuffer, verified again with the limits it was read with
+    pub fn footer(&self) -> Result<fb_footer::Footer<'_>, FooterError> {
+        verified::<fb_footer::Footer>("footer", &self.footer_bytes, &self.limits)
+    }
+}
+
+fn verified<'a, T: flatbuffers::Follow<'a> + flatbuffers::Verifiable + 'a>(
+    flatbuffer: &'static str,
+    bytes: &'a [u8],
+    limits: &VerifierLimits,
+) -> Result<T::Inner, FooterError> {
+    flatbuffers::root_with_opts::<T>(&limits.options(), bytes)
+        .map_err(|source| FooterError::Verification { flatbuffer, source })
+}
+
+async fn read_range(file: &mut File, offset: u64, length: usize) -> Result<Vec<u8>, FooterError> {
+    file.seek(std::io::SeekFrom::Start(offset)).await?;
+    let mut bytes = vec![0u8; length];
+    file.read_exact(&mut bytes).await?;
+    Ok(bytes)
+}
+
+/// Read the EOF marker, postscript and footer of a Vortex file
+///
+/// Every flatbuffer is checked by the flatbuffers verifier before it is accessed, so
+/// truncated or corrupted files produce an error instead of undefined behaviour.
+pub async fn read_raw_footer(
+    path: &Path,
+    limits: &VerifierLimits,
+) -> Result<RawFooter, FooterError> {
+    let mut file = File::open(path).await?;
+    let file_size = file.metadata().await?.len();
+
+    if file_size < EOF_SIZE {
+        return Err(FooterError::TooSmall { file_size });
+    }
+
+    // Format: [version: 2 bytes][postscript_len: 2 bytes][magic "VTXF": 4 bytes]
+    let eof_bytes = read_range(&mut file, file_size - EOF_SIZE, EOF_SIZE as usize).await?;
+    if &eof_bytes[4..8] != MAGIC {
+        return Err(FooterError::BadMagic {
+            found: [eof_bytes[4], eof_bytes[5], eof_bytes[6], eof_bytes[7]],
+        });
+    }
+
+    let eof = Eof {
+        version: u16::from_le_bytes([eof_bytes[0], eof_bytes[1]]),
+        postscript_size: u16::from_le_bytes([eof_bytes[2], eof_bytes[3]]),
+    };
+    if eof.version != SUPPORTED_VERSION {
+        return Err(FooterError::UnsupportedVersion {
+            version: eof.version,
+        });
+    }
+    if eof.postscript_size == 0 {
+        return Err(FooterError::BadPostscript {
+            reason: "postscript size is 0".to_string(),
+        });
+    }
+    if eof.postscript_size as u64 + EOF_SIZE > file_size {
+        return Err(FooterError::BadPostscript {
+            reason: format!(
+                "postscript size {} exceeds file size {}",
+                eof.postscript_size, file_size
+            ),
+        });
+    }
+
+    let postscript_offset = file_size - EOF_SIZE - eof.postscript_size as u64;
+    let postscript_bytes =
+        read_range(&mut file, postscript_offset, eof.postscript_size as usize).await?;
+    let postscript = verified::<fb_footer::Postscript>("postscript", &postscript_bytes, limits)?;
+
+    let segments: Vec<PostscriptSegment> = [
+        ("dtype", postscript.dtype()),
+        ("layout", postscript.layout()),
+        ("statistics", postscript.statistics()),
+        ("footer", postscript.footer()),
+    ]
+    .into_iter()
+    .filter_map(|(name, segment)| {
+        segment.map(|segment| PostscriptSegment {
+            name,
+            offset: segment.offset(),
+            length: segment.length(),
+            alignment_exponent: segment.alignment_exponent(),
+        })
+    })
+    .collect();
+
+    for segment in &segments {
+        let end = segment.end()?;
+        if end > postscript_offset {
+            return Err(FooterError::OutOfRange {
+                segment: segment.name,
+                offset: segment.offset,
+                end,
+                limit: postscript_offset,
+            });
+        }
+    }
+
+    let footer_segment = segments
+        .iter()
+        .find(|s| s.name == "footer")
+        .ok_or_else(|| FooterError::BadPostscript {
+            reason: "missing footer segment".to_string(),
+        })?;
+    if footer_segment.length == 0 {
+        return Err(FooterError::BadPostscript {
+            reason: "footer length is 0".to_string(),
+        });
+    }
+
+    let footer_bytes = read_range(
+        &mut file,
+        footer_segment.offset,
+        footer_segment.length as usize,
+    )
+    .await?;
+    verified::<fb_footer::Footer>("footer", &footer_bytes, limits)?;
+
+    if let Some(segment) = segments.iter().find(|s| s.name == "dtype") {
+        let bytes = read_range(&mut file, segment.offset, segment.length as usize).await?;
+        verified::<fb_dtype::DType>("dtype", &bytes, limits)?;
+    }
+    if let Some(segment) = segments.iter().find(|s| s.name == "layout") {
+        let bytes = read_range(&mut file, segment.offset, segment.length as usize).await?;
+        verified::<fb_layout::Layout>("layout", &bytes, limits)?;
+    }
+
+    Ok(RawFooter {
+        file_size,
+        eof,
+        postscript_offset,
+        segments,
+        footer_bytes,
+        limits: *limits,
+    })
+}
+
+/// Distinct array and layout encoding ids declared in the footer
+pub fn footer_encodings(raw: &RawFooter) -> Result<(Vec<String>, Vec<String>), FooterError> {
+    let footer = raw.footer()?;
+
+    // Extract array encodings
+    let mut array_encodings = Vec::new();
+    if let Some(array_specs) = footer.array_specs() {
+        for spec in array_specs.iter() {
+            let encoding_id = spec.id();
+            if !encoding_id.is_empty() && !array_encodings.contains(&encoding_id.to_string()) {
+                array_encodings.push(encoding_id.to_string());
+            }
+        }
+    }
+
+    // Extract layout encodings
+    let mut layout_encodings = Vec::new();
+    if let Some(layout_specs) = footer.layout_specs() {
+        for spec in layout_specs.iter() {
+            let encoding_id = spec.id();
+            if !encoding_id.is_empty() && !layout_encodings.contains(&encoding_id.to_string()) {
+                layout_encodings.push(encoding_id.to_string());
+            }
+        }
+    }
+
+    Ok((array_encodings, layout_encodings))
+}
+
+/// A segment spec from the footer's segment table
+#[derive(Clone, Copy, Debug)]
+struct SegmentEntry {
+    offset: u64,
+    length: u32,
+    alignment_exponent: u8,
+}
+
+/// Everything the footer flatbuffer declares, in a form that outlives its bytes
+#[derive(Debug)]
+struct FooterContents {
+    array_specs: Vec<String>,
+    layout_specs: Vec<String>,
+    compression_specs: Vec<String>,
+    segment_specs: Vec<SegmentEntry>,
+}
+
+fn footer_contents(raw: &RawFooter) -> Result<FooterContents, FooterError> {
+    let footer = raw.footer()?;
+
+    Ok(FooterContents {
+        array_specs: footer
+            .array_specs()
+            .map(|specs| specs.iter().map(|s| s.id().to_string()).collect())
+            .unwrap_or_default(),
+        layout_specs: footer
+            .layout_specs()
+            .map(|specs| specs.iter().map(|s| s.id().to_string()).collect())
+            .unwrap_or_default(),
+        compression_specs: footer
+            .compression_specs()
+            .map(|specs| {
+                specs
+                    .iter()
+                    .map(|s| compression_scheme_name(s.scheme()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        segment_specs: footer
+            .segment_specs()
+            .map(|specs| {
+                specs
+                    .iter()
+                    .map(|s| SegmentEntry {
+                        offset: s.offset(),
+                        length: s.length(),
+                        alignment_exponent: s.alignment_exponent(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Name of a compression scheme as in the flatbuffer schema, e.g. `zstd`
+///
+/// Schemes unknown to this build are shown by their value.
+fn compression_scheme_name(scheme: fb_footer::CompressionScheme) -> String {
+    scheme
+        .variant_name()
+        .map(|name| name.to_lowercase())
+        .unwrap_or_else(|| format!("unknown({})", scheme.0))
+}
+
+/// Alignment in bytes of an alignment exponent, `None` if it does not fit in a u64
+pub fn alignment(exponent: u8) -> Option<u64> {
+    1u64.checked_shl(exponent as u32)
+}
+
+/// Alignment for text output, the raw exponent when it does not fit in a u64
+fn alignment_text(exponent: u8) -> String {
+    alignment(exponent)
+        .map(|alignment| alignment.to_string())
+        .unwrap_or_else(|| format!("2^{}", exponent))
+}
+
+fn alignment_json(exponent: u8) -> serde_json::Value {
+    serde_json::json!({
+        "alignment": alignment(exponent),
+        "alignment_exponent": exponent,
+    })
+}
+
+fn postscript_segment_json(segment: &PostscriptSegment) -> serde_json::Value {
+    let mut segment_json = serde_json::json!({
+        "offset": segment.offset,
+        "length": segment.length,
+    });
+    merge_json(
+        &mut segment_json,
+        alignment_json(segment.alignment_exponent),
+    );
+    segment_json
+}
+
+fn merge_json(target: &mut serde_json::Value, source: serde_json::Value) {
+    if let (Some(target), serde_json::Value::Object(source)) = (target.as_object_mut(), source) {
+        target.extend(source);
+    }
+}
+
+/// Dump the EOF marker, postscript and decoded footer of a file
+pub async fn show_footer(
+    path: &Path,
+    format: DocumentFormat,
+    limits: &VerifierLimits,
+) -> anyhow::Result<()> {
+    let raw = read_raw_footer(path, limits).await?;
+    let contents = footer_contents(&raw)?;
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let postscript: serde_json::Map<String, serde_json::Value> = raw
+                .segments
+                .iter()
+                .map(|s| (s.name.to_string(), postscript_segment_json(s)))
+                .collect();
+
+            let footer_json = serde_json::json!({
+                "file": path.display().to_string(),
+                "file_size": raw.file_size,
+                "eof": {
+                    "version": raw.eof.version,
+                    "postscript_length": raw.eof.postscript_size,
+                    "magic": String::from_utf8_lossy(MAGIC),
+                },
+                "postscript": {
+                    "offset": raw.postscript_offset,
+                    "segments": postscript,
+                },
+                "footer": {
+                    "array_specs": contents.array_specs,
+                    "layout_specs": contents.layout_specs,
+                    "compression_specs": contents.compression_specs,
+                    "segment_specs": contents
+                        .segment_specs
+                        .iter()
+                        .map(|s| {
+                            let mut segment_json = serde_json::json!({
+                                "offset": s.offset,
+                                "length": s.length,
+                            });
+                            merge_json(&mut segment_json, alignment_json(s.alignment_exponent));
+                            segment_json
+                        })
+                        .collect::<Vec<_>>(),
+                },
+            });
+            print_json(&footer_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex File Footer ===");
+            println!("File: {}", path.display());
+            println!("File size: {} bytes", raw.file_size);
+
+            println!("\n--- EOF ---");
+            println!("Version: {}", raw.eof.version);
+            println!("Postscript length: {} bytes", raw.eof.postscript_size);
+            println!("Magic: {}", String::from_utf8_lossy(MAGIC));
+
+            println!("\n--- Postscript (offset {}) ---", raw.postscript_offset);
+            println!(
+                "{:<12} {:>14} {:>12} {:>10}",
+                "Segment", "Offset", "Length", "Alignment"
+            );
+            println!("{}", "-".repeat(51));
+            for segment in &raw.segments {
+                println!(
+                    "{:<12} {:>14} {:>12} {:>10}",
+                    segment.name,
+                    segment.offset,
+                    segment.length,
+                    alignment_text(segment.alignment_exponent)
+                );
+            }
+
+            for (title, specs) in [
+                ("Array Specs", &contents.array_specs),
+                ("Layout Specs", &contents.layout_specs),
+                ("Compression Specs", &contents.compression_specs),
+            ] {
+                println!("\n--- {} ({}) ---", title, specs.len());
+                for (idx, spec) in specs.iter().enumerate() {
+                    println!("{:>5}  {}", idx, spec);
+                }
+            }
+
+            println!("\n--- Segment Specs ({}) ---", contents.segment_specs.len());
+            println!(
+                "{:>7} {:>14} {:>12} {:>10}",
+                "Segment", "Offset", "Length", "Alignment"
+            );
+            println!("{}", "-".repeat(46));
+            for (idx, segment) in contents.segment_specs.iter().enumerate() {
+                println!(
+                    "{:>7} {:>14} {:>12} {:>10}",
+                    idx,
+                    segment.offset,
+                    segment.length,
+                    alignment_text(segment.alignment_exponent)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::testing::write_vortex_file;
+
+    /// Write a small valid file and return its path and bytes
+    async fn valid_file(dir: &Path) -> (PathBuf, Vec<u8>) {
+        let path = dir.join("valid.vortex");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..1000)),
+                Arc::new(StringArray::from_iter(
+                    (0..1000).map(|i| (i % 3 != 0).then(|| format!("name-{}", i))),
+                )),
+            ],
+        )
+        .unwrap();
+        write_vortex_file(&path, vec![batch]).await.unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        (path, bytes)
+    }
+
+    /// Write corrupted bytes next to the valid file and read their footer
+    async fn read_corrupted(dir: &Path, bytes: &[u8]) -> FooterError {
+        let path = dir.join("corrupted.vortex");
+        std::fs::write(&path, bytes).unwrap();
+        read_raw_footer(&path, &VerifierLimits::default())
+            .await
+            .expect_err("reading a corrupted footer must fail")
+    }
+
+    /// Offset of the EOF marker's postscript length in the file
+    fn postscript_size_at(bytes: &[u8]) -> usize {
+        bytes.len() - EOF_SIZE as usize + 2
+    }
+
+    #[tokio::test]
+    async fn valid_footer() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, bytes) = valid_file(dir.path()).await;
+
+        let raw = read_raw_footer(&path, &VerifierLimits::default())
+            .await
+            .unwrap();
+        assert_eq!(raw.file_size, bytes.len() as u64);
+        assert_eq!(raw.eof.version, SUPPORTED_VERSION);
+        assert!(raw.segments.iter().any(|s| s.name == "footer"));
+        assert!(footer_contents(&raw).is_ok());
+    }
+
+    #[tokio::test]
+    async fn truncated_below_eof() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, bytes) = valid_file(dir.path()).await;
+
+        for len in [0, EOF_SIZE as usize - 1] {
+            let err = read_corrupted(dir.path(), &bytes[..len]).await;
+            assert!(
+                matches!(err, FooterError::TooSmall { file_size } if file_size == len as u64),
+                "{:?}",
+                err
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn corrupted_magic() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, mut bytes) = valid_file(dir.path()).await;
+
+        let len = bytes.len();
+        bytes[len - 4..].copy_from_slice(b"XXXX");
+        let err = read_corrupted(dir.path(), &bytes).await;
+        assert!(
+            matches!(err, FooterError::BadMagic { found } if &found == b"XXXX"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn corrupted_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, mut bytes) = valid_file(dir.path()).await;
+
+        let at = bytes.len() - EOF_SIZE as usize;
+        bytes[at..at + 2].copy_from_slice(&99u16.to_le_bytes());
+        let err = read_corrupted(dir.path(), &bytes).await;
+        assert!(
+            matches!(err, FooterError::UnsupportedVersion { version: 99 }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn corrupted_postscript() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, mut bytes) = valid_file(dir.path()).await;
+
+        // A postscript length of zero
+        let at = postscript_size_at(&bytes);
+        let mut empty = bytes.clone();
+        empty[at..at + 2].copy_from_slice(&0u16.to_le_bytes());
+        let err = read_corrupted(dir.path(), &empty).await;
+        assert!(
+            matches!(err, FooterError::BadPostscript { .. }),
+            "{:?}",
+            err
+        );
+
+        // Postscript bytes that are not a flatbuffer
+        let size = u16::from_le_bytes([bytes[at], bytes[at + 1]]) as usize;
+        let start = bytes.len() - EOF_SIZE as usize - size;
+        bytes[start..start + size].fill(0xFF);
+        let err = read_corrupted(dir.path(), &bytes).await;
+        assert!(
+            matches!(
+                err,
+                FooterError::Verification {
+                    flatbuffer: "postscript",
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn out_of_range_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, bytes) = valid_file(dir.path()).await;
+        let raw = read_raw_footer(&path, &VerifierLimits::default())
+            .await
+            .unwrap();
+        let footer = raw.segments.iter().find(|s| s.name == "footer").unwrap();
+
+        // Patch the footer offset stored in the postscript flatbuffer
+        let postscript = raw.postscript_offset as usize..bytes.len() - EOF_SIZE as usize;
+        let offset_bytes = footer.offset.to_le_bytes();
+        let at = postscript.start
+            + bytes[postscript]
+                .windows(offset_bytes.len())
+                .position(|window| window == offset_bytes)
+                .expect("footer offset not found in the postscript");
+
+        for (offset, end) in [
+            (
+                raw.postscript_offset,
+                Some(raw.postscript_offset + footer.length as u64),
+            ),
+            // The end of the segment overflows
+            (u64::MAX - 1, None),
+        ] {
+            let mut corrupted = bytes.clone();
+            corrupted[at..at + 8].copy_from_slice(&offset.to_le_bytes());
+            let err = read_corrupted(dir.path(), &corrupted).await;
+            match err {
+                FooterError::OutOfRange {
+                    segment: "footer",
+                    offset: found_offset,
+                    end: found_end,
+                    ..
+                } => {
+                    assert_eq!(found_offset, offset);
+                    assert_eq!(found_end, end.unwrap_or(u64::MAX));
+                }
+                err => panic!("expected an out of range footer, got {:?}", err),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn corrupted_footer_flatbuffer() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, mut bytes) = valid_file(dir.path()).await;
+        let raw = read_raw_footer(&path, &VerifierLimits::default())
+            .await
+            .unwrap();
+        let footer = raw.segments.iter().find(|s| s.name == "footer").unwrap();
+
+        let start = footer.offset as usize;
+        bytes[start..start + footer.length as usize].fill(0xFF);
+        let err = read_corrupted(dir.path(), &bytes).await;
+        assert!(
+            matches!(
+                err,
+                FooterError::Verification {
+                    flatbuffer: "footer",
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+    }
+}
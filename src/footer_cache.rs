@@ -0,0 +1,241 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    Attributes, GetOptions, GetRange, GetResult, GetResultPayload, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Requested range of a read, as a hashable cache key
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum RangeKey {
+    Bounded(u64, u64),
+    Offset(u64),
+    Suffix(u64),
+}
+
+impl From<&GetRange> for RangeKey {
+    fn from(range: &GetRange) -> Self {
+        match range {
+            GetRange::Bounded(range) => RangeKey::Bounded(range.start, range.end),
+            GetRange::Offset(offset) => RangeKey::Offset(*offset),
+            GetRange::Suffix(length) => RangeKey::Suffix(*length),
+        }
+    }
+}
+
+/// A read that reached the end of a file, which holds its footer
+#[derive(Clone)]
+struct CachedTail {
+    meta: ObjectMeta,
+    range: Range<u64>,
+    attributes: Attributes,
+    bytes: Bytes,
+}
+
+impl fmt::Debug for CachedTail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedTail")
+            .field("location", &self.meta.location)
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+/// Object store that keeps the reads of file tails in memory
+///
+/// Vortex files end with their postscript and footer, so every open of a file
+/// reads the same range at its end. Caching those reads by path and range lets
+/// a long-running server open the same files for every request without
+/// reading their footers again. Cached entries are dropped when the size or
+/// modification time of the file changes.
+#[derive(Debug)]
+pub struct FooterCache {
+    inner: Arc<dyn ObjectStore>,
+    tails: Mutex<HashMap<(Path, RangeKey), CachedTail>>,
+    footer_reads: AtomicUsize,
+}
+
+impl FooterCache {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            inner,
+            tails: Mutex::new(HashMap::new()),
+            footer_reads: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of file tails read from the underlying store
+    #[cfg(test)]
+    pub fn footer_reads(&self) -> usize {
+        self.footer_reads.load(Ordering::Relaxed)
+    }
+
+    /// Return a cached tail if the file has not changed since it was read
+    async fn cached(&self, key: &(Path, RangeKey)) -> object_store::Result<Option<CachedTail>> {
+        let Some(tail) = self.tails.lock().unwrap().get(key).cloned() else {
+            return Ok(None);
+        };
+
+        let meta = self.inner.head(&key.0).await?;
+        if meta.size == tail.meta.size && meta.last_modified == tail.meta.last_modified {
+            return Ok(Some(tail));
+        }
+
+        self.tails.lock().unwrap().remove(key);
+        Ok(None)
+    }
+}
+
+/// Whether a read is a plain range read whose result can be reused
+fn cacheable_range(options: &GetOptions) -> Option<RangeKey> {
+    if options.head
+        || options.if_match.is_some()
+        || options.if_none_match.is_some()
+        || options.if_modified_since.is_some()
+        || options.if_unmodified_since.is_some()
+        || options.version.is_some()
+    {
+        return None;
+    }
+
+    options.range.as_ref().map(RangeKey::from)
+}
+
+fn tail_result(tail: CachedTail) -> GetResult {
+    let bytes = tail.bytes;
+    GetResult {
+        payload: GetResultPayload::Stream(futures::stream::once(async move { Ok(bytes) }).boxed()),
+        meta: tail.meta,
+        range: tail.range,
+        attributes: tail.attributes,
+    }
+}
+
+impl fmt::Display for FooterCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FooterCache({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for FooterCache {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        let Some(range) = cacheable_range(&options) else {
+            return self.inner.get_opts(location, options).await;
+        };
+
+        let key = (location.clone(), range);
+        if let Some(tail) = self.cached(&key).await? {
+            return Ok(tail_result(tail));
+        }
+
+        let result = self.inner.get_opts(location, options).await?;
+        if result.range.end != result.meta.size {
+            return Ok(result);
+        }
+
+        let tail = CachedTail {
+            meta: result.meta.clone(),
+            range: result.range.clone(),
+            attributes: result.attributes.clone(),
+            bytes: result.bytes().await?,
+        };
+        self.footer_reads.fetch_add(1, Ordering::Relaxed);
+        self.tails.lock().unwrap().insert(key, tail.clone());
+
+        Ok(tail_result(tail))
+    }
+
+    async fn head(&self, location: &Path) -> object_store::Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> object_store::Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> object_store::Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn tails_are_read_once() {
+        let inner = Arc::new(InMemory::new());
+        let path = Path::from("file.vortex");
+        inner
+            .put(&path, PutPayload::from(vec![1u8, 2, 3, 4, 5, 6, 7, 8]))
+            .await
+            .unwrap();
+
+        let cache = FooterCache::new(inner.clone());
+        for _ in 0..2 {
+            assert_eq!(
+                cache.get_range(&path, 4..8).await.unwrap().as_ref(),
+                &[5, 6, 7, 8]
+            );
+            assert_eq!(
+                cache.get_range(&path, 0..2).await.unwrap().as_ref(),
+                &[1, 2]
+            );
+        }
+        assert_eq!(cache.footer_reads(), 1);
+
+        // A rewritten file is read again
+        inner
+            .put(&path, PutPayload::from(vec![8u8, 7, 6, 5, 4, 3, 2, 1]))
+            .await
+            .unwrap();
+        assert_eq!(
+            cache.get_range(&path, 4..8).await.unwrap().as_ref(),
+            &[4, 3, 2, 1]
+        );
+        assert_eq!(cache.footer_reads(), 2);
+    }
+}
@@ -1,19 +1,43 @@
 use anyhow::{Context, Result};
+use async_walkdir::WalkDir;
 use clap::{Parser, Subcommand};
+use futures::StreamExt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::fs::File;
-use tokio::io::{AsyncReadExt, AsyncSeekExt};
 use vortex::VortexSessionDefault;
-use vortex_array::{Array, ArrayRef, ArrayVisitor};
-use vortex_array::arrays::{DictArray, StructArray};
-use vortex_array::stream::ArrayStreamExt;
 use vortex_file::{OpenOptionsSessionExt, VortexFile, register_default_encodings};
-use vortex_flatbuffers::footer as fb_footer;
+use vortex_layout::LayoutRef;
 use vortex_layout::display::DisplayLayoutTree;
 use vortex_session::VortexSession;
 
+mod bytemap;
+mod convert;
+mod dataset;
+mod diff;
+mod encoding;
+mod export;
+mod filter;
+mod footer;
+mod footer_cache;
+mod model;
+mod promql;
+mod query;
+mod recompress;
+mod rewrite;
+mod rows;
+mod schema_check;
+mod serve;
+mod shell;
+mod size;
+mod stats;
+mod strategy;
+#[cfg(test)]
+mod testing;
+mod trace;
+mod verify;
+mod zones;
+
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
@@ -29,39 +53,58 @@ struct Cli {
 enum Commands {
     /// Display metadata information from a Vortex file
     Metadata {
-        /// Path to the Vortex file
+        /// Path to a Vortex file, a directory or a glob of files
         #[arg(value_name = "FILE")]
         file: PathBuf,
 
-        /// Output format (json or text)
-        #[arg(short, long, default_value = "text")]
-        format: OutputFormat,
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
     },
 
     /// Display schema (Arrow schema) from a Vortex file
     Schema {
-        /// Path to the Vortex file
+        /// Path to a Vortex file, a directory or a glob of files
         #[arg(value_name = "FILE")]
         file: PathBuf,
 
-        /// Output format (json or text)
-        #[arg(short, long, default_value = "text")]
-        format: OutputFormat,
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
 
         /// Show detailed field information
         #[arg(short, long)]
         verbose: bool,
     },
 
+    /// Group the files of a dataset by schema and report drift from a baseline
+    SchemaCheck {
+        /// Directory or glob of Vortex files
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+
+        /// Vortex file or schema JSON file to compare against, defaults to the most common schema
+        #[arg(short, long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+
+        /// List every file of each schema variant
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// Display layout information from a Vortex file
     Layout {
-        /// Path to the Vortex file
+        /// Path to a Vortex file, a directory or a glob of files
         #[arg(value_name = "FILE")]
         file: PathBuf,
 
-        /// Output format (json or text)
-        #[arg(short, long, default_value = "text")]
-        format: OutputFormat,
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
 
         /// Show detailed layout tree
         #[arg(short, long)]
@@ -70,32 +113,406 @@ enum Commands {
 
     /// Display all information (metadata, schema, and layout)
     Inspect {
-        /// Path to the Vortex file
+        /// Path to a Vortex file, a directory or a glob of files
         #[arg(value_name = "FILE")]
         file: PathBuf,
 
-        /// Output format (json or text)
-        #[arg(short, long, default_value = "text")]
-        format: OutputFormat,
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
 
         /// Show verbose output
         #[arg(short, long)]
         verbose: bool,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Display the on-disk size of each column in a Vortex file
+    Size {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+
+        /// Decode each column to compute its exact uncompressed size
+        #[arg(short, long)]
+        decode: bool,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Display the file-level statistics of each column
+    Stats {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+    },
+
+    /// Display the zone map of a column: row range, min, max and null count of each zone
+    Zones {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Column whose zone map to show
+        #[arg(short, long)]
+        column: String,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Print the JSON Schema of the JSON output
+    JsonSchema,
+
+    /// Dump the EOF marker, postscript and decoded footer flatbuffer
+    Footer {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+
+        #[command(flatten)]
+        limits: footer::VerifierLimits,
+    },
+
+    /// Show the physical regions of a Vortex file as a byte map
+    Bytemap {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+
+        /// List every region with its offset, length, column and chunk
+        #[arg(short, long)]
+        verbose: bool,
+
+        #[command(flatten)]
+        limits: footer::VerifierLimits,
+    },
+
+    /// Fully decode and validate a Vortex file, exiting non-zero on any failure
+    Verify {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+
+        #[command(flatten)]
+        limits: footer::VerifierLimits,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Re-encode each column under alternative compression strategies and compare them
+    RecompressEval {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Columns to evaluate (comma-separated), defaults to all columns
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<String>,
+
+        /// Only evaluate the first N chunks of each column
+        #[arg(long, value_name = "N")]
+        sample_chunks: Option<usize>,
+
+        /// Output format (text, csv, ndjson or json)
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
     },
 
     /// Inspect array encodings and compression methods used in a Vortex file
     Encoding {
-        /// Path to the Vortex file
+        /// Path to a Vortex file, a directory or a glob of files
         #[arg(value_name = "FILE")]
         file: PathBuf,
 
-        /// Output format (json or text)
+        /// Output format (text, json or ndjson, csv with --matrix)
         #[arg(short, long, default_value = "text")]
         format: OutputFormat,
 
-        /// Show detailed encoding tree
+        /// Show detailed encoding tree of the first chunk of each column
         #[arg(short, long)]
         verbose: bool,
+
+        /// Only read the first N chunks of each column
+        #[arg(long, value_name = "N")]
+        sample_chunks: Option<usize>,
+
+        /// List the columns using an encoding anywhere in their chunks (e.g. vortex.fsst)
+        #[arg(long, value_name = "ENCODING")]
+        find: Option<String>,
+
+        /// Show the top-level encoding of each column in each chunk as a grid
+        #[arg(long, conflicts_with_all = ["verbose", "find"])]
+        matrix: bool,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Compare two Vortex files by schema, layout and encodings, and data
+    Diff {
+        /// Path to the first Vortex file (A)
+        #[arg(value_name = "A")]
+        a: PathBuf,
+
+        /// Path to the second Vortex file (B)
+        #[arg(value_name = "B")]
+        b: PathBuf,
+
+        /// How far to compare, each level including the previous ones
+        #[arg(short, long, value_enum, default_value = "data")]
+        level: diff::DiffLevel,
+
+        /// Match rows by this column instead of by position
+        #[arg(short, long, value_name = "COLUMN")]
+        key: Option<String>,
+
+        /// Maximum number of differing rows to show
+        #[arg(long, value_name = "N", default_value = "10")]
+        sample: usize,
+
+        /// Only read the first N chunks of each column to compare encodings
+        #[arg(long, value_name = "N")]
+        sample_chunks: Option<usize>,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Print the first rows of a Vortex file
+    Head {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Number of rows to print
+        #[arg(short = 'n', long, default_value = "10")]
+        rows: u64,
+
+        /// Columns to print (comma-separated), defaults to all columns
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<String>,
+
+        /// Output format (text, csv, ndjson or json)
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Print rows of a Vortex file
+    Cat {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Row range to print (START..END, START.. or ..END), defaults to all rows
+        #[arg(short, long, value_parser = rows::parse_row_range)]
+        rows: Option<std::ops::Range<u64>>,
+
+        /// Columns to print (comma-separated), defaults to all columns
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<String>,
+
+        /// Output format (text, csv, ndjson or json)
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Print rows matching a predicate, pushing the filter into the scan
+    Filter {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// SQL predicate, e.g. "col > 10 AND host = 'a'"
+        #[arg(short, long = "where", value_name = "PREDICATE")]
+        predicate: String,
+
+        /// Columns to print (comma-separated), defaults to all columns
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<String>,
+
+        /// Output format (text, csv, ndjson or json)
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+
+        /// Report for each chunk whether statistics prune it instead of printing rows
+        #[arg(long)]
+        explain_prune: bool,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Convert a Parquet file to a Vortex file
+    Convert {
+        /// Path to the input Parquet file
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Path to the output Vortex file
+        #[arg(value_name = "OUTPUT")]
+        output: PathBuf,
+
+        #[command(flatten)]
+        options: strategy::WriteOptions,
+    },
+
+    /// Rewrite a Vortex file with a different layout or compression strategy
+    Rewrite {
+        /// Path to the input Vortex file
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Path to the output Vortex file
+        #[arg(value_name = "OUTPUT")]
+        output: PathBuf,
+
+        /// Columns to keep (comma-separated), defaults to all columns
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<String>,
+
+        #[command(flatten)]
+        options: strategy::WriteOptions,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Export a Vortex file to Parquet, Arrow IPC, CSV or NDJSON
+    Export {
+        /// Path to the Vortex file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Format to export to
+        #[arg(short, long = "to", value_enum)]
+        to: export::ExportFormat,
+
+        /// Path to the output file
+        #[arg(short, long, value_name = "OUTPUT")]
+        output: PathBuf,
+
+        #[command(flatten)]
+        trace: trace::TraceArgs,
+    },
+
+    /// Run a SQL query over Vortex files using DataFusion
+    Query {
+        /// SQL query to execute
+        #[arg(value_name = "SQL")]
+        sql: String,
+
+        /// Register a Vortex file as a table (NAME=PATH), can be repeated
+        #[arg(short, long = "table", value_name = "NAME=PATH", value_parser = query::parse_table_arg)]
+        tables: Vec<(String, PathBuf)>,
+
+        /// Output format (text, csv, ndjson or json)
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        trace: trace::UntracedArgs,
+    },
+
+    /// Start an interactive SQL shell over all Vortex files in a directory
+    Shell {
+        /// Directory containing Vortex files
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        #[command(flatten)]
+        trace: trace::UntracedArgs,
+    },
+
+    /// Evaluate a PromQL query over metric data stored in Vortex files
+    Promql {
+        /// PromQL expression
+        #[arg(value_name = "QUERY")]
+        query: String,
+
+        /// Vortex file or directory containing metric files
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+
+        /// Start of a range query (RFC 3339 or unix seconds)
+        #[arg(long)]
+        start: Option<String>,
+
+        /// End of a range query or time of an instant query [default: now]
+        #[arg(long)]
+        end: Option<String>,
+
+        /// Resolution step of a range query (e.g. 15s, 1m)
+        #[arg(long)]
+        step: Option<String>,
+
+        #[command(flatten)]
+        columns: promql::MetricColumns,
+
+        /// Output format (text, json or ndjson)
+        #[arg(short, long, value_enum, ignore_case = true, default_value = "text")]
+        format: DocumentFormat,
+
+        #[command(flatten)]
+        trace: trace::UntracedArgs,
+    },
+
+    /// Serve a Prometheus-compatible query API over metric data stored in Vortex files
+    Serve {
+        /// Vortex file or directory containing metric files
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+
+        /// Address to listen on
+        #[arg(short, long, default_value = "127.0.0.1:9090")]
+        listen: std::net::SocketAddr,
+
+        #[command(flatten)]
+        columns: promql::MetricColumns,
+
+        #[command(flatten)]
+        trace: trace::UntracedArgs,
     },
 }
 
@@ -103,6 +520,8 @@ enum Commands {
 enum OutputFormat {
     Json,
     Text,
+    Csv,
+    Ndjson,
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -112,168 +531,423 @@ impl std::str::FromStr for OutputFormat {
         match s.to_lowercase().as_str() {
             "json" => Ok(OutputFormat::Json),
             "text" => Ok(OutputFormat::Text),
-            _ => Err(format!("Invalid format: {}. Use 'json' or 'text'", s)),
+            "csv" => Ok(OutputFormat::Csv),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            _ => Err(format!(
+                "Invalid format: {}. Use 'json', 'text', 'csv' or 'ndjson'",
+                s
+            )),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// The format for output that is a single document, which has no CSV form
+    fn document(&self) -> Option<DocumentFormat> {
+        match self {
+            OutputFormat::Json => Some(DocumentFormat::Json),
+            OutputFormat::Text => Some(DocumentFormat::Text),
+            OutputFormat::Ndjson => Some(DocumentFormat::Ndjson),
+            OutputFormat::Csv => None,
         }
     }
 }
 
+/// Output format of commands printing a single document rather than rows
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum DocumentFormat {
+    Json,
+    Text,
+    Ndjson,
+}
+
+/// Print a JSON document, pretty for json and on a single line for ndjson
+fn print_json(value: &serde_json::Value, format: &DocumentFormat) -> Result<()> {
+    match format {
+        DocumentFormat::Ndjson => println!("{}", serde_json::to_string(value)?),
+        _ => println!("{}", serde_json::to_string_pretty(value)?),
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
         Commands::Metadata { file, format } => {
-            show_metadata(&file, format).await?;
+            if dataset::is_dataset(&file) {
+                dataset::show_dataset(&file, format, dataset::DatasetView::Metadata).await?;
+            } else {
+                show_metadata(&file, format).await?;
+            }
         }
         Commands::Schema {
             file,
             format,
             verbose,
         } => {
-            show_schema(&file, format, verbose).await?;
+            if dataset::is_dataset(&file) {
+                if verbose {
+                    anyhow::bail!("--verbose requires a single file");
+                }
+                dataset::show_dataset(&file, format, dataset::DatasetView::Schema).await?;
+            } else {
+                show_schema(&file, format, verbose).await?;
+            }
+        }
+        Commands::SchemaCheck {
+            path,
+            baseline,
+            format,
+            verbose,
+        } => {
+            schema_check::run_schema_check(&path, baseline.as_deref(), format, verbose).await?;
         }
         Commands::Layout {
             file,
             format,
             verbose,
         } => {
-            show_layout(&file, format, verbose).await?;
+            if dataset::is_dataset(&file) {
+                if verbose {
+                    anyhow::bail!("--verbose requires a single file");
+                }
+                dataset::show_dataset(&file, format, dataset::DatasetView::Layout).await?;
+            } else {
+                show_layout(&file, format, verbose).await?;
+            }
         }
         Commands::Inspect {
             file,
             format,
             verbose,
+            trace,
         } => {
-            show_inspect(&file, format, verbose).await?;
+            trace::traced(trace, async {
+                if dataset::is_dataset(&file) {
+                    if verbose {
+                        anyhow::bail!("--verbose requires a single file");
+                    }
+                    dataset::show_dataset(&file, format, dataset::DatasetView::Inspect).await
+                } else {
+                    show_inspect(&file, format, verbose).await
+                }
+            })
+            .await?;
+        }
+        Commands::Stats { file, format } => {
+            stats::show_stats(&file, format).await?;
+        }
+        Commands::Zones {
+            file,
+            column,
+            format,
+            trace,
+        } => {
+            trace::traced(trace, zones::show_zones(&file, &column, format)).await?;
+        }
+        Commands::JsonSchema => {
+            print!("{}", model::JSON_SCHEMA);
+        }
+        Commands::Size {
+            file,
+            format,
+            decode,
+            trace,
+        } => {
+            trace::traced(trace, size::show_size(&file, format, decode)).await?;
+        }
+        Commands::Footer {
+            file,
+            format,
+            limits,
+        } => {
+            footer::show_footer(&file, format, &limits).await?;
+        }
+        Commands::Bytemap {
+            file,
+            format,
+            verbose,
+            limits,
+        } => {
+            bytemap::show_bytemap(&file, format, verbose, &limits).await?;
+        }
+        Commands::Verify {
+            file,
+            format,
+            limits,
+            trace,
+        } => {
+            trace::traced(trace, verify::run_verify(&file, format, &limits)).await?;
+        }
+        Commands::RecompressEval {
+            file,
+            columns,
+            sample_chunks,
+            format,
+            trace,
+        } => {
+            trace::traced(
+                trace,
+                recompress::run_recompress_eval(&file, &columns, sample_chunks, format),
+            )
+            .await?;
         }
         Commands::Encoding {
             file,
             format,
             verbose,
+            sample_chunks,
+            find,
+            matrix,
+            trace,
         } => {
-            show_encoding(&file, format, verbose).await?;
+            trace::traced(trace, async {
+                if matrix {
+                    if dataset::is_dataset(&file) {
+                        anyhow::bail!("--matrix and --find require a single file");
+                    }
+                    return encoding::show_encoding_matrix(&file, format, sample_chunks).await;
+                }
+
+                let format = format
+                    .document()
+                    .context("CSV output is only supported with --matrix")?;
+                if dataset::is_dataset(&file) {
+                    if find.is_some() {
+                        anyhow::bail!("--matrix and --find require a single file");
+                    }
+                    if verbose {
+                        anyhow::bail!("--verbose requires a single file");
+                    }
+                    dataset::show_dataset(
+                        &file,
+                        format,
+                        dataset::DatasetView::Encoding { sample_chunks },
+                    )
+                    .await
+                } else {
+                    encoding::show_encoding(&file, format, verbose, sample_chunks, find.as_deref())
+                        .await
+                }
+            })
+            .await?;
+        }
+        Commands::Diff {
+            a,
+            b,
+            level,
+            key,
+            sample,
+            sample_chunks,
+            format,
+            trace,
+        } => {
+            trace::traced(
+                trace,
+                diff::run_diff(&a, &b, level, key.as_deref(), sample, sample_chunks, format),
+            )
+            .await?;
+        }
+        Commands::Head {
+            file,
+            rows,
+            columns,
+            format,
+            trace,
+        } => {
+            trace::traced(trace, rows::show_rows(&file, &columns, 0..rows, format)).await?;
+        }
+        Commands::Cat {
+            file,
+            rows,
+            columns,
+            format,
+            trace,
+        } => {
+            let row_range = rows.unwrap_or(0..u64::MAX);
+            trace::traced(trace, rows::show_rows(&file, &columns, row_range, format)).await?;
+        }
+        Commands::Filter {
+            file,
+            predicate,
+            columns,
+            format,
+            explain_prune,
+            trace,
+        } => {
+            if explain_prune {
+                let format = format
+                    .document()
+                    .context("CSV output is not supported with --explain-prune")?;
+                trace::traced(trace, filter::explain_prune(&file, &predicate, format)).await?;
+            } else {
+                trace::traced(
+                    trace,
+                    filter::run_filter(&file, &predicate, &columns, format),
+                )
+                .await?;
+            }
+        }
+        Commands::Convert {
+            input,
+            output,
+            options,
+        } => {
+            convert::run_convert(&input, &output, options).await?;
+        }
+        Commands::Rewrite {
+            input,
+            output,
+            columns,
+            options,
+            trace,
+        } => {
+            trace::traced(
+                trace,
+                rewrite::run_rewrite(&input, &output, &columns, options),
+            )
+            .await?;
+        }
+        Commands::Export {
+            file,
+            to,
+            output,
+            trace,
+        } => {
+            trace::traced(trace, export::run_export(&file, to, &output)).await?;
+        }
+        Commands::Query {
+            sql,
+            tables,
+            format,
+            trace,
+        } => {
+            trace.reject("query")?;
+            query::run_query(&sql, &tables, format).await?;
+        }
+        Commands::Shell { dir, trace } => {
+            trace.reject("shell")?;
+            shell::run_shell(&dir).await?;
+        }
+        Commands::Promql {
+            query,
+            path,
+            start,
+            end,
+            step,
+            columns,
+            format,
+            trace,
+        } => {
+            trace.reject("promql")?;
+            promql::run_promql(
+                &query,
+                &path,
+                start.as_deref(),
+                end.as_deref(),
+                step.as_deref(),
+                columns,
+                format,
+            )
+            .await?;
+        }
+        Commands::Serve {
+            path,
+            listen,
+            columns,
+            trace,
+        } => {
+            trace.reject("serve")?;
+            serve::run_serve(&path, listen, columns).await?;
         }
     }
 
     Ok(())
 }
 
+/// Shorten a string to at most `max_len` characters, ending it with `...` when cut
 fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len - 3])
+        let kept: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", kept)
     }
 }
 
 /// Read footer flatbuffer from file and extract encoding specs
 async fn read_footer_encodings(path: &Path) -> Result<(Vec<String>, Vec<String>)> {
-    use vortex_flatbuffers::footer as fb;
-
-    let mut file = File::open(path).await?;
-    let file_size = file.metadata().await?.len();
-
-    if file_size < 8 {
-        anyhow::bail!("File too small to be a valid Vortex file");
-    }
-
-    // Read EOF (last 8 bytes)
-    // Format: [version: 2 bytes][postscript_len: 2 bytes][magic "VTXF": 4 bytes]
-    file.seek(std::io::SeekFrom::End(-8)).await?;
-    let mut eof = vec![0u8; 8];
-    file.read_exact(&mut eof).await?;
-
-    // Verify magic bytes (last 4 bytes)
-    if &eof[4..8] != b"VTXF" {
-        anyhow::bail!("Invalid magic bytes, not a Vortex file");
-    }
-
-    // Extract postscript size (bytes 2-4)
-    let postscript_size = u16::from_le_bytes([eof[2], eof[3]]) as u64;
-
-    if postscript_size == 0 {
-        anyhow::bail!("Invalid postscript size: 0");
-    }
-
-    if postscript_size + 8 > file_size {
-        anyhow::bail!("Postscript size {} exceeds file size {}", postscript_size, file_size);
-    }
-
-    // Read postscript (before EOF)
-    let postscript_offset = file_size - 8 - postscript_size;
-    file.seek(std::io::SeekFrom::Start(postscript_offset)).await?;
-    let mut postscript_bytes = vec![0u8; postscript_size as usize];
-    file.read_exact(&mut postscript_bytes).await?;
+    let raw = footer::read_raw_footer(path, &footer::VerifierLimits::default()).await?;
+    Ok(footer::footer_encodings(&raw)?)
+}
 
-    if postscript_bytes.is_empty() {
-        anyhow::bail!("Failed to read postscript bytes");
+/// Data layout encoding of each top-level column of a struct layout
+///
+/// The `vortex.stats` wrapper is skipped to show the encoding of the data itself.
+fn column_layout_encodings(layout: &LayoutRef) -> Vec<(String, String)> {
+    if layout.encoding().to_string() != "vortex.struct" {
+        return Vec::new();
     }
 
-    // Parse postscript flatbuffer (use root_unchecked to skip alignment check)
-    let fb_postscript = unsafe {
-        flatbuffers::root_unchecked::<fb::Postscript>(&postscript_bytes)
-    };
-
-    // Get footer segment info from postscript
-    let footer_segment = fb_postscript.footer()
-        .ok_or_else(|| anyhow::anyhow!("Postscript missing footer segment"))?;
-    let footer_offset = footer_segment.offset();
-    let footer_length = footer_segment.length();
-
-    if footer_length == 0 {
-        anyhow::bail!("Invalid footer length: 0");
-    }
+    (0..layout.nchildren())
+        .filter_map(|idx| {
+            let child = layout.child(idx).ok()?;
+            let encoding = child.encoding().to_string();
+
+            // Get the actual data encoding (skip stats wrapper if present)
+            let data_encoding = if encoding == "vortex.stats" && child.nchildren() > 0 {
+                child
+                    .child(0)
+                    .map(|data_child| data_child.encoding().to_string())
+                    .unwrap_or(encoding)
+            } else {
+                encoding
+            };
 
-    if footer_offset + footer_length as u64 > file_size {
-        anyhow::bail!("Footer extends beyond file size");
-    }
+            Some((layout.child_type(idx).name().to_string(), data_encoding))
+        })
+        .collect()
+}
 
-    // Read footer bytes
-    file.seek(std::io::SeekFrom::Start(footer_offset)).await?;
-    let mut footer_bytes = vec![0u8; footer_length as usize];
-    file.read_exact(&mut footer_bytes).await?;
+/// Recursively find all `.vortex` files under a directory
+async fn find_vortex_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut entries = WalkDir::new(dir);
 
-    if footer_bytes.is_empty() {
-        anyhow::bail!("Failed to read footer bytes");
-    }
+    while let Some(entry) = entries.next().await {
+        let entry = entry.context(format!("Failed to walk directory: {}", dir.display()))?;
+        let path = entry.path();
 
-    // Parse footer flatbuffer (use root_unchecked to skip alignment check)
-    let fb_footer = unsafe {
-        flatbuffers::root_unchecked::<fb_footer::Footer>(&footer_bytes)
-    };
-
-    // Extract array encodings
-    let mut array_encodings = Vec::new();
-    if let Some(array_specs) = fb_footer.array_specs() {
-        for spec in array_specs.iter() {
-            let encoding_id = spec.id();
-            if !encoding_id.is_empty() && !array_encodings.contains(&encoding_id.to_string()) {
-                array_encodings.push(encoding_id.to_string());
-            }
+        if entry.file_type().await?.is_file() && path.extension().is_some_and(|ext| ext == "vortex")
+        {
+            files.push(path);
         }
     }
 
-    // Extract layout encodings
-    let mut layout_encodings = Vec::new();
-    if let Some(layout_specs) = fb_footer.layout_specs() {
-        for spec in layout_specs.iter() {
-            let encoding_id = spec.id();
-            if !encoding_id.is_empty() && !layout_encodings.contains(&encoding_id.to_string()) {
-                layout_encodings.push(encoding_id.to_string());
-            }
-        }
-    }
-
-    Ok((array_encodings, layout_encodings))
+    files.sort();
+    Ok(files)
 }
 
-async fn open_vortex_file(path: &Path) -> Result<VortexFile> {
+/// Create a Vortex session with the default encodings registered
+fn create_session() -> Arc<VortexSession> {
     // Create a new Vortex session
     let session = Arc::new(VortexSession::default());
 
     // Register default encodings
     register_default_encodings(&session);
 
+    session
+}
+
+async fn open_vortex_file(path: &Path) -> Result<VortexFile> {
+    let session = create_session();
+
+    if let Some(trace) = trace::active() {
+        return trace::open_traced(&session, path, trace).await;
+    }
+
     // Open the Vortex file
     let vortex_file = session
         .open_options()
@@ -284,20 +958,21 @@ async fn open_vortex_file(path: &Path) -> Result<VortexFile> {
     Ok(vortex_file)
 }
 
-async fn show_metadata(path: &Path, format: OutputFormat) -> Result<()> {
+async fn show_metadata(path: &Path, format: DocumentFormat) -> Result<()> {
     let vortex_file = open_vortex_file(path).await?;
     let dtype = vortex_file.dtype();
 
     match format {
-        OutputFormat::Json => {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
             let metadata = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
                 "file": path.display().to_string(),
                 "row_count": vortex_file.row_count(),
-                "dtype": format!("{:?}", dtype),
+                "dtype": model::dtype_json(dtype),
             });
-            println!("{}", serde_json::to_string_pretty(&metadata)?);
+            print_json(&metadata, &format)?;
         }
-        OutputFormat::Text => {
+        DocumentFormat::Text => {
             println!("=== Vortex File Metadata ===");
             println!("File: {}", path.display());
             println!("Row Count: {}", vortex_file.row_count());
@@ -315,21 +990,22 @@ async fn show_metadata(path: &Path, format: OutputFormat) -> Result<()> {
     Ok(())
 }
 
-async fn show_schema(path: &Path, format: OutputFormat, verbose: bool) -> Result<()> {
+async fn show_schema(path: &Path, format: DocumentFormat, verbose: bool) -> Result<()> {
     let vortex_file = open_vortex_file(path).await?;
     let dtype = vortex_file.dtype();
 
     match format {
-        OutputFormat::Json => {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
             let arrow_schema = dtype.to_arrow_schema()?;
             let schema_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
                 "file": path.display().to_string(),
-                "vortex_dtype": format!("{:?}", dtype),
-                "arrow_schema": format!("{:?}", arrow_schema),
+                "vortex_dtype": model::dtype_json(dtype),
+                "arrow_schema": model::arrow_schema_json(&arrow_schema),
             });
-            println!("{}", serde_json::to_string_pretty(&schema_json)?);
+            print_json(&schema_json, &format)?;
         }
-        OutputFormat::Text => {
+        DocumentFormat::Text => {
             println!("=== Vortex Schema ===");
             println!("File: {}", path.display());
 
@@ -373,19 +1049,25 @@ async fn show_schema(path: &Path, format: OutputFormat, verbose: bool) -> Result
     Ok(())
 }
 
-async fn show_layout(path: &Path, format: OutputFormat, verbose: bool) -> Result<()> {
+async fn show_layout(path: &Path, format: DocumentFormat, verbose: bool) -> Result<()> {
     let vortex_file = open_vortex_file(path).await?;
     let layout = vortex_file.footer().layout();
 
     match format {
-        OutputFormat::Json => {
-            let layout_json = serde_json::json!({
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let mut layout_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
                 "file": path.display().to_string(),
-                "layout": format!("{:?}", layout),
+                "layout": model::layout_json(layout),
             });
-            println!("{}", serde_json::to_string_pretty(&layout_json)?);
+
+            if let Ok((array_encodings, layout_encodings)) = read_footer_encodings(path).await {
+                layout_json["array_encodings"] = serde_json::json!(array_encodings);
+                layout_json["layout_encodings"] = serde_json::json!(layout_encodings);
+            }
+            print_json(&layout_json, &format)?;
         }
-        OutputFormat::Text => {
+        DocumentFormat::Text => {
             println!("=== Vortex Layout ===");
             println!("File: {}", path.display());
 
@@ -397,33 +1079,21 @@ async fn show_layout(path: &Path, format: OutputFormat, verbose: bool) -> Result
             // Show column encoding summary
             if layout.encoding().to_string() == "vortex.struct" {
                 println!("\nColumn Encodings:");
-                println!("{:<5} {:<60} {:<20}", "Index", "Column Name", "Encoding Type");
+                println!(
+                    "{:<5} {:<60} {:<20}",
+                    "Index", "Column Name", "Encoding Type"
+                );
                 println!("{}", "-".repeat(90));
 
-                for idx in 0..layout.nchildren() {
-                    if let Ok(child) = layout.child(idx) {
-                        let child_type = layout.child_type(idx);
-                        let col_name = child_type.name();
-                        let encoding = child.encoding().to_string();
-
-                        // Get the actual data encoding (skip stats wrapper if present)
-                        let data_encoding = if encoding == "vortex.stats" && child.nchildren() > 0 {
-                            if let Ok(data_child) = child.child(0) {
-                                data_child.encoding().to_string()
-                            } else {
-                                encoding.clone()
-                            }
-                        } else {
-                            encoding.clone()
-                        };
-
-                        println!(
-                            "{:<5} {:<60} {:<20}",
-                            idx,
-                            truncate_string(&col_name.to_string(), 60),
-                            data_encoding
-                        );
-                    }
+                for (idx, (col_name, data_encoding)) in
+                    column_layout_encodings(layout).iter().enumerate()
+                {
+                    println!(
+                        "{:<5} {:<60} {:<20}",
+                        idx,
+                        truncate_string(col_name, 60),
+                        data_encoding
+                    );
                 }
                 println!();
             }
@@ -463,40 +1133,44 @@ async fn show_layout(path: &Path, format: OutputFormat, verbose: bool) -> Result
     Ok(())
 }
 
-async fn show_inspect(path: &Path, format: OutputFormat, verbose: bool) -> Result<()> {
+async fn show_inspect(path: &Path, format: DocumentFormat, verbose: bool) -> Result<()> {
     let vortex_file = open_vortex_file(path).await?;
     let dtype = vortex_file.dtype();
     let layout = vortex_file.footer().layout();
     let row_count = vortex_file.row_count();
     let file_stats = vortex_file.file_stats();
+    let file_size = tokio::fs::metadata(path).await?.len();
+    let column_sizes = size::column_sizes(&vortex_file);
 
     match format {
-        OutputFormat::Json => {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
             let arrow_schema = dtype.to_arrow_schema()?;
 
             let mut inspect_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
                 "file": path.display().to_string(),
                 "metadata": {
                     "row_count": row_count,
-                    "dtype": format!("{:?}", dtype),
+                    "dtype": model::dtype_json(dtype),
                 },
                 "schema": {
-                    "vortex_dtype": format!("{:?}", dtype),
-                    "arrow_schema": format!("{:?}", arrow_schema),
+                    "vortex_dtype": model::dtype_json(dtype),
+                    "arrow_schema": model::arrow_schema_json(&arrow_schema),
                 },
-                "layout": format!("{:?}", layout),
+                "layout": model::layout_json(layout),
+                "column_sizes": size::column_sizes_json(&column_sizes, file_size),
             });
 
             if let Some(stats) = file_stats {
                 inspect_json["statistics"] = serde_json::json!({
                     "available": true,
-                    "stats": format!("{:?}", stats),
+                    "fields": model::file_stats_json(stats, dtype),
                 });
             }
 
-            println!("{}", serde_json::to_string_pretty(&inspect_json)?);
+            print_json(&inspect_json, &format)?;
         }
-        OutputFormat::Text => {
+        DocumentFormat::Text => {
             println!("=== Vortex File Inspection ===");
             println!("File: {}", path.display());
 
@@ -554,33 +1228,21 @@ async fn show_inspect(path: &Path, format: OutputFormat, verbose: bool) -> Resul
             // Show column encoding summary
             if layout.encoding().to_string() == "vortex.struct" {
                 println!("\nColumn Encodings:");
-                println!("{:<5} {:<60} {:<20}", "Index", "Column Name", "Encoding Type");
+                println!(
+                    "{:<5} {:<60} {:<20}",
+                    "Index", "Column Name", "Encoding Type"
+                );
                 println!("{}", "-".repeat(90));
 
-                for idx in 0..layout.nchildren() {
-                    if let Ok(child) = layout.child(idx) {
-                        let child_type = layout.child_type(idx);
-                        let col_name = child_type.name();
-                        let encoding = child.encoding().to_string();
-
-                        // Get the actual data encoding (skip stats wrapper if present)
-                        let data_encoding = if encoding == "vortex.stats" && child.nchildren() > 0 {
-                            if let Ok(data_child) = child.child(0) {
-                                data_child.encoding().to_string()
-                            } else {
-                                encoding.clone()
-                            }
-                        } else {
-                            encoding.clone()
-                        };
-
-                        println!(
-                            "{:<5} {:<60} {:<20}",
-                            idx,
-                            truncate_string(&col_name.to_string(), 60),
-                            data_encoding
-                        );
-                    }
+                for (idx, (col_name, data_encoding)) in
+                    column_layout_encodings(layout).iter().enumerate()
+                {
+                    println!(
+                        "{:<5} {:<60} {:<20}",
+                        idx,
+                        truncate_string(col_name, 60),
+                        data_encoding
+                    );
                 }
             }
 
@@ -614,13 +1276,17 @@ async fn show_inspect(path: &Path, format: OutputFormat, verbose: bool) -> Resul
             let display_tree = DisplayLayoutTree::new(layout.clone(), verbose);
             println!("{}", display_tree);
 
+            println!("\n--- Column Sizes ---");
+            size::print_column_sizes(&column_sizes, file_size);
+
             // Show statistics if available
             if let Some(stats) = file_stats {
                 println!("\n--- Statistics ---");
+                if let Some(columns) = stats::column_stats(&vortex_file) {
+                    stats::print_column_stats(&columns);
+                }
                 if verbose {
-                    println!("{:#?}", stats);
-                } else {
-                    println!("{:?}", stats);
+                    println!("\n{:#?}", stats);
                 }
             } else {
                 println!("\n--- Statistics ---");
@@ -632,329 +1298,19 @@ async fn show_inspect(path: &Path, format: OutputFormat, verbose: bool) -> Resul
     Ok(())
 }
 
-/// Helper function to get children arrays
-fn get_array_children(array: &ArrayRef) -> Vec<ArrayRef> {
-    array.children()
-}
-
-/// Get compact description for specific encodings
-fn get_encoding_description(encoding_id: &str, array: &ArrayRef) -> String {
-    match encoding_id {
-        "vortex.zstd" => " [Zstd]".to_string(),
-        "vortex.dict" => {
-            if let Some(dict_array) = array.as_any().downcast_ref::<DictArray>() {
-                format!(
-                    " [Dict: {} values, {} codes]",
-                    dict_array.values().len(),
-                    dict_array.codes().len()
-                )
-            } else {
-                " [Dict]".to_string()
-            }
-        }
-        "vortex.runend" => {
-            if let Some(rle_array) = array.as_any().downcast_ref::<vortex_runend::RunEndArray>() {
-                format!(" [RLE: {} runs]", rle_array.ends().len())
-            } else {
-                " [RLE]".to_string()
-            }
-        }
-        "vortex.sparse" => " [Sparse]".to_string(),
-        "vortex.alp" => " [ALP float compression]".to_string(),
-        "vortex.alprd" => " [ALP-RD]".to_string(),
-        "vortex.pco" => " [PCO quantile compression]".to_string(),
-        "vortex.for" => " [Frame-of-Reference]".to_string(),
-        "fastlanes.bitpacked" => " [Bit-packed]".to_string(),
-        "vortex.delta" => " [Delta]".to_string(),
-        "vortex.fsst" => " [FSST string compression]".to_string(),
-        "vortex.sequence" => " [Sequence]".to_string(),
-        "vortex.constant" => " [Constant]".to_string(),
-        _ => String::new(),
-    }
-}
-
-/// Extract column arrays from the tree structure
-fn extract_column_encodings_from_tree(array: &ArrayRef) -> Vec<ArrayRef> {
-    // Check if this is a struct
-    if let Some(struct_array) = array.as_any().downcast_ref::<StructArray>() {
-        return struct_array.fields().iter().cloned().collect();
-    }
-
-    // Recursively search children
-    for child in array.children() {
-        let result = extract_column_encodings_from_tree(&child);
-        if !result.is_empty() {
-            return result;
-        }
-    }
-
-    Vec::new()
-}
-
-/// Check if an array or its children contain a specific encoding
-fn contains_encoding(array: &ArrayRef, target_encoding: &str) -> bool {
-    // Check current array
-    if array.encoding_id().as_ref() == target_encoding {
-        return true;
-    }
-
-    // Recursively check children
-    for child in array.children() {
-        if contains_encoding(&child, target_encoding) {
-            return true;
-        }
-    }
-
-    false
-}
-
-/// Find columns that use a specific encoding (recursively search)
-fn find_columns_with_encoding(
-    array: &ArrayRef,
-    target_encoding: &str,
-    column_names: &vortex_dtype::FieldNames,
-) -> Vec<String> {
-    let mut result = Vec::new();
-
-    // Check if the entire array tree contains the target encoding
-    if contains_encoding(array, target_encoding) {
-        // If we find the encoding anywhere in the tree, report all columns
-        for name in column_names.iter() {
-            result.push(name.to_string());
-        }
-    } else {
-        // Try the old logic for non-chunked cases
-        let column_encodings = extract_column_encodings_from_tree(array);
-
-        if !column_encodings.is_empty() {
-            for (name, child) in column_names.iter().zip(column_encodings.iter()) {
-                if contains_encoding(child, target_encoding) {
-                    result.push(name.to_string());
-                }
-            }
-        }
-    }
-
-    result
-}
-
-/// Recursively analyzes and displays the encoding tree of an array
-fn analyze_encoding_tree(array: &ArrayRef, depth: usize) {
-    let indent = "  ".repeat(depth);
-    let encoding_id = array.encoding_id();
-
-    // Build compact encoding description
-    let encoding_desc = get_encoding_description(encoding_id.as_ref(), array);
-
-    // Display encoding information in one line
-    println!(
-        "{}└─ {} ({} bytes){}",
-        indent,
-        encoding_id,
-        array.nbytes(),
-        encoding_desc
-    );
-
-    // Check for child arrays (nested encodings)
-    let children = get_array_children(array);
-
-    if !children.is_empty() {
-        for child in children {
-            analyze_encoding_tree(&child, depth + 1);
-        }
-    }
-}
-
-/// Recursively analyzes and displays the encoding tree with column names
-fn analyze_encoding_tree_with_names(
-    array: &ArrayRef,
-    depth: usize,
-    column_names: &vortex_dtype::FieldNames,
-    show_first_struct_details: bool,
-) {
-    let indent = "  ".repeat(depth);
-    let encoding_id = array.encoding_id();
-
-    // Build compact encoding description
-    let encoding_desc = get_encoding_description(encoding_id.as_ref(), array);
-
-    // Display encoding information
-    println!(
-        "{}└─ {} ({} bytes){}",
-        indent,
-        encoding_id,
-        array.nbytes(),
-        encoding_desc
-    );
-
-    // Check if this is a struct by encoding ID
-    if encoding_id.as_ref() == "vortex.struct" {
-        // Get children (which are the struct fields)
-        let children = get_array_children(array);
-
-        if children.len() == column_names.len() {
-            if show_first_struct_details {
-                // Display each field with its column name (first struct only)
-                for (idx, (name, child)) in column_names.iter().zip(children.iter()).enumerate() {
-                    let prefix = if idx == column_names.len() - 1 {
-                        "└─"
-                    } else {
-                        "├─"
-                    };
-                    println!("{}  {} Column [{}]:", indent, prefix, name);
-                    analyze_encoding_tree(child, depth + 2);
-                }
-            } else {
-                // For subsequent structs, just show a summary
-                println!("{}  [Same structure: {} columns]", indent, children.len());
-            }
-            return;
-        }
-    }
-
-    // Not a struct or column count doesn't match, recurse into children
-    let children = get_array_children(array);
-
-    if !children.is_empty() {
-        // Track if we've already shown details for a struct
-        let mut shown_first_struct = !show_first_struct_details;
-
-        for child in children {
-            // Only show details for the first struct we encounter
-            let show_details = !shown_first_struct;
-            if child.encoding_id().as_ref() == "vortex.struct" && show_details {
-                shown_first_struct = true;
-            }
-
-            // Recursively call with names in case we find struct deeper in the tree
-            analyze_encoding_tree_with_names(&child, depth + 1, column_names, show_details);
-        }
-    }
-}
-
-async fn show_encoding(path: &Path, format: OutputFormat, verbose: bool) -> Result<()> {
-    let session = VortexSession::default();
-
-    // Open the file
-    let reader = session.open_options().open(path.to_path_buf()).await?;
-
-    // Read the array
-    let array = reader.scan()?.into_array_stream()?.read_all().await?;
-
-    match format {
-        OutputFormat::Json => {
-            let mut encoding_info = serde_json::json!({
-                "file": path.display().to_string(),
-                "row_count": reader.row_count(),
-                "root_encoding": array.encoding_id().as_ref(),
-            });
-
-            // Try to extract column information
-            if let Some(struct_fields) = reader.dtype().as_struct_fields_opt() {
-                let column_encodings = extract_column_encodings_from_tree(&array);
-
-                if !column_encodings.is_empty() {
-                    let mut columns = Vec::new();
-                    for (name, child) in struct_fields.names().iter().zip(column_encodings.iter()) {
-                        columns.push(serde_json::json!({
-                            "name": name,
-                            "encoding": child.encoding_id().as_ref(),
-                            "bytes": child.nbytes(),
-                        }));
-                    }
-                    encoding_info["columns"] = serde_json::Value::Array(columns);
-                }
-
-                // Add compression summary
-                let zstd_columns = find_columns_with_encoding(&array, "vortex.zstd", struct_fields.names());
-                encoding_info["zstd_compressed_columns"] = serde_json::Value::Array(
-                    zstd_columns.into_iter().map(serde_json::Value::String).collect()
-                );
-            }
-
-            println!("{}", serde_json::to_string_pretty(&encoding_info)?);
-        }
-        OutputFormat::Text => {
-            println!("=== Vortex File Encoding Inspection ===");
-            println!("File: {}", path.display());
-            println!();
-
-            println!("File Information:");
-            println!("  Rows: {}", reader.row_count());
-            println!("  DType: {}", reader.dtype());
-            println!();
-
-            println!("Root Array Encoding:");
-            println!("  {}", array.encoding_id());
-            println!();
-
-            // Try to extract column names from DType if it's a struct type
-            if let Some(struct_fields) = reader.dtype().as_struct_fields_opt() {
-                println!("Columns ({} total):\n", struct_fields.names().len());
-
-                // Try to get encodings from struct in the tree
-                let column_encodings = extract_column_encodings_from_tree(&array);
-
-                if !column_encodings.is_empty() {
-                    // Successfully found column encodings
-                    for (idx, (name, child)) in struct_fields
-                        .names()
-                        .iter()
-                        .zip(column_encodings.iter())
-                        .enumerate()
-                    {
-                        let prefix = if idx == column_encodings.len() - 1 {
-                            "└─"
-                        } else {
-                            "├─"
-                        };
-
-                        // Get encoding summary
-                        let enc_id = child.encoding_id();
-                        let enc_desc = get_encoding_description(enc_id.as_ref(), child);
-
-                        println!(
-                            "{} {} -> {} ({} bytes){}",
-                            prefix,
-                            name,
-                            enc_id,
-                            child.nbytes(),
-                            enc_desc
-                        );
-                    }
-                } else {
-                    // Couldn't extract column encodings, just show names
-                    for (idx, name) in struct_fields.names().iter().enumerate() {
-                        let prefix = if idx == struct_fields.names().len() - 1 {
-                            "└─"
-                        } else {
-                            "├─"
-                        };
-                        println!("{} {}", prefix, name);
-                    }
-                }
-
-                // Add a summary of zstd usage
-                println!("\n--- Compression Summary ---");
-                let zstd_columns = find_columns_with_encoding(&array, "vortex.zstd", struct_fields.names());
-                if !zstd_columns.is_empty() {
-                    println!("Zstd compressed columns: {}", zstd_columns.join(", "));
-                } else {
-                    println!("No zstd compressed columns found");
-                }
-
-                // Show detailed encoding tree
-                if verbose {
-                    println!("\n--- Detailed Encoding Tree ---");
-                    analyze_encoding_tree_with_names(&array, 0, struct_fields.names(), true);
-                }
-            } else {
-                // Not a struct type, show the root array tree
-                println!("Array Encodings:\n");
-                analyze_encoding_tree(&array, 0);
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_string_counts_characters() {
+        assert_eq!(truncate_string("short", 10), "short");
+        assert_eq!(truncate_string("exactly10!", 10), "exactly10!");
+        assert_eq!(truncate_string("a longer string", 10), "a longe...");
+        assert_eq!(truncate_string("héllo wörld", 11), "héllo wörld");
+        assert_eq!(truncate_string("héllo wörld", 8), "héllo...");
+        assert_eq!(truncate_string("日本語のテキスト", 5), "日本...");
+        assert_eq!(truncate_string("🦀🦀🦀", 3), "🦀🦀🦀");
+        assert_eq!(truncate_string("🦀🦀🦀🦀", 3), "...");
     }
-
-    Ok(())
 }
@@ -0,0 +1,283 @@
+use anyhow::Result;
+use arrow::datatypes::{DataType, Field, Schema};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use vortex_array::stats::{Precision, StatsSet};
+use vortex_dtype::{DType, DecimalDType, FieldName, FieldNames, PType, StructFields};
+use vortex_layout::LayoutRef;
+use vortex_scalar::Scalar;
+
+/// Version of the JSON output model, bumped on breaking changes
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema describing the JSON output of the inspection commands
+pub const JSON_SCHEMA: &str = include_str!("../schema/vortex-cli.schema.json");
+
+/// A Vortex dtype as a nested JSON object
+pub fn dtype_json(dtype: &DType) -> Value {
+    let nullable = dtype.is_nullable();
+
+    match dtype {
+        DType::Null => json!({ "kind": "null", "nullable": true }),
+        DType::Bool(_) => json!({ "kind": "bool", "nullable": nullable }),
+        DType::Primitive(ptype, _) => json!({
+            "kind": "primitive",
+            "ptype": ptype.to_string(),
+            "nullable": nullable,
+        }),
+        DType::Decimal(decimal, _) => json!({
+            "kind": "decimal",
+            "precision": decimal.precision(),
+            "scale": decimal.scale(),
+            "nullable": nullable,
+        }),
+        DType::Utf8(_) => json!({ "kind": "utf8", "nullable": nullable }),
+        DType::Binary(_) => json!({ "kind": "binary", "nullable": nullable }),
+        DType::Struct(fields, _) => json!({
+            "kind": "struct",
+            "nullable": nullable,
+            "fields": fields
+                .names()
+                .iter()
+                .zip(fields.fields())
+                .map(|(name, field)| json!({
+                    "name": name.to_string(),
+                    "dtype": dtype_json(&field),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        DType::List(element, _) => json!({
+            "kind": "list",
+            "nullable": nullable,
+            "element": dtype_json(element),
+        }),
+        DType::Extension(ext) => json!({
+            "kind": "extension",
+            "id": ext.id().to_string(),
+            "nullable": nullable,
+            "storage": dtype_json(ext.storage_dtype()),
+        }),
+        _ => json!({
+            "kind": "other",
+            "nullable": nullable,
+            "display": dtype.to_string(),
+        }),
+    }
+}
+
+fn ptype_from_str(ptype: &str) -> Result<PType> {
+    Ok(match ptype {
+        "u8" => PType::U8,
+        "u16" => PType::U16,
+        "u32" => PType::U32,
+        "u64" => PType::U64,
+        "i8" => PType::I8,
+        "i16" => PType::I16,
+        "i32" => PType::I32,
+        "i64" => PType::I64,
+        "f16" => PType::F16,
+        "f32" => PType::F32,
+        "f64" => PType::F64,
+        _ => anyhow::bail!("Unknown primitive type: {}", ptype),
+    })
+}
+
+/// Read back a dtype written by [`dtype_json`]
+///
+/// Extension and `other` dtypes cannot be reconstructed and are rejected.
+pub fn dtype_from_json(value: &Value) -> Result<DType> {
+    let field = |name: &str| {
+        value
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("dtype is missing \"{}\": {}", name, value))
+    };
+    let kind = field("kind")?
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("dtype \"kind\" must be a string"))?;
+    let nullable = value
+        .get("nullable")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+        .into();
+
+    Ok(match kind {
+        "null" => DType::Null,
+        "bool" => DType::Bool(nullable),
+        "primitive" => {
+            let ptype = field("ptype")?
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("dtype \"ptype\" must be a string"))?;
+            DType::Primitive(ptype_from_str(ptype)?, nullable)
+        }
+        "decimal" => {
+            let precision = field("precision")?
+                .as_u64()
+                .ok_or_else(|| anyhow::anyhow!("decimal precision must be an integer"))?;
+            let scale = field("scale")?
+                .as_i64()
+                .ok_or_else(|| anyhow::anyhow!("decimal scale must be an integer"))?;
+            DType::Decimal(
+                DecimalDType::new(u8::try_from(precision)?, i8::try_from(scale)?),
+                nullable,
+            )
+        }
+        "utf8" => DType::Utf8(nullable),
+        "binary" => DType::Binary(nullable),
+        "struct" => {
+            let fields = field("fields")?
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("struct \"fields\" must be an array"))?;
+
+            let mut names = Vec::with_capacity(fields.len());
+            let mut dtypes = Vec::with_capacity(fields.len());
+            for struct_field in fields {
+                let name = struct_field
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| anyhow::anyhow!("struct field is missing a name"))?;
+                let dtype = struct_field
+                    .get("dtype")
+                    .ok_or_else(|| anyhow::anyhow!("struct field {} is missing a dtype", name))?;
+                names.push(FieldName::from(name));
+                dtypes.push(dtype_from_json(dtype)?);
+            }
+
+            DType::Struct(
+                StructFields::new(names.into_iter().collect::<FieldNames>(), dtypes),
+                nullable,
+            )
+        }
+        "list" => DType::List(Arc::new(dtype_from_json(field("element")?)?), nullable),
+        _ => anyhow::bail!("dtype kind \"{}\" cannot be read back", kind),
+    })
+}
+
+fn arrow_field_json(field: &Field) -> Value {
+    let mut field_json = json!({
+        "name": field.name(),
+        "data_type": field.data_type().to_string(),
+        "nullable": field.is_nullable(),
+        "metadata": field.metadata(),
+    });
+
+    match field.data_type() {
+        DataType::Struct(children) => {
+            field_json["fields"] = children.iter().map(|f| arrow_field_json(f)).collect();
+        }
+        DataType::List(child) | DataType::LargeList(child) | DataType::ListView(child) => {
+            field_json["fields"] = json!([arrow_field_json(child)]);
+        }
+        DataType::FixedSizeList(child, _) => {
+            field_json["fields"] = json!([arrow_field_json(child)]);
+        }
+        _ => {}
+    }
+
+    field_json
+}
+
+/// An Arrow schema as an array of fields
+pub fn arrow_schema_json(schema: &Schema) -> Value {
+    json!({
+        "fields": schema.fields().iter().map(|f| arrow_field_json(f)).collect::<Vec<_>>(),
+        "metadata": schema.metadata(),
+    })
+}
+
+/// A layout as a recursive tree
+pub fn layout_json(layout: &LayoutRef) -> Value {
+    let children: Vec<Value> = (0..layout.nchildren())
+        .filter_map(|idx| {
+            let child = layout.child(idx).ok()?;
+            let mut child_json = layout_json(&child);
+            child_json["name"] = json!(layout.child_type(idx).name().to_string());
+            Some(child_json)
+        })
+        .collect();
+
+    json!({
+        "encoding": layout.encoding().to_string(),
+        "row_count": layout.row_count(),
+        "dtype": dtype_json(layout.dtype()),
+        "segment_ids": layout.segment_ids().iter().map(|id| **id).collect::<Vec<u32>>(),
+        "metadata_bytes": layout.metadata().len(),
+        "children": children,
+    })
+}
+
+/// A scalar as a typed JSON value
+///
+/// Numbers and booleans keep their JSON type, other types use their display form.
+pub fn scalar_json(scalar: &Scalar) -> Value {
+    if scalar.is_null() {
+        return Value::Null;
+    }
+
+    match scalar.dtype() {
+        DType::Bool(_) => json!(scalar.as_bool().value()),
+        DType::Primitive(ptype, _) => {
+            let primitive = scalar.as_primitive();
+            if ptype.is_float() {
+                json!(primitive.as_::<f64>())
+            } else if ptype.is_unsigned_int() {
+                json!(primitive.as_::<u64>())
+            } else {
+                json!(primitive.as_::<i64>())
+            }
+        }
+        DType::Utf8(_) => json!(scalar.as_utf8().value().map(|v| v.as_str().to_string())),
+        _ => json!(scalar.to_string()),
+    }
+}
+
+/// A set of statistics as typed values, given the dtype they describe
+pub fn stats_set_json(stats: &StatsSet, dtype: &DType) -> Value {
+    let values: Vec<Value> = stats
+        .iter()
+        .map(|(stat, precision)| {
+            let (value, exact) = match precision {
+                Precision::Exact(value) => (value, true),
+                Precision::Inexact(value) => (value, false),
+            };
+
+            let value = stat
+                .dtype(dtype)
+                .map(|stat_dtype| scalar_json(&Scalar::new(stat_dtype, value.clone())))
+                .unwrap_or(Value::Null);
+
+            json!({
+                "stat": stat.to_string(),
+                "value": value,
+                "exact": exact,
+            })
+        })
+        .collect();
+
+    Value::Array(values)
+}
+
+/// File-level statistics, one entry per top-level field
+pub fn file_stats_json(stats: &[StatsSet], dtype: &DType) -> Value {
+    let fields: Vec<(String, DType)> = match dtype.as_struct_fields_opt() {
+        Some(fields) => fields
+            .names()
+            .iter()
+            .zip(fields.fields())
+            .map(|(name, field)| (name.to_string(), field))
+            .collect(),
+        None => vec![("<root>".to_string(), dtype.clone())],
+    };
+
+    Value::Array(
+        fields
+            .iter()
+            .zip(stats.iter())
+            .map(|((name, field_dtype), stats_set)| {
+                json!({
+                    "name": name,
+                    "stats": stats_set_json(stats_set, field_dtype),
+                })
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,1631 @@
+use anyhow::{Context, Result};
+use arrow::array::{Array as ArrowArray, ArrayRef, AsArray, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type, Int64Type, SchemaRef, TimeUnit};
+use datafusion::common::ScalarValue;
+use datafusion::execution::object_store::ObjectStoreUrl;
+use datafusion::logical_expr::{Expr as DfExpr, Operator, binary_expr, ident, lit};
+use datafusion::prelude::SessionContext;
+use object_store::local::LocalFileSystem;
+use promql_parser::label::{MatchOp, Matcher};
+use promql_parser::parser::{
+    self, AggregateExpr, BinModifier, BinaryExpr, Call, Expr, LabelModifier, Offset,
+    VectorMatchCardinality, VectorSelector,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::footer_cache::FooterCache;
+use crate::query::register_vortex_table;
+use crate::{DocumentFormat, print_json};
+
+/// Name of the DataFusion table holding all metric files
+const METRICS_TABLE: &str = "metrics";
+
+/// Label holding the metric name in PromQL
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// Default lookback for instant vector selectors, same as Prometheus
+const LOOKBACK_DELTA_MS: i64 = 5 * 60 * 1000;
+
+/// Column mapping for metric data stored in Vortex files
+#[derive(clap::Args, Clone, Debug)]
+pub struct MetricColumns {
+    /// Column holding the sample timestamp
+    #[arg(long, default_value = "_timestamp")]
+    pub timestamp_column: String,
+
+    /// Unit of an integer timestamp column (s, ms, us or ns)
+    #[arg(long, default_value = "us", value_parser = parse_time_unit)]
+    pub timestamp_unit: TimeUnit,
+
+    /// Column holding the sample value
+    #[arg(long, default_value = "value")]
+    pub value_column: String,
+
+    /// Column holding the metric name
+    #[arg(long, default_value = METRIC_NAME_LABEL)]
+    pub metric_column: String,
+}
+
+fn parse_time_unit(s: &str) -> std::result::Result<TimeUnit, String> {
+    match s.to_lowercase().as_str() {
+        "s" => Ok(TimeUnit::Second),
+        "ms" => Ok(TimeUnit::Millisecond),
+        "us" => Ok(TimeUnit::Microsecond),
+        "ns" => Ok(TimeUnit::Nanosecond),
+        _ => Err(format!(
+            "Invalid timestamp unit: {}. Use 's', 'ms', 'us' or 'ns'",
+            s
+        )),
+    }
+}
+
+/// Parse a PromQL API time: RFC 3339, unix seconds or "now", in milliseconds
+pub fn parse_time(s: &str) -> Result<i64> {
+    if s == "now" {
+        return Ok(now_ms());
+    }
+
+    if let Ok(secs) = s.parse::<f64>() {
+        return Ok((secs * 1000.0).round() as i64);
+    }
+
+    let time = chrono::DateTime::parse_from_rfc3339(s)
+        .map_err(|_| anyhow::anyhow!("Invalid time: {}. Use RFC 3339 or unix seconds", s))?;
+    Ok(time.timestamp_millis())
+}
+
+/// Parse a step or range: PromQL duration (e.g. "15s", "1m") or seconds
+pub fn parse_step(s: &str) -> Result<i64> {
+    if let Ok(secs) = s.parse::<f64>() {
+        return Ok((secs * 1000.0).round() as i64);
+    }
+
+    let duration = promql_parser::util::parse_duration(s)
+        .map_err(|e| anyhow::anyhow!("Invalid duration {}: {}", s, e))?;
+    Ok(duration.as_millis() as i64)
+}
+
+pub fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn duration_ms(duration: &Duration) -> i64 {
+    duration.as_millis() as i64
+}
+
+/// Label set of a series, sorted by label name
+pub type Labels = BTreeMap<String, String>;
+
+/// A series with its samples as (timestamp ms, value), sorted by timestamp
+#[derive(Clone, Debug)]
+pub struct Series {
+    pub labels: Labels,
+    pub samples: Vec<(i64, f64)>,
+}
+
+/// A single sample of an instant vector
+#[derive(Clone, Debug)]
+pub struct Sample {
+    pub labels: Labels,
+    pub value: f64,
+}
+
+/// Result of evaluating an expression at a single timestamp
+#[derive(Clone, Debug)]
+enum Value {
+    Scalar(f64),
+    Vector(Vec<Sample>),
+    Matrix(Vec<Series>),
+}
+
+/// Result of a PromQL query, in the shape of Prometheus's query API
+#[derive(Clone, Debug)]
+pub enum QueryResult {
+    Scalar(i64, f64),
+    Vector(i64, Vec<Sample>),
+    Matrix(Vec<Series>),
+}
+
+/// Metric samples stored in a set of Vortex files, queried through DataFusion
+pub struct MetricStore {
+    ctx: SessionContext,
+    schema: SchemaRef,
+    columns: MetricColumns,
+    footers: Arc<FooterCache>,
+}
+
+impl MetricStore {
+    /// Register a Vortex file or a directory of Vortex files as the metric table
+    ///
+    /// Local files are read through a footer cache, so every query after the
+    /// first reuses the footers instead of reading them again.
+    pub async fn open(path: &Path, columns: MetricColumns) -> Result<Self> {
+        let ctx = SessionContext::new();
+        let session = crate::create_session();
+
+        let footers = Arc::new(FooterCache::new(Arc::new(LocalFileSystem::new())));
+        ctx.register_object_store(ObjectStoreUrl::local_filesystem().as_ref(), footers.clone());
+
+        // Directories must end with a separator to be listed as a table
+        let mut table_path = std::fs::canonicalize(path)
+            .context(format!("Failed to resolve path: {}", path.display()))?
+            .into_os_string();
+        if path.is_dir() {
+            table_path.push(std::path::MAIN_SEPARATOR_STR);
+        }
+
+        register_vortex_table(&ctx, &session, METRICS_TABLE, &PathBuf::from(table_path)).await?;
+
+        let schema = ctx.table(METRICS_TABLE).await?.schema().inner().clone();
+
+        for column in [&columns.timestamp_column, &columns.value_column] {
+            if schema.field_with_name(column).is_err() {
+                anyhow::bail!("Column '{}' not found in metric files", column);
+            }
+        }
+
+        Ok(Self {
+            ctx,
+            schema,
+            columns,
+            footers,
+        })
+    }
+
+    /// Number of file footers read since the store was opened
+    #[cfg(test)]
+    pub fn footer_reads(&self) -> usize {
+        self.footers.footer_reads()
+    }
+
+    /// Names of the string columns used as series labels
+    ///
+    /// Columns starting with `__` are internal, except for the metric name.
+    pub fn label_columns(&self) -> Vec<String> {
+        self.schema
+            .fields()
+            .iter()
+            .filter(|field| {
+                let name = field.name();
+                name != &self.columns.timestamp_column
+                    && name != &self.columns.value_column
+                    && (name == &self.columns.metric_column || !name.starts_with("__"))
+                    && is_string_type(field.data_type())
+            })
+            .map(|field| field.name().clone())
+            .collect()
+    }
+
+    /// Map a PromQL label name to its column
+    fn label_to_column<'a>(&'a self, label: &'a str) -> &'a str {
+        if label == METRIC_NAME_LABEL {
+            &self.columns.metric_column
+        } else {
+            label
+        }
+    }
+
+    /// Map a column to its PromQL label name
+    fn column_to_label<'a>(&'a self, column: &'a str) -> &'a str {
+        if column == self.columns.metric_column {
+            METRIC_NAME_LABEL
+        } else {
+            column
+        }
+    }
+
+    /// Convert a millisecond timestamp into a literal of the timestamp column type
+    fn timestamp_literal(&self, ms: i64) -> Result<DfExpr> {
+        let data_type = self
+            .schema
+            .field_with_name(&self.columns.timestamp_column)?
+            .data_type();
+        let unit = match data_type {
+            DataType::Timestamp(unit, _) => *unit,
+            _ => self.columns.timestamp_unit,
+        };
+
+        let raw = match unit {
+            TimeUnit::Second => ms.div_euclid(1000),
+            TimeUnit::Millisecond => ms,
+            TimeUnit::Microsecond => ms.saturating_mul(1_000),
+            TimeUnit::Nanosecond => ms.saturating_mul(1_000_000),
+        };
+
+        Ok(lit(ScalarValue::Int64(Some(raw)).cast_to(data_type)?))
+    }
+
+    /// Translate a label matcher into a column filter
+    ///
+    /// Returns `None` if the matcher is always true and `Some(lit(false))` if it never matches.
+    fn matcher_filter(&self, matcher: &Matcher) -> Option<DfExpr> {
+        let column = self.label_to_column(&matcher.name);
+        let matches_empty = matcher.is_match("");
+
+        if self.schema.field_with_name(column).is_err() {
+            // A missing label has the empty value
+            return if matches_empty {
+                None
+            } else {
+                Some(lit(false))
+            };
+        }
+
+        // Nulls mean the label is missing, so treat them as empty strings
+        let value = if matches_empty {
+            datafusion::functions::expr_fn::coalesce(vec![ident(column), lit("")])
+        } else {
+            ident(column)
+        };
+
+        Some(match &matcher.op {
+            MatchOp::Equal => value.eq(lit(matcher.value.clone())),
+            MatchOp::NotEqual => value.not_eq(lit(matcher.value.clone())),
+            MatchOp::Re(_) => binary_expr(
+                value,
+                Operator::RegexMatch,
+                lit(format!("^(?:{})$", matcher.value)),
+            ),
+            MatchOp::NotRe(_) => binary_expr(
+                value,
+                Operator::RegexNotMatch,
+                lit(format!("^(?:{})$", matcher.value)),
+            ),
+        })
+    }
+
+    /// Build the column filters for a set of matchers
+    fn matchers_filter(&self, matchers: &[Matcher]) -> DfExpr {
+        matchers
+            .iter()
+            .filter_map(|m| self.matcher_filter(m))
+            .fold(lit(true), |acc, expr| acc.and(expr))
+    }
+
+    /// Filter for samples matching a selector in `[start_ms, end_ms]`
+    fn selection_filter(&self, matchers: &[Matcher], start_ms: i64, end_ms: i64) -> Result<DfExpr> {
+        Ok(self
+            .matchers_filter(matchers)
+            .and(ident(&self.columns.timestamp_column).gt_eq(self.timestamp_literal(start_ms)?))
+            .and(ident(&self.columns.timestamp_column).lt_eq(self.timestamp_literal(end_ms)?)))
+    }
+
+    /// Names of all labels, with the metric column reported as `__name__`
+    pub fn label_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .label_columns()
+            .iter()
+            .map(|column| self.column_to_label(column).to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Label sets of the series matching a selector in `[start_ms, end_ms]`
+    ///
+    /// Only the label columns are scanned.
+    pub async fn series(
+        &self,
+        matchers: &[Matcher],
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Result<Vec<Labels>> {
+        let label_columns = self.label_columns();
+        let filter = self.selection_filter(matchers, start_ms, end_ms)?;
+
+        let batches = self
+            .ctx
+            .table(METRICS_TABLE)
+            .await?
+            .filter(filter)?
+            .select(label_columns.iter().map(ident).collect())?
+            .distinct()?
+            .collect()
+            .await?;
+
+        let mut series = Vec::new();
+        for batch in &batches {
+            let labels = (0..label_columns.len())
+                .map(|idx| cast(batch.column(idx), &DataType::Utf8))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            for row in 0..batch.num_rows() {
+                series.push(self.row_labels(&label_columns, &labels, row));
+            }
+        }
+
+        // Distinct label columns can still collapse to the same set when null and empty mix
+        series.sort();
+        series.dedup();
+        Ok(series)
+    }
+
+    /// Labels of a row, skipping missing and empty values
+    fn row_labels(&self, label_columns: &[String], arrays: &[ArrayRef], row: usize) -> Labels {
+        let mut labels = Labels::new();
+        for (column, array) in label_columns.iter().zip(arrays.iter()) {
+            let array = array.as_string::<i32>();
+            if !array.is_null(row) && !array.value(row).is_empty() {
+                labels.insert(
+                    self.column_to_label(column).to_string(),
+                    array.value(row).to_string(),
+                );
+            }
+        }
+        labels
+    }
+
+    /// Load series matching a selector with samples in `[start_ms, end_ms]`
+    ///
+    /// Only the timestamp, value and label columns are scanned, and the matchers and
+    /// time range are pushed down into the Vortex scan.
+    pub async fn select(
+        &self,
+        matchers: &[Matcher],
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Result<Vec<Series>> {
+        let label_columns = self.label_columns();
+        let filter = self.selection_filter(matchers, start_ms, end_ms)?;
+
+        let mut projection = vec![
+            ident(&self.columns.timestamp_column),
+            ident(&self.columns.value_column),
+        ];
+        projection.extend(label_columns.iter().map(ident));
+
+        let batches = self
+            .ctx
+            .table(METRICS_TABLE)
+            .await?
+            .filter(filter)?
+            .select(projection)?
+            .collect()
+            .await?;
+
+        let mut series: HashMap<Labels, Vec<(i64, f64)>> = HashMap::new();
+
+        for batch in &batches {
+            let timestamps = self.batch_timestamps_ms(batch)?;
+            let values = cast(batch.column(1), &DataType::Float64)?;
+            let values = values.as_primitive::<Float64Type>();
+
+            let labels = (0..label_columns.len())
+                .map(|idx| cast(batch.column(idx + 2), &DataType::Utf8))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            for row in 0..batch.num_rows() {
+                if values.is_null(row) {
+                    continue;
+                }
+
+                series
+                    .entry(self.row_labels(&label_columns, &labels, row))
+                    .or_default()
+                    .push((timestamps[row], values.value(row)));
+            }
+        }
+
+        Ok(series
+            .into_iter()
+            .map(|(labels, mut samples)| {
+                samples.sort_by_key(|(ts, _)| *ts);
+                Series { labels, samples }
+            })
+            .collect())
+    }
+
+    /// Timestamps of the first column of a batch in milliseconds
+    fn batch_timestamps_ms(&self, batch: &RecordBatch) -> Result<Vec<i64>> {
+        let column = batch.column(0);
+        let unit = match column.data_type() {
+            DataType::Timestamp(unit, _) => *unit,
+            _ => self.columns.timestamp_unit,
+        };
+
+        let raw = cast(column, &DataType::Int64)?;
+        let raw = raw.as_primitive::<Int64Type>();
+
+        Ok(raw
+            .values()
+            .iter()
+            .map(|&v| match unit {
+                TimeUnit::Second => v.saturating_mul(1000),
+                TimeUnit::Millisecond => v,
+                TimeUnit::Microsecond => v.div_euclid(1_000),
+                TimeUnit::Nanosecond => v.div_euclid(1_000_000),
+            })
+            .collect())
+    }
+}
+
+fn is_string_type(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => true,
+        DataType::Dictionary(_, value) => is_string_type(value),
+        _ => false,
+    }
+}
+
+/// Selector data preloaded for a query, keyed by the selector's text
+struct Evaluator {
+    selectors: HashMap<String, Vec<Series>>,
+}
+
+/// Collect every selector in an expression with how far back it looks
+fn collect_selectors(expr: &Expr, out: &mut Vec<(VectorSelector, i64)>) -> Result<()> {
+    match expr {
+        Expr::VectorSelector(vs) => out.push((vs.clone(), LOOKBACK_DELTA_MS)),
+        Expr::MatrixSelector(ms) => out.push((ms.vs.clone(), duration_ms(&ms.range))),
+        Expr::Aggregate(agg) => {
+            collect_selectors(&agg.expr, out)?;
+            if let Some(param) = &agg.param {
+                collect_selectors(param, out)?;
+            }
+        }
+        Expr::Call(call) => {
+            for arg in &call.args.args {
+                collect_selectors(arg, out)?;
+            }
+        }
+        Expr::Binary(binary) => {
+            collect_selectors(&binary.lhs, out)?;
+            collect_selectors(&binary.rhs, out)?;
+        }
+        Expr::Paren(paren) => collect_selectors(&paren.expr, out)?,
+        Expr::Unary(unary) => collect_selectors(&unary.expr, out)?,
+        Expr::NumberLiteral(_) | Expr::StringLiteral(_) => {}
+        Expr::Subquery(_) => anyhow::bail!("Subqueries are not supported"),
+        _ => anyhow::bail!("Unsupported expression: {}", expr),
+    }
+
+    Ok(())
+}
+
+/// Offset of a selector in milliseconds, positive values look into the past
+fn offset_ms(vs: &VectorSelector) -> i64 {
+    match &vs.offset {
+        Some(Offset::Pos(d)) => duration_ms(d),
+        Some(Offset::Neg(d)) => -duration_ms(d),
+        None => 0,
+    }
+}
+
+/// All matchers of a selector, including the metric name
+pub fn selector_matchers(vs: &VectorSelector) -> Vec<Matcher> {
+    let mut matchers = vs.matchers.matchers.clone();
+    if let Some(name) = &vs.name
+        && !matchers.iter().any(|m| m.name == METRIC_NAME_LABEL)
+    {
+        matchers.push(Matcher::new(MatchOp::Equal, METRIC_NAME_LABEL, name));
+    }
+    matchers
+}
+
+impl Evaluator {
+    /// Load all series referenced by an expression for evaluation in `[start_ms, end_ms]`
+    async fn load(store: &MetricStore, expr: &Expr, start_ms: i64, end_ms: i64) -> Result<Self> {
+        let mut found = Vec::new();
+        collect_selectors(expr, &mut found)?;
+
+        // Merge the windows of selectors that appear more than once
+        let mut windows: HashMap<String, (VectorSelector, i64, i64)> = HashMap::new();
+        for (vs, range) in found {
+            if vs.at.is_some() {
+                anyhow::bail!("The @ modifier is not supported");
+            }
+
+            let offset = offset_ms(&vs);
+            let window_start = start_ms - offset - range;
+            let window_end = end_ms - offset;
+
+            windows
+                .entry(vs.to_string())
+                .and_modify(|(_, s, e)| {
+                    *s = (*s).min(window_start);
+                    *e = (*e).max(window_end);
+                })
+                .or_insert((vs, window_start, window_end));
+        }
+
+        let mut selectors = HashMap::new();
+        for (key, (vs, window_start, window_end)) in windows {
+            let series = store
+                .select(&selector_matchers(&vs), window_start, window_end)
+                .await?;
+            selectors.insert(key, series);
+        }
+
+        Ok(Self { selectors })
+    }
+
+    /// Samples of a selector in the left-open range `(t - range, t]`
+    fn select_range(&self, vs: &VectorSelector, t: i64, range: i64) -> Vec<Series> {
+        let end = t - offset_ms(vs);
+        let start = end - range;
+
+        self.selectors
+            .get(&vs.to_string())
+            .map(|series| {
+                series
+                    .iter()
+                    .filter_map(|s| {
+                        let samples: Vec<_> = s
+                            .samples
+                            .iter()
+                            .filter(|(ts, _)| *ts > start && *ts <= end)
+                            .copied()
+                            .collect();
+                        (!samples.is_empty()).then(|| Series {
+                            labels: s.labels.clone(),
+                            samples,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn eval(&self, expr: &Expr, t: i64) -> Result<Value> {
+        match expr {
+            Expr::NumberLiteral(n) => Ok(Value::Scalar(n.val)),
+            Expr::Paren(paren) => self.eval(&paren.expr, t),
+            Expr::Unary(unary) => match self.eval(&unary.expr, t)? {
+                Value::Scalar(v) => Ok(Value::Scalar(-v)),
+                Value::Vector(samples) => Ok(Value::Vector(
+                    samples
+                        .into_iter()
+                        .map(|s| Sample {
+                            labels: drop_metric_name(s.labels),
+                            value: -s.value,
+                        })
+                        .collect(),
+                )),
+                Value::Matrix(_) => anyhow::bail!("Unary minus is not supported on range vectors"),
+            },
+            Expr::VectorSelector(vs) => Ok(Value::Vector(
+                self.select_range(vs, t, LOOKBACK_DELTA_MS)
+                    .into_iter()
+                    .filter_map(|s| {
+                        s.samples.last().map(|(_, value)| Sample {
+                            labels: s.labels,
+                            value: *value,
+                        })
+                    })
+                    .collect(),
+            )),
+            Expr::MatrixSelector(ms) => Ok(Value::Matrix(self.select_range(
+                &ms.vs,
+                t,
+                duration_ms(&ms.range),
+            ))),
+            Expr::Call(call) => self.eval_call(call, t),
+            Expr::Aggregate(agg) => self.eval_aggregate(agg, t),
+            Expr::Binary(binary) => self.eval_binary(binary, t),
+            _ => anyhow::bail!("Unsupported expression: {}", expr),
+        }
+    }
+
+    fn eval_call(&self, call: &Call, t: i64) -> Result<Value> {
+        let name = call.func.name;
+        let args = &call.args.args;
+
+        match name {
+            "rate" | "increase" | "delta" | "irate" | "idelta" | "avg_over_time"
+            | "sum_over_time" | "min_over_time" | "max_over_time" | "count_over_time"
+            | "last_over_time" => {
+                let Some(Expr::MatrixSelector(ms)) = args.first().map(|a| a.as_ref()) else {
+                    anyhow::bail!("{} expects a range vector selector", name);
+                };
+                let range = duration_ms(&ms.range);
+                let range_end = t - offset_ms(&ms.vs);
+                let range_start = range_end - range;
+
+                let samples = self
+                    .select_range(&ms.vs, t, range)
+                    .into_iter()
+                    .filter_map(|s| {
+                        let value = match name {
+                            "rate" => {
+                                extrapolated_rate(&s.samples, range_start, range_end, true, true)
+                            }
+                            "increase" => {
+                                extrapolated_rate(&s.samples, range_start, range_end, true, false)
+                            }
+                            "delta" => {
+                                extrapolated_rate(&s.samples, range_start, range_end, false, false)
+                            }
+                            "irate" => instant_delta(&s.samples, true),
+                            "idelta" => instant_delta(&s.samples, false),
+                            _ => over_time(name, &s.samples),
+                        }?;
+
+                        let labels = if name == "last_over_time" {
+                            s.labels
+                        } else {
+                            drop_metric_name(s.labels)
+                        };
+                        Some(Sample { labels, value })
+                    })
+                    .collect();
+
+                Ok(Value::Vector(samples))
+            }
+            "histogram_quantile" => {
+                let (Some(phi), Some(input)) = (args.first(), args.get(1)) else {
+                    anyhow::bail!("histogram_quantile expects 2 arguments");
+                };
+                let Value::Scalar(phi) = self.eval(phi, t)? else {
+                    anyhow::bail!("histogram_quantile expects a scalar quantile");
+                };
+                let Value::Vector(samples) = self.eval(input, t)? else {
+                    anyhow::bail!("histogram_quantile expects an instant vector");
+                };
+
+                Ok(Value::Vector(histogram_quantile(phi, samples)))
+            }
+            "abs" | "ceil" | "floor" | "round" | "sqrt" | "exp" | "ln" | "log2" | "log10" => {
+                let Some(input) = args.first() else {
+                    anyhow::bail!("{} expects 1 argument", name);
+                };
+                let Value::Vector(samples) = self.eval(input, t)? else {
+                    anyhow::bail!("{} expects an instant vector", name);
+                };
+
+                let f: fn(f64) -> f64 = match name {
+                    "abs" => f64::abs,
+                    "ceil" => f64::ceil,
+                    "floor" => f64::floor,
+                    "round" => f64::round,
+                    "sqrt" => f64::sqrt,
+                    "exp" => f64::exp,
+                    "ln" => f64::ln,
+                    "log2" => f64::log2,
+                    _ => f64::log10,
+                };
+
+                Ok(Value::Vector(
+                    samples
+                        .into_iter()
+                        .map(|s| Sample {
+                            labels: drop_metric_name(s.labels),
+                            value: f(s.value),
+                        })
+                        .collect(),
+                ))
+            }
+            "scalar" => {
+                let Some(input) = args.first() else {
+                    anyhow::bail!("scalar expects 1 argument");
+                };
+                let Value::Vector(samples) = self.eval(input, t)? else {
+                    anyhow::bail!("scalar expects an instant vector");
+                };
+                Ok(Value::Scalar(match samples.as_slice() {
+                    [sample] => sample.value,
+                    _ => f64::NAN,
+                }))
+            }
+            "time" => Ok(Value::Scalar(t as f64 / 1000.0)),
+            _ => anyhow::bail!("Unsupported function: {}", name),
+        }
+    }
+
+    fn eval_aggregate(&self, agg: &AggregateExpr, t: i64) -> Result<Value> {
+        let op = agg.op.to_string().to_lowercase();
+        let Value::Vector(samples) = self.eval(&agg.expr, t)? else {
+            anyhow::bail!("{} expects an instant vector", op);
+        };
+
+        let mut groups: BTreeMap<Labels, Vec<f64>> = BTreeMap::new();
+        for sample in samples {
+            let labels = match &agg.modifier {
+                Some(LabelModifier::Include(by)) => sample
+                    .labels
+                    .into_iter()
+                    .filter(|(k, _)| by.labels.contains(k))
+                    .collect(),
+                Some(LabelModifier::Exclude(without)) => sample
+                    .labels
+                    .into_iter()
+                    .filter(|(k, _)| k != METRIC_NAME_LABEL && !without.labels.contains(k))
+                    .collect(),
+                None => Labels::new(),
+            };
+            groups.entry(labels).or_default().push(sample.value);
+        }
+
+        let samples = groups
+            .into_iter()
+            .map(|(labels, values)| {
+                let value = match op.as_str() {
+                    "sum" => values.iter().sum(),
+                    "avg" => values.iter().sum::<f64>() / values.len() as f64,
+                    "min" => values.iter().copied().fold(f64::NAN, f64::min),
+                    "max" => values.iter().copied().fold(f64::NAN, f64::max),
+                    "count" => values.len() as f64,
+                    "stddev" | "stdvar" => {
+                        let mean = values.iter().sum::<f64>() / values.len() as f64;
+                        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>()
+                            / values.len() as f64;
+                        if op == "stddev" { var.sqrt() } else { var }
+                    }
+                    _ => anyhow::bail!("Unsupported aggregation: {}", op),
+                };
+                Ok(Sample { labels, value })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Value::Vector(samples))
+    }
+
+    fn eval_binary(&self, binary: &BinaryExpr, t: i64) -> Result<Value> {
+        let op = binary.op.to_string();
+        let return_bool = binary.modifier.as_ref().is_some_and(|m| m.return_bool);
+        if return_bool && !is_comparison(&op) {
+            anyhow::bail!("bool modifier can only be used on comparison operators");
+        }
+
+        let lhs = self.eval(&binary.lhs, t)?;
+        let rhs = self.eval(&binary.rhs, t)?;
+
+        match (lhs, rhs) {
+            (Value::Scalar(l), Value::Scalar(r)) => {
+                if is_comparison(&op) && !return_bool {
+                    anyhow::bail!("Comparisons between scalars must use the bool modifier");
+                }
+                Ok(Value::Scalar(apply_binary_op(&op, l, r)?))
+            }
+            (Value::Vector(samples), Value::Scalar(r)) => Ok(Value::Vector(vector_scalar_op(
+                &op,
+                samples,
+                return_bool,
+                |l| (l, r),
+            )?)),
+            (Value::Scalar(l), Value::Vector(samples)) => Ok(Value::Vector(vector_scalar_op(
+                &op,
+                samples,
+                return_bool,
+                |r| (l, r),
+            )?)),
+            (Value::Vector(lhs), Value::Vector(rhs)) => Ok(Value::Vector(vector_vector_op(
+                &op,
+                lhs,
+                rhs,
+                binary.modifier.as_ref(),
+            )?)),
+            _ => anyhow::bail!("Binary operator {} is not supported on range vectors", op),
+        }
+    }
+}
+
+fn is_comparison(op: &str) -> bool {
+    matches!(op, "==" | "!=" | ">" | "<" | ">=" | "<=")
+}
+
+/// Apply a binary operator, comparisons return 1.0 for true and 0.0 for false
+fn apply_binary_op(op: &str, l: f64, r: f64) -> Result<f64> {
+    Ok(match op {
+        "+" => l + r,
+        "-" => l - r,
+        "*" => l * r,
+        "/" => l / r,
+        "%" => l % r,
+        "^" => l.powf(r),
+        "==" => (l == r) as u8 as f64,
+        "!=" => (l != r) as u8 as f64,
+        ">" => (l > r) as u8 as f64,
+        "<" => (l < r) as u8 as f64,
+        ">=" => (l >= r) as u8 as f64,
+        "<=" => (l <= r) as u8 as f64,
+        _ => anyhow::bail!("Unsupported binary operator: {}", op),
+    })
+}
+
+/// Apply a binary operator between each sample and a scalar
+///
+/// `operands` maps a sample value to the (lhs, rhs) pair of the operation.
+fn vector_scalar_op(
+    op: &str,
+    samples: Vec<Sample>,
+    return_bool: bool,
+    operands: impl Fn(f64) -> (f64, f64),
+) -> Result<Vec<Sample>> {
+    let mut result = Vec::new();
+
+    for sample in samples {
+        let (l, r) = operands(sample.value);
+        let value = apply_binary_op(op, l, r)?;
+
+        if is_comparison(op) && !return_bool {
+            // Comparisons filter the vector and keep the original sample
+            if value != 0.0 {
+                result.push(sample);
+            }
+        } else {
+            result.push(Sample {
+                labels: drop_metric_name(sample.labels),
+                value,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Apply a binary operator between two instant vectors, matching their samples by labels
+///
+/// Samples match on all labels except the metric name, or on the labels selected by
+/// `on`/`ignoring`. Matching is one-to-one unless `group_left`/`group_right` make one side
+/// the "many" side, whose samples may share a match with a single sample of the other side.
+fn vector_vector_op(
+    op: &str,
+    lhs: Vec<Sample>,
+    rhs: Vec<Sample>,
+    modifier: Option<&BinModifier>,
+) -> Result<Vec<Sample>> {
+    let matching = modifier.and_then(|m| m.matching.as_ref());
+    let return_bool = modifier.is_some_and(|m| m.return_bool);
+
+    // group_right swaps the sides so the "many" side is always iterated
+    let (many, one, include, swapped) = match modifier.map(|m| &m.card) {
+        None | Some(VectorMatchCardinality::OneToOne) => (lhs, rhs, None, false),
+        Some(VectorMatchCardinality::ManyToOne(include)) => {
+            (lhs, rhs, Some(&include.labels), false)
+        }
+        Some(VectorMatchCardinality::OneToMany(include)) => (rhs, lhs, Some(&include.labels), true),
+        Some(VectorMatchCardinality::ManyToMany) => {
+            anyhow::bail!("Set operator {} is not supported", op)
+        }
+    };
+
+    let mut one_side: HashMap<Labels, Sample> = HashMap::new();
+    for sample in one {
+        let signature = matching_labels(&sample.labels, matching);
+        if one_side.insert(signature, sample).is_some() {
+            anyhow::bail!(
+                "Found duplicate series for the match group on the {} side of {}, \
+                 many-to-many matching is not supported",
+                if swapped { "left" } else { "right" },
+                op
+            );
+        }
+    }
+
+    let mut matched = HashSet::new();
+    let mut samples = Vec::new();
+    for sample in many {
+        let signature = matching_labels(&sample.labels, matching);
+        let Some(other) = one_side.get(&signature) else {
+            continue;
+        };
+        if include.is_none() && !matched.insert(signature) {
+            anyhow::bail!(
+                "Multiple matches for the labels of {:?}, many-to-one matching must be \
+                 explicit (group_left/group_right)",
+                sample.labels
+            );
+        }
+
+        let (l, r) = if swapped {
+            (other.value, sample.value)
+        } else {
+            (sample.value, other.value)
+        };
+        let mut value = apply_binary_op(op, l, r)?;
+        if is_comparison(op) && !return_bool {
+            // Comparisons filter the vector and keep the left-hand side value
+            if value == 0.0 {
+                continue;
+            }
+            value = l;
+        }
+
+        let mut labels = sample.labels;
+        if !is_comparison(op) || return_bool {
+            labels.remove(METRIC_NAME_LABEL);
+        }
+        match (include, matching) {
+            (None, Some(LabelModifier::Include(on))) => {
+                labels.retain(|k, _| on.labels.contains(k));
+            }
+            (None, Some(LabelModifier::Exclude(ignoring))) => {
+                labels.retain(|k, _| !ignoring.labels.contains(k));
+            }
+            _ => {}
+        }
+        // group_left/group_right copy the listed labels from the "one" side
+        for name in include.into_iter().flatten() {
+            match other.labels.get(name) {
+                Some(value) => labels.insert(name.clone(), value.clone()),
+                None => labels.remove(name),
+            };
+        }
+
+        samples.push(Sample { labels, value });
+    }
+
+    Ok(samples)
+}
+
+/// Labels two samples must share to match, as selected by `on`/`ignoring`
+fn matching_labels(labels: &Labels, matching: Option<&LabelModifier>) -> Labels {
+    match matching {
+        Some(LabelModifier::Include(on)) => labels
+            .iter()
+            .filter(|(k, _)| on.labels.contains(k))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+        Some(LabelModifier::Exclude(ignoring)) => labels
+            .iter()
+            .filter(|(k, _)| *k != METRIC_NAME_LABEL && !ignoring.labels.contains(k))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+        None => drop_metric_name(labels.clone()),
+    }
+}
+
+fn drop_metric_name(mut labels: Labels) -> Labels {
+    labels.remove(METRIC_NAME_LABEL);
+    labels
+}
+
+/// Rate calculation with extrapolation to the range boundaries, as in Prometheus
+fn extrapolated_rate(
+    samples: &[(i64, f64)],
+    range_start: i64,
+    range_end: i64,
+    is_counter: bool,
+    is_rate: bool,
+) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let (first_ts, first_value) = samples[0];
+    let (last_ts, last_value) = samples[samples.len() - 1];
+
+    let mut result = last_value - first_value;
+    if is_counter {
+        // Account for counter resets
+        for pair in samples.windows(2) {
+            if pair[1].1 < pair[0].1 {
+                result += pair[0].1;
+            }
+        }
+    }
+
+    let sampled_interval = (last_ts - first_ts) as f64 / 1000.0;
+    if sampled_interval == 0.0 {
+        // All samples share a timestamp, there is no interval to extrapolate from
+        return None;
+    }
+    let average_interval = sampled_interval / (samples.len() - 1) as f64;
+    let mut duration_to_start = (first_ts - range_start) as f64 / 1000.0;
+    let duration_to_end = (range_end - last_ts) as f64 / 1000.0;
+
+    // Counters cannot extrapolate below zero
+    if is_counter && result > 0.0 && first_value >= 0.0 {
+        let duration_to_zero = sampled_interval * (first_value / result);
+        if duration_to_zero < duration_to_start {
+            duration_to_start = duration_to_zero;
+        }
+    }
+
+    let threshold = average_interval * 1.1;
+    let mut extrapolate_to_interval = sampled_interval;
+    extrapolate_to_interval += if duration_to_start < threshold {
+        duration_to_start
+    } else {
+        average_interval / 2.0
+    };
+    extrapolate_to_interval += if duration_to_end < threshold {
+        duration_to_end
+    } else {
+        average_interval / 2.0
+    };
+
+    let mut factor = extrapolate_to_interval / sampled_interval;
+    if is_rate {
+        factor /= (range_end - range_start) as f64 / 1000.0;
+    }
+
+    Some(result * factor)
+}
+
+/// Rate or delta between the last two samples (irate/idelta)
+fn instant_delta(samples: &[(i64, f64)], is_rate: bool) -> Option<f64> {
+    let [.., (prev_ts, prev_value), (last_ts, last_value)] = samples else {
+        return None;
+    };
+
+    let mut result = last_value - prev_value;
+    if is_rate {
+        if last_value < prev_value {
+            // Counter reset
+            result = *last_value;
+        }
+        let interval = (last_ts - prev_ts) as f64 / 1000.0;
+        if interval == 0.0 {
+            return None;
+        }
+        result /= interval;
+    }
+
+    Some(result)
+}
+
+/// Evaluate a `*_over_time` function over the samples of a range
+fn over_time(name: &str, samples: &[(i64, f64)]) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let values = samples.iter().map(|(_, v)| *v);
+    Some(match name {
+        "avg_over_time" => values.sum::<f64>() / samples.len() as f64,
+        "sum_over_time" => values.sum(),
+        "min_over_time" => values.fold(f64::NAN, f64::min),
+        "max_over_time" => values.fold(f64::NAN, f64::max),
+        "count_over_time" => samples.len() as f64,
+        _ => samples[samples.len() - 1].1,
+    })
+}
+
+/// Calculate quantiles from classic histogram buckets grouped by their `le` label
+fn histogram_quantile(phi: f64, samples: Vec<Sample>) -> Vec<Sample> {
+    let mut groups: BTreeMap<Labels, Vec<(f64, f64)>> = BTreeMap::new();
+
+    for sample in samples {
+        let mut labels = drop_metric_name(sample.labels);
+        let Some(upper_bound) = labels.remove("le").and_then(|le| parse_bucket_bound(&le)) else {
+            continue;
+        };
+        groups
+            .entry(labels)
+            .or_default()
+            .push((upper_bound, sample.value));
+    }
+
+    groups
+        .into_iter()
+        .map(|(labels, buckets)| Sample {
+            labels,
+            value: bucket_quantile(phi, buckets),
+        })
+        .collect()
+}
+
+fn parse_bucket_bound(le: &str) -> Option<f64> {
+    match le {
+        "+Inf" | "Inf" | "+inf" | "inf" => Some(f64::INFINITY),
+        _ => le.parse().ok(),
+    }
+}
+
+/// Quantile of a set of (upper bound, cumulative count) buckets, as in Prometheus
+fn bucket_quantile(phi: f64, mut buckets: Vec<(f64, f64)>) -> f64 {
+    if phi.is_nan() {
+        return f64::NAN;
+    }
+    if phi < 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if phi > 1.0 {
+        return f64::INFINITY;
+    }
+
+    buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if buckets.len() < 2 || buckets[buckets.len() - 1].0 != f64::INFINITY {
+        return f64::NAN;
+    }
+
+    // Cumulative counts must be monotonic, fix up any decreasing values
+    for idx in 1..buckets.len() {
+        if buckets[idx].1 < buckets[idx - 1].1 {
+            buckets[idx].1 = buckets[idx - 1].1;
+        }
+    }
+
+    let observations = buckets[buckets.len() - 1].1;
+    if observations == 0.0 {
+        return f64::NAN;
+    }
+
+    let mut rank = phi * observations;
+    let b = buckets
+        .iter()
+        .position(|(_, count)| *count >= rank)
+        .unwrap_or(buckets.len() - 1);
+
+    if b == buckets.len() - 1 {
+        return buckets[buckets.len() - 2].0;
+    }
+    if b == 0 && buckets[0].0 <= 0.0 {
+        return buckets[0].0;
+    }
+
+    let mut bucket_start = 0.0;
+    let bucket_end = buckets[b].0;
+    let mut count = buckets[b].1;
+    if b > 0 {
+        bucket_start = buckets[b - 1].0;
+        count -= buckets[b - 1].1;
+        rank -= buckets[b - 1].1;
+    }
+
+    bucket_start + (bucket_end - bucket_start) * (rank / count)
+}
+
+/// Parse a PromQL query
+pub fn parse_query(query: &str) -> Result<Expr> {
+    parser::parse(query).map_err(|e| anyhow::anyhow!("Invalid PromQL: {}", e))
+}
+
+/// Evaluate an instant query at `time_ms`
+pub async fn instant_query(store: &MetricStore, expr: &Expr, time_ms: i64) -> Result<QueryResult> {
+    let evaluator = Evaluator::load(store, expr, time_ms, time_ms).await?;
+
+    match evaluator.eval(expr, time_ms)? {
+        Value::Scalar(v) => Ok(QueryResult::Scalar(time_ms, v)),
+        Value::Vector(samples) => Ok(QueryResult::Vector(time_ms, samples)),
+        Value::Matrix(series) => Ok(QueryResult::Matrix(series)),
+    }
+}
+
+/// Evaluate a range query at every step in `[start_ms, end_ms]`
+pub async fn range_query(
+    store: &MetricStore,
+    expr: &Expr,
+    start_ms: i64,
+    end_ms: i64,
+    step_ms: i64,
+) -> Result<QueryResult> {
+    if step_ms <= 0 {
+        anyhow::bail!("Step must be positive");
+    }
+    if end_ms < start_ms {
+        anyhow::bail!("End time must not be before start time");
+    }
+
+    let evaluator = Evaluator::load(store, expr, start_ms, end_ms).await?;
+
+    let mut series: BTreeMap<Labels, Vec<(i64, f64)>> = BTreeMap::new();
+    let mut t = start_ms;
+    while t <= end_ms {
+        match evaluator.eval(expr, t)? {
+            Value::Scalar(v) => series.entry(Labels::new()).or_default().push((t, v)),
+            Value::Vector(samples) => {
+                for sample in samples {
+                    series
+                        .entry(sample.labels)
+                        .or_default()
+                        .push((t, sample.value));
+                }
+            }
+            Value::Matrix(_) => {
+                anyhow::bail!("Range queries must return a scalar or instant vector")
+            }
+        }
+        t += step_ms;
+    }
+
+    Ok(QueryResult::Matrix(
+        series
+            .into_iter()
+            .map(|(labels, samples)| Series { labels, samples })
+            .collect(),
+    ))
+}
+
+/// Format a sample value like Prometheus
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_timestamp(ms: i64) -> serde_json::Value {
+    serde_json::json!(ms as f64 / 1000.0)
+}
+
+fn format_labels(labels: &Labels) -> String {
+    let name = labels
+        .get(METRIC_NAME_LABEL)
+        .map(String::as_str)
+        .unwrap_or("");
+    let pairs: Vec<String> = labels
+        .iter()
+        .filter(|(k, _)| k.as_str() != METRIC_NAME_LABEL)
+        .map(|(k, v)| format!("{}={:?}", k, v))
+        .collect();
+    format!("{}{{{}}}", name, pairs.join(", "))
+}
+
+impl QueryResult {
+    /// The `data` object of a Prometheus query API response
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            QueryResult::Scalar(ts, v) => serde_json::json!({
+                "resultType": "scalar",
+                "result": [format_timestamp(*ts), format_value(*v)],
+            }),
+            QueryResult::Vector(ts, samples) => serde_json::json!({
+                "resultType": "vector",
+                "result": samples.iter().map(|s| serde_json::json!({
+                    "metric": s.labels,
+                    "value": [format_timestamp(*ts), format_value(s.value)],
+                })).collect::<Vec<_>>(),
+            }),
+            QueryResult::Matrix(series) => serde_json::json!({
+                "resultType": "matrix",
+                "result": series.iter().map(|s| serde_json::json!({
+                    "metric": s.labels,
+                    "values": s.samples.iter()
+                        .map(|(ts, v)| serde_json::json!([format_timestamp(*ts), format_value(*v)]))
+                        .collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+pub async fn run_promql(
+    query: &str,
+    path: &Path,
+    start: Option<&str>,
+    end: Option<&str>,
+    step: Option<&str>,
+    columns: MetricColumns,
+    format: DocumentFormat,
+) -> Result<()> {
+    let expr = parse_query(query)?;
+    let store = MetricStore::open(path, columns).await?;
+
+    let end_ms = end.map(parse_time).transpose()?.unwrap_or_else(now_ms);
+    let result = match (start, step) {
+        (Some(start), Some(step)) => {
+            range_query(&store, &expr, parse_time(start)?, end_ms, parse_step(step)?).await?
+        }
+        (None, None) => instant_query(&store, &expr, end_ms).await?,
+        _ => anyhow::bail!("Range queries need both --start and --step"),
+    };
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let response = serde_json::json!({
+                "status": "success",
+                "data": result.to_json(),
+            });
+            print_json(&response, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== PromQL Query ===");
+            println!("Query: {}", query);
+
+            match &result {
+                QueryResult::Scalar(_, v) => {
+                    println!("\nScalar: {}", format_value(*v));
+                }
+                QueryResult::Vector(_, samples) => {
+                    println!("\nInstant vector ({} series):", samples.len());
+                    println!("{:<80} {:<20}", "Series", "Value");
+                    println!("{}", "-".repeat(100));
+                    for sample in samples {
+                        println!(
+                            "{:<80} {:<20}",
+                            format_labels(&sample.labels),
+                            format_value(sample.value)
+                        );
+                    }
+                }
+                QueryResult::Matrix(series) => {
+                    println!("\nRange vector ({} series):", series.len());
+                    for s in series {
+                        println!("\n{}", format_labels(&s.labels));
+                        for (ts, v) in &s.samples {
+                            println!("  {:<20} {}", *ts as f64 / 1000.0, format_value(*v));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: i64 = 60 * 1000;
+
+    fn series(labels: &[(&str, &str)], samples: Vec<(i64, f64)>) -> Series {
+        Series {
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            samples,
+        }
+    }
+
+    /// Samples `start, start + step, ...` every `interval`, like `load` in Prometheus tests
+    fn load(interval: i64, start: f64, step: f64, count: usize) -> Vec<(i64, f64)> {
+        (0..=count)
+            .map(|i| (i as i64 * interval, start + step * i as f64))
+            .collect()
+    }
+
+    /// Evaluate a query at `t` against in-memory series, sorted by labels
+    fn eval(query: &str, data: &[Series], t: i64) -> Result<Value> {
+        let expr = parse_query(query)?;
+        let mut found = Vec::new();
+        collect_selectors(&expr, &mut found)?;
+
+        let selectors = found
+            .into_iter()
+            .map(|(vs, _)| {
+                let matchers = selector_matchers(&vs);
+                let matching = data
+                    .iter()
+                    .filter(|s| {
+                        matchers.iter().all(|m| {
+                            m.is_match(s.labels.get(&m.name).map(String::as_str).unwrap_or(""))
+                        })
+                    })
+                    .cloned()
+                    .collect();
+                (vs.to_string(), matching)
+            })
+            .collect();
+
+        Evaluator { selectors }.eval(&expr, t)
+    }
+
+    fn eval_vector(query: &str, data: &[Series], t: i64) -> Vec<(Labels, f64)> {
+        let Value::Vector(samples) = eval(query, data, t).unwrap() else {
+            panic!("{} is not an instant vector", query);
+        };
+        let mut samples: Vec<(Labels, f64)> =
+            samples.into_iter().map(|s| (s.labels, s.value)).collect();
+        samples.sort_by(|a, b| a.0.cmp(&b.0));
+        samples
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        series(pairs, Vec::new()).labels
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn increase_extrapolates_to_the_range_start() {
+        // functions.test: load 5m, http_requests{path="/foo"} 0+10x10
+        let data = vec![series(
+            &[("__name__", "http_requests"), ("path", "/foo")],
+            load(5 * MINUTE, 0.0, 10.0, 10),
+        )];
+
+        let increase = eval_vector("increase(http_requests[50m])", &data, 50 * MINUTE);
+        assert_eq!(increase.len(), 1);
+        assert_eq!(increase[0].0, labels(&[("path", "/foo")]));
+        assert_close(increase[0].1, 100.0);
+
+        let rate = eval_vector("rate(http_requests[50m])", &data, 50 * MINUTE);
+        assert_close(rate[0].1, 100.0 / 3000.0);
+    }
+
+    #[test]
+    fn rate_extrapolates_half_an_interval_away_from_the_edges() {
+        // Samples 200s after the range start and 280s before its end, 60s apart
+        let samples = [(200_000, 10.0), (260_000, 16.0), (320_000, 22.0)];
+        assert_close(
+            extrapolated_rate(&samples, 0, 600_000, true, false).unwrap(),
+            18.0,
+        );
+        assert_close(
+            extrapolated_rate(&samples, 0, 600_000, true, true).unwrap(),
+            0.03,
+        );
+    }
+
+    #[test]
+    fn counters_do_not_extrapolate_below_zero() {
+        // Extrapolating 30s back would reach -1, so it stops at zero 15s back
+        let samples = [(30_000, 1.0), (45_000, 2.0), (60_000, 3.0)];
+        assert_close(
+            extrapolated_rate(&samples, 0, 60_000, true, false).unwrap(),
+            3.0,
+        );
+        // Gauges extrapolate half an interval towards the range start
+        assert_close(
+            extrapolated_rate(&samples, 0, 60_000, false, false).unwrap(),
+            2.5,
+        );
+        assert_eq!(
+            extrapolated_rate(&samples[..1], 0, 60_000, true, true),
+            None
+        );
+    }
+
+    #[test]
+    fn counter_resets_are_added() {
+        let samples = [(0, 5.0), (10_000, 10.0), (20_000, 2.0), (30_000, 6.0)];
+        assert_close(
+            extrapolated_rate(&samples, 0, 30_000, true, false).unwrap(),
+            11.0,
+        );
+    }
+
+    fn histogram() -> Vec<Series> {
+        // histograms.test: load 5m, testhistogram_bucket{start="positive"}
+        [("0.1", 5.0), (".2", 7.0), ("1e0", 11.0), ("+Inf", 12.0)]
+            .iter()
+            .map(|(le, step)| {
+                series(
+                    &[
+                        ("__name__", "testhistogram_bucket"),
+                        ("le", le),
+                        ("start", "positive"),
+                    ],
+                    load(5 * MINUTE, 0.0, *step, 10),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn histogram_quantile_interpolates_within_buckets() {
+        let data = histogram();
+        let quantile = |phi: &str| {
+            eval_vector(
+                &format!("histogram_quantile({}, testhistogram_bucket)", phi),
+                &data,
+                50 * MINUTE,
+            )
+        };
+
+        let median = quantile("0.5");
+        assert_eq!(median.len(), 1);
+        assert_eq!(median[0].0, labels(&[("start", "positive")]));
+        assert_close(median[0].1, 0.15);
+        assert_close(quantile("0")[0].1, 0.0);
+        assert_close(quantile("0.3")[0].1, 0.072);
+    }
+
+    #[test]
+    fn histogram_quantile_in_the_inf_bucket_is_the_highest_finite_bound() {
+        let data = histogram();
+        let p95 = eval_vector(
+            "histogram_quantile(0.95, testhistogram_bucket)",
+            &data,
+            50 * MINUTE,
+        );
+        assert_close(p95[0].1, 1.0);
+
+        assert_eq!(
+            bucket_quantile(1.5, vec![(1.0, 1.0), (f64::INFINITY, 2.0)]),
+            f64::INFINITY
+        );
+        assert!(bucket_quantile(0.5, vec![(1.0, 1.0), (2.0, 2.0)]).is_nan());
+        assert!(bucket_quantile(0.5, vec![(1.0, 0.0), (f64::INFINITY, 0.0)]).is_nan());
+    }
+
+    fn requests() -> Vec<Series> {
+        [
+            ("api", "a", 1.0),
+            ("api", "b", 2.0),
+            ("db", "a", 4.0),
+            ("db", "c", 8.0),
+        ]
+        .iter()
+        .map(|(job, instance, value)| {
+            series(
+                &[
+                    ("__name__", "requests"),
+                    ("job", job),
+                    ("instance", instance),
+                ],
+                vec![(0, *value)],
+            )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn sum_by_groups_on_the_listed_labels() {
+        let data = requests();
+        assert_eq!(
+            eval_vector("sum by (job) (requests)", &data, 0),
+            vec![
+                (labels(&[("job", "api")]), 3.0),
+                (labels(&[("job", "db")]), 12.0),
+            ]
+        );
+        assert_eq!(
+            eval_vector("sum without (job) (requests)", &data, 0),
+            vec![
+                (labels(&[("instance", "a")]), 5.0),
+                (labels(&[("instance", "b")]), 2.0),
+                (labels(&[("instance", "c")]), 8.0),
+            ]
+        );
+        assert_eq!(
+            eval_vector("sum(requests)", &data, 0),
+            vec![(Labels::new(), 15.0)]
+        );
+    }
+
+    #[test]
+    fn group_left_matches_many_to_one() {
+        let mut data = requests();
+        for (instance, team) in [("a", "red"), ("b", "blue"), ("c", "green")] {
+            data.push(series(
+                &[
+                    ("__name__", "instance_info"),
+                    ("instance", instance),
+                    ("team", team),
+                ],
+                vec![(0, 1.0)],
+            ));
+        }
+
+        assert_eq!(
+            eval_vector(
+                "requests * on (instance) group_left (team) instance_info",
+                &data,
+                0
+            ),
+            vec![
+                (
+                    labels(&[("instance", "a"), ("job", "api"), ("team", "red")]),
+                    1.0
+                ),
+                (
+                    labels(&[("instance", "a"), ("job", "db"), ("team", "red")]),
+                    4.0
+                ),
+                (
+                    labels(&[("instance", "b"), ("job", "api"), ("team", "blue")]),
+                    2.0
+                ),
+                (
+                    labels(&[("instance", "c"), ("job", "db"), ("team", "green")]),
+                    8.0
+                ),
+            ]
+        );
+
+        // Without group_left, two requests series match instance "a"
+        assert!(eval("requests * on (instance) instance_info", &data, 0).is_err());
+    }
+
+    #[test]
+    fn scalar_comparisons_require_bool() {
+        assert!(eval("1 > 2", &[], 0).is_err());
+        assert!(matches!(eval("1 < bool 2", &[], 0).unwrap(), Value::Scalar(v) if v == 1.0));
+        assert!(matches!(eval("2 * 3", &[], 0).unwrap(), Value::Scalar(v) if v == 6.0));
+    }
+}
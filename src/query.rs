@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use arrow::array::RecordBatch;
+use arrow::util::pretty::pretty_format_batches;
+use datafusion::datasource::listing::{
+    ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+};
+use datafusion::prelude::SessionContext;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use vortex_datafusion::VortexFormat;
+use vortex_session::VortexSession;
+
+use crate::rows::BatchWriter;
+use crate::{OutputFormat, create_session};
+
+/// Parse a `--table NAME=PATH` argument
+pub fn parse_table_arg(s: &str) -> std::result::Result<(String, PathBuf), String> {
+    let (name, path) = s
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid table: {}. Use NAME=PATH", s))?;
+
+    if name.is_empty() || path.is_empty() {
+        return Err(format!("Invalid table: {}. Use NAME=PATH", s));
+    }
+
+    Ok((name.to_string(), PathBuf::from(path)))
+}
+
+/// Register a Vortex file as a DataFusion table through the vortex-datafusion provider
+pub async fn register_vortex_table(
+    ctx: &SessionContext,
+    session: &VortexSession,
+    name: &str,
+    path: &Path,
+) -> Result<()> {
+    let format = Arc::new(VortexFormat::new(session.clone()));
+
+    let path = std::fs::canonicalize(path)
+        .context(format!("Failed to resolve table path: {}", path.display()))?;
+    let table_url = ListingTableUrl::parse(path.to_string_lossy())?;
+
+    let listing_options = ListingOptions::new(format)
+        .with_file_extension(".vortex")
+        .with_session_config_options(ctx.state().config());
+
+    let config = ListingTableConfig::new(table_url)
+        .with_listing_options(listing_options)
+        .infer_schema(&ctx.state())
+        .await
+        .context(format!("Failed to infer schema for table '{}'", name))?;
+
+    ctx.register_table(name, Arc::new(ListingTable::try_new(config)?))?;
+
+    Ok(())
+}
+
+/// Print record batches using the given output format
+pub fn print_batches(sql: &str, batches: &[RecordBatch], format: &OutputFormat) -> Result<()> {
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+
+    match format {
+        OutputFormat::Json => {
+            let mut writer = arrow::json::ArrayWriter::new(Vec::new());
+            for batch in batches {
+                writer.write(batch)?;
+            }
+            writer.finish()?;
+
+            let buf = writer.into_inner();
+            let rows: serde_json::Value = if buf.is_empty() {
+                serde_json::Value::Array(Vec::new())
+            } else {
+                serde_json::from_slice(&buf)?
+            };
+
+            let result = serde_json::json!({
+                "query": sql,
+                "row_count": row_count,
+                "rows": rows,
+            });
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        OutputFormat::Text => {
+            println!("{}", pretty_format_batches(batches)?);
+            println!("\n{} row(s)", row_count);
+        }
+        OutputFormat::Csv | OutputFormat::Ndjson => {
+            let mut writer = BatchWriter::new(format);
+            for batch in batches {
+                writer.write(batch)?;
+            }
+            writer.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a SQL query over Vortex files registered as tables, collecting its result
+pub async fn execute_query(sql: &str, tables: &[(String, PathBuf)]) -> Result<Vec<RecordBatch>> {
+    if tables.is_empty() {
+        anyhow::bail!("At least one --table NAME=PATH is required");
+    }
+
+    let ctx = SessionContext::new();
+    let session = create_session();
+
+    for (name, path) in tables {
+        register_vortex_table(&ctx, &session, name, path).await?;
+    }
+
+    let batches = ctx
+        .sql(sql)
+        .await
+        .context("Failed to plan query")?
+        .collect()
+        .await
+        .context("Failed to execute query")?;
+
+    Ok(batches)
+}
+
+pub async fn run_query(
+    sql: &str,
+    tables: &[(String, PathBuf)],
+    format: OutputFormat,
+) -> Result<()> {
+    let batches = execute_query(sql, tables).await?;
+    print_batches(sql, &batches, &format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{AsArray, Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Int64Type, Schema};
+
+    use crate::testing::write_vortex_file;
+
+    async fn write_table(path: &Path, ids: std::ops::Range<i64>) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("host", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from_iter_values(ids.clone())),
+                Arc::new(StringArray::from_iter_values(
+                    ids.map(|i| if i % 2 == 0 { "a" } else { "b" }),
+                )),
+            ],
+        )
+        .unwrap();
+        write_vortex_file(path, vec![batch]).await.unwrap();
+    }
+
+    #[test]
+    fn table_args() {
+        assert_eq!(
+            parse_table_arg("t=data/t.vortex").unwrap(),
+            ("t".to_string(), PathBuf::from("data/t.vortex"))
+        );
+        assert!(parse_table_arg("t").is_err());
+        assert!(parse_table_arg("=data/t.vortex").is_err());
+        assert!(parse_table_arg("t=").is_err());
+    }
+
+    #[tokio::test]
+    async fn query_filters_and_aggregates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.vortex");
+        write_table(&path, 0..100).await;
+
+        let batches = execute_query(
+            "SELECT host, count(*) AS n, sum(id) AS total FROM t \
+             WHERE id >= 50 GROUP BY host ORDER BY host",
+            &[("t".to_string(), path)],
+        )
+        .await
+        .unwrap();
+        let batch = arrow::compute::concat_batches(&batches[0].schema(), &batches).unwrap();
+
+        let hosts: Vec<&str> = batch
+            .column(0)
+            .as_string::<i32>()
+            .iter()
+            .flatten()
+            .collect();
+        assert_eq!(hosts, vec!["a", "b"]);
+        assert_eq!(
+            batch
+                .column(1)
+                .as_primitive::<Int64Type>()
+                .values()
+                .to_vec(),
+            vec![25, 25]
+        );
+        // Even ids 50..=98 and odd ids 51..=99
+        assert_eq!(
+            batch
+                .column(2)
+                .as_primitive::<Int64Type>()
+                .values()
+                .to_vec(),
+            vec![1850, 1875]
+        );
+    }
+
+    #[tokio::test]
+    async fn query_joins_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let left = dir.path().join("left.vortex");
+        let right = dir.path().join("right.vortex");
+        write_table(&left, 0..10).await;
+        write_table(&right, 5..20).await;
+
+        let batches = execute_query(
+            "SELECT count(*) FROM l JOIN r ON l.id = r.id",
+            &[("l".to_string(), left), ("r".to_string(), right)],
+        )
+        .await
+        .unwrap();
+        assert_eq!(batches[0].column(0).as_primitive::<Int64Type>().value(0), 5);
+    }
+
+    #[tokio::test]
+    async fn query_requires_a_table() {
+        assert!(execute_query("SELECT 1", &[]).await.is_err());
+    }
+}
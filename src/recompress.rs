@@ -0,0 +1,488 @@
+use anyhow::{Context, Result};
+use arrow::array::{BooleanArray, Float64Array, RecordBatch, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use futures::StreamExt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use vortex::compressor::BtrBlocksCompressor;
+use vortex::encodings::fsst::{fsst_compress, fsst_train_compressor};
+use vortex::encodings::zstd::ZstdArray;
+use vortex::expr::{root, select};
+use vortex_array::arrays::DictArray;
+use vortex_array::builders::dict::dict_encode;
+use vortex_array::{Array, ArrayRef, Canonical, IntoArray};
+use vortex_dtype::{DType, FieldName, FieldNames};
+use vortex_file::VortexFile;
+use vortex_layout::layouts::compact::CompactCompressor;
+
+use crate::rows::BatchWriter;
+use crate::size::{column_sizes, format_bytes};
+use crate::{DocumentFormat, OutputFormat, open_vortex_file, print_json, truncate_string};
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// A compression strategy to evaluate a column against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Candidate {
+    /// BtrBlocks-style sampling compressor, the Vortex default
+    Default,
+    /// Zstd-heavy compressor used by `--compression compact`
+    Compact,
+    /// Zstd over the canonical values
+    Zstd,
+    /// Canonical arrays without compression
+    None,
+    /// Dictionary encoding with compressed codes and values
+    Dict,
+    /// FSST string compression, for string and binary columns only
+    Fsst,
+}
+
+impl Candidate {
+    const ALL: [Candidate; 6] = [
+        Candidate::Default,
+        Candidate::Compact,
+        Candidate::Zstd,
+        Candidate::None,
+        Candidate::Dict,
+        Candidate::Fsst,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Candidate::Default => "default",
+            Candidate::Compact => "compact",
+            Candidate::Zstd => "zstd",
+            Candidate::None => "none",
+            Candidate::Dict => "dict",
+            Candidate::Fsst => "fsst",
+        }
+    }
+
+    fn supports(&self, dtype: &DType) -> bool {
+        match self {
+            Candidate::Fsst => matches!(dtype, DType::Utf8(_) | DType::Binary(_)),
+            Candidate::Zstd | Candidate::Dict => {
+                matches!(
+                    dtype,
+                    DType::Primitive(..) | DType::Utf8(_) | DType::Binary(_)
+                )
+            }
+            _ => true,
+        }
+    }
+
+    /// Encode a canonical array with this strategy
+    fn encode(&self, canonical: &Canonical) -> Result<ArrayRef> {
+        let array = canonical.clone().into_array();
+
+        Ok(match self {
+            Candidate::Default => BtrBlocksCompressor::default().compress(&array)?,
+            Candidate::Compact => CompactCompressor::default().compress(&array)?,
+            Candidate::Zstd => ZstdArray::from_canonical(canonical, ZSTD_LEVEL, 0)?
+                .ok_or_else(|| anyhow::anyhow!("zstd does not support {}", array.dtype()))?
+                .into_array(),
+            Candidate::None => array,
+            Candidate::Dict => {
+                let dict = dict_encode(&array)?;
+                let compressor = BtrBlocksCompressor::default();
+                DictArray::try_new(
+                    compressor.compress(dict.codes())?,
+                    compressor.compress(dict.values())?,
+                )?
+                .into_array()
+            }
+            Candidate::Fsst => {
+                let compressor = fsst_train_compressor(&array)?;
+                fsst_compress(&array, &compressor)?.into_array()
+            }
+        })
+    }
+}
+
+/// Size and timings of a column encoded with one candidate strategy
+#[derive(Clone, Debug)]
+struct CandidateResult {
+    candidate: Candidate,
+    /// Whether the strategy applies to the column's dtype
+    supported: bool,
+    /// Error of the first chunk the strategy failed to encode or decode, it is not tried further
+    error: Option<String>,
+    bytes: u64,
+    encode_time: Duration,
+    decode_time: Duration,
+}
+
+impl CandidateResult {
+    /// Whether the strategy encoded and decoded every chunk, so its size and timings are complete
+    fn succeeded(&self) -> bool {
+        self.supported && self.error.is_none()
+    }
+}
+
+/// Results of every candidate strategy for a single column
+#[derive(Debug)]
+struct ColumnEval {
+    name: String,
+    /// Rows of the evaluated chunks
+    rows: u64,
+    /// On-disk bytes of the column, scaled to the evaluated rows when chunks are sampled
+    on_disk_bytes: u64,
+    uncompressed_bytes: u64,
+    results: Vec<CandidateResult>,
+}
+
+impl ColumnEval {
+    fn ratio(&self, result: &CandidateResult) -> Option<f64> {
+        (result.succeeded() && result.bytes > 0)
+            .then(|| self.uncompressed_bytes as f64 / result.bytes as f64)
+    }
+
+    /// The successful candidate with the smallest encoded size
+    fn smallest(&self) -> Option<&CandidateResult> {
+        self.results
+            .iter()
+            .filter(|r| r.succeeded())
+            .min_by_key(|r| r.bytes)
+    }
+}
+
+/// Re-encode a column chunk by chunk under every candidate strategy
+///
+/// With `sample_chunks`, only the first chunks are evaluated and the on-disk size of the
+/// column is scaled by the share of rows they hold, so it compares with the encoded sizes.
+async fn eval_column(
+    vortex_file: &VortexFile,
+    name: &str,
+    dtype: &DType,
+    on_disk_bytes: u64,
+    sample_chunks: Option<usize>,
+) -> Result<ColumnEval> {
+    let mut results: Vec<CandidateResult> = Candidate::ALL
+        .iter()
+        .map(|candidate| CandidateResult {
+            candidate: *candidate,
+            supported: candidate.supports(dtype),
+            error: None,
+            bytes: 0,
+            encode_time: Duration::ZERO,
+            decode_time: Duration::ZERO,
+        })
+        .collect();
+    let mut uncompressed_bytes = 0;
+
+    let names: FieldNames = [FieldName::from(name)].into_iter().collect();
+    let stream = vortex_file
+        .scan()?
+        .with_projection(select(names, root()))
+        .into_array_stream()?;
+    let mut stream = std::pin::pin!(stream);
+    let mut chunks = 0;
+    let mut rows = 0;
+
+    while let Some(chunk) = stream.next().await {
+        if sample_chunks.is_some_and(|limit| chunks >= limit) {
+            break;
+        }
+        chunks += 1;
+
+        let column = chunk?.to_canonical()?.into_struct().fields()[0].clone();
+        rows += column.len() as u64;
+        let canonical = column.to_canonical()?;
+        uncompressed_bytes += canonical.clone().into_array().nbytes() as u64;
+
+        for result in results.iter_mut().filter(|r| r.succeeded()) {
+            let start = Instant::now();
+            let encoded = match result.candidate.encode(&canonical) {
+                Ok(encoded) => encoded,
+                Err(e) => {
+                    // Keep the reason, e.g. dict on a column with too many unique values
+                    result.error = Some(format!("{:#}", e));
+                    continue;
+                }
+            };
+            result.encode_time += start.elapsed();
+            result.bytes += encoded.nbytes() as u64;
+
+            let start = Instant::now();
+            if let Err(e) = encoded.to_canonical() {
+                result.error = Some(format!("Failed to decode: {:#}", e));
+                continue;
+            }
+            result.decode_time += start.elapsed();
+        }
+    }
+
+    let row_count = vortex_file.row_count();
+    let on_disk_bytes = if rows < row_count {
+        (on_disk_bytes as u128 * rows as u128 / row_count as u128) as u64
+    } else {
+        on_disk_bytes
+    };
+
+    Ok(ColumnEval {
+        name: name.to_string(),
+        rows,
+        on_disk_bytes,
+        uncompressed_bytes,
+        results,
+    })
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The evaluation as one row per column and strategy, for CSV and NDJSON output
+fn eval_batch(columns: &[ColumnEval]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("column", DataType::Utf8, false),
+        Field::new("strategy", DataType::Utf8, false),
+        Field::new("supported", DataType::Boolean, false),
+        Field::new("error", DataType::Utf8, true),
+        Field::new("bytes", DataType::UInt64, true),
+        Field::new("ratio", DataType::Float64, true),
+        Field::new("encode_ms", DataType::Float64, true),
+        Field::new("decode_ms", DataType::Float64, true),
+    ]);
+
+    let rows: Vec<(&ColumnEval, &CandidateResult)> = columns
+        .iter()
+        .flat_map(|c| c.results.iter().map(move |r| (c, r)))
+        .collect();
+    let succeeded = |r: &CandidateResult| r.succeeded();
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|(c, _)| c.name.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|(_, r)| r.candidate.name()),
+            )),
+            Arc::new(BooleanArray::from_iter(
+                rows.iter().map(|(_, r)| Some(r.supported)),
+            )),
+            Arc::new(StringArray::from_iter(
+                rows.iter().map(|(_, r)| r.error.as_deref()),
+            )),
+            Arc::new(UInt64Array::from_iter(
+                rows.iter().map(|(_, r)| succeeded(r).then_some(r.bytes)),
+            )),
+            Arc::new(Float64Array::from_iter(
+                rows.iter().map(|(c, r)| c.ratio(r)),
+            )),
+            Arc::new(Float64Array::from_iter(
+                rows.iter()
+                    .map(|(_, r)| succeeded(r).then(|| millis(r.encode_time))),
+            )),
+            Arc::new(Float64Array::from_iter(
+                rows.iter()
+                    .map(|(_, r)| succeeded(r).then(|| millis(r.decode_time))),
+            )),
+        ],
+    )?)
+}
+
+/// Re-encode each column under several strategies and compare size and speed
+pub async fn run_recompress_eval(
+    path: &Path,
+    columns: &[String],
+    sample_chunks: Option<usize>,
+    format: OutputFormat,
+) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let struct_fields = vortex_file
+        .dtype()
+        .as_struct_fields_opt()
+        .ok_or_else(|| anyhow::anyhow!("recompress-eval requires a file with a struct dtype"))?;
+    let on_disk = column_sizes(&vortex_file);
+
+    let mut evals = Vec::new();
+    for (name, dtype) in struct_fields.names().iter().zip(struct_fields.fields()) {
+        if !columns.is_empty() && !columns.iter().any(|c| c.as_str() == name.as_ref()) {
+            continue;
+        }
+
+        let on_disk_bytes = on_disk
+            .iter()
+            .find(|c| c.name == name.as_ref())
+            .map_or(0, |c| c.compressed_bytes);
+        let eval = eval_column(&vortex_file, name, &dtype, on_disk_bytes, sample_chunks)
+            .await
+            .context(format!("Failed to evaluate column: {}", name))?;
+        evals.push(eval);
+    }
+
+    for column in columns {
+        if !evals.iter().any(|e| &e.name == column) {
+            anyhow::bail!("Column not found: {}", column);
+        }
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let columns_json: Vec<_> = evals
+                .iter()
+                .map(|eval| {
+                    let results: Vec<_> = eval
+                        .results
+                        .iter()
+                        .map(|r| {
+                            serde_json::json!({
+                                "strategy": r.candidate.name(),
+                                "supported": r.supported,
+                                "error": r.error,
+                                "bytes": r.succeeded().then_some(r.bytes),
+                                "ratio": eval.ratio(r),
+                                "encode_ms": r.succeeded().then(|| millis(r.encode_time)),
+                                "decode_ms": r.succeeded().then(|| millis(r.decode_time)),
+                            })
+                        })
+                        .collect();
+
+                    serde_json::json!({
+                        "name": eval.name,
+                        "rows": eval.rows,
+                        "on_disk_bytes": eval.on_disk_bytes,
+                        "uncompressed_bytes": eval.uncompressed_bytes,
+                        "smallest": eval.smallest().map(|r| r.candidate.name()),
+                        "strategies": results,
+                    })
+                })
+                .collect();
+
+            let eval_json = serde_json::json!({
+                "file": path.display().to_string(),
+                "sample_chunks": sample_chunks,
+                "columns": columns_json,
+            });
+            print_json(&eval_json, &DocumentFormat::Json)?;
+        }
+        OutputFormat::Csv | OutputFormat::Ndjson => {
+            let mut writer = BatchWriter::new(&format);
+            writer.write(&eval_batch(&evals)?)?;
+            writer.finish()?;
+        }
+        OutputFormat::Text => {
+            println!("=== Vortex Recompression Evaluation ===");
+            println!("File: {}", path.display());
+            if let Some(limit) = sample_chunks {
+                println!("Sampled chunks: {}", limit);
+            }
+
+            for eval in &evals {
+                println!(
+                    "\n--- {} ({} rows, on disk {}{}, uncompressed {}) ---",
+                    truncate_string(&eval.name, 40),
+                    eval.rows,
+                    if eval.rows < vortex_file.row_count() {
+                        "~"
+                    } else {
+                        ""
+                    },
+                    format_bytes(eval.on_disk_bytes),
+                    format_bytes(eval.uncompressed_bytes)
+                );
+                println!(
+                    "{:<10} {:>14} {:>8} {:>12} {:>12}",
+                    "Strategy", "Size", "Ratio", "Encode (ms)", "Decode (ms)"
+                );
+                println!("{}", "-".repeat(60));
+
+                let smallest = eval.smallest().map(|r| r.candidate);
+                for r in &eval.results {
+                    if !r.supported {
+                        println!("{:<10} {:>14}", r.candidate.name(), "unsupported");
+                        continue;
+                    }
+                    if let Some(error) = &r.error {
+                        println!(
+                            "{:<10} {:>14}  {}",
+                            r.candidate.name(),
+                            "failed",
+                            truncate_string(error, 60)
+                        );
+                        continue;
+                    }
+
+                    println!(
+                        "{:<10} {:>14} {:>8} {:>12.2} {:>12.2}{}",
+                        r.candidate.name(),
+                        format_bytes(r.bytes),
+                        eval.ratio(r)
+                            .map(|ratio| format!("{:.2}x", ratio))
+                            .unwrap_or_else(|| "-".to_string()),
+                        millis(r.encode_time),
+                        millis(r.decode_time),
+                        if smallest == Some(r.candidate) {
+                            "  <- smallest"
+                        } else {
+                            ""
+                        }
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    use crate::testing::{chunked_write_options, write_vortex_file_with};
+
+    #[tokio::test]
+    async fn sampled_chunks_scale_the_on_disk_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.vortex");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("host", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..1000)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..1000).map(|i| format!("host-{}", i % 3)),
+                )),
+            ],
+        )
+        .unwrap();
+        write_vortex_file_with(&path, vec![batch], &chunked_write_options(100))
+            .await
+            .unwrap();
+        let vortex_file = open_vortex_file(&path).await.unwrap();
+
+        let full = eval_column(&vortex_file, "host", &DType::Utf8(false.into()), 1000, None)
+            .await
+            .unwrap();
+        assert_eq!(full.rows, 1000);
+        assert_eq!(full.on_disk_bytes, 1000);
+
+        let sampled = eval_column(
+            &vortex_file,
+            "host",
+            &DType::Utf8(false.into()),
+            1000,
+            Some(3),
+        )
+        .await
+        .unwrap();
+        assert_eq!(sampled.rows, 300);
+        assert_eq!(sampled.on_disk_bytes, 300);
+        assert!(sampled.uncompressed_bytes < full.uncompressed_bytes);
+
+        let default = &sampled.results[0];
+        assert_eq!(default.candidate, Candidate::Default);
+        assert!(default.succeeded(), "{:?}", default);
+        assert!(default.bytes > 0);
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::Instant;
+use vortex::expr::{root, select};
+use vortex_dtype::{FieldName, FieldNames};
+use vortex_file::{VortexFile, WriteOptionsSessionExt};
+use vortex_scan::SplitBy;
+
+use crate::encoding::{EncodingHistogram, column_encodings, column_layouts, layout_chunks};
+use crate::size::{ColumnSize, column_sizes, format_bytes};
+use crate::strategy::{WriteOptions, build_write_strategy};
+use crate::{create_session, open_vortex_file, truncate_string};
+
+/// Per-column sizes, chunks and encodings of a file
+///
+/// Sizes and chunk counts come from the footer's layout tree and segment map, only the
+/// first chunk of each column is read for its encoding.
+struct FileSummary {
+    file_size: u64,
+    sizes: Vec<ColumnSize>,
+    /// Number of layout chunks of each column, in file order
+    chunks: Vec<(String, usize)>,
+    /// Top-level encoding of the first chunk of each column
+    encodings: Vec<(String, EncodingHistogram)>,
+}
+
+impl FileSummary {
+    async fn read(path: &Path, vortex_file: &VortexFile) -> Result<Self> {
+        let segment_map = vortex_file.footer().segment_map();
+        Ok(FileSummary {
+            file_size: tokio::fs::metadata(path).await?.len(),
+            sizes: column_sizes(vortex_file),
+            chunks: column_layouts(vortex_file)
+                .into_iter()
+                .map(|(name, layout)| {
+                    let chunks = layout_chunks(&layout, segment_map).len();
+                    (name, chunks)
+                })
+                .collect(),
+            encodings: column_encodings(vortex_file, Some(1)).await?,
+        })
+    }
+
+    fn column_chunks(&self, name: &str) -> Option<usize> {
+        self.chunks
+            .iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, chunks)| *chunks)
+    }
+
+    /// Largest number of chunks of a column
+    fn max_chunks(&self) -> usize {
+        self.chunks
+            .iter()
+            .map(|(_, chunks)| *chunks)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn column_bytes(&self, name: &str) -> Option<u64> {
+        self.sizes
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.compressed_bytes)
+    }
+
+    fn column_encodings(&self, name: &str) -> String {
+        self.encodings
+            .iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, histogram)| histogram.short_summary())
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+/// Rewrite a Vortex file with a new write strategy, streaming one chunk at a time
+pub async fn run_rewrite(
+    input: &Path,
+    output: &Path,
+    columns: &[String],
+    options: WriteOptions,
+) -> Result<()> {
+    let start = Instant::now();
+
+    if output.exists() && std::fs::canonicalize(input)? == std::fs::canonicalize(output)? {
+        anyhow::bail!("Output file must be different from the input file");
+    }
+
+    let vortex_file = open_vortex_file(input).await?;
+    let mut scan = vortex_file.scan()?;
+
+    if !columns.is_empty() {
+        let fields = vortex_file
+            .dtype()
+            .as_struct_fields_opt()
+            .ok_or_else(|| anyhow::anyhow!("Column projection requires a struct file"))?;
+
+        for column in columns {
+            if !fields
+                .names()
+                .iter()
+                .any(|name| name.as_ref() == column.as_str())
+            {
+                anyhow::bail!("Column not found: {}", column);
+            }
+        }
+
+        let names: FieldNames = columns
+            .iter()
+            .map(|c| FieldName::from(c.as_str()))
+            .collect();
+        scan = scan.with_projection(select(names, root()));
+    }
+
+    // Feed the writer at most `chunk_rows` rows at a time, so it can cut chunks of that length
+    if let Some(chunk_rows) = options.chunk_rows {
+        scan = scan.with_split_by(SplitBy::RowCount(chunk_rows));
+    }
+
+    let stream = scan
+        .into_array_stream()
+        .context("Failed to scan Vortex file")?;
+
+    let session = create_session();
+    let mut output_file = tokio::fs::File::create(output).await.context(format!(
+        "Failed to create output file: {}",
+        output.display()
+    ))?;
+
+    session
+        .write_options()
+        .with_strategy(build_write_strategy(&options))
+        .write(&mut output_file, stream)
+        .await
+        .context("Failed to write Vortex file")?;
+
+    let elapsed = start.elapsed();
+    let rewritten_file = open_vortex_file(output).await?;
+    let before = FileSummary::read(input, &vortex_file).await?;
+    let after = FileSummary::read(output, &rewritten_file).await?;
+
+    println!("=== Vortex Rewrite ===");
+    println!("Input: {}", input.display());
+    println!("Output: {}", output.display());
+    println!("Rows: {}", rewritten_file.row_count());
+    println!("Compression: {:?}", options.compression);
+    println!(
+        "Stats layouts: {}",
+        if options.no_stats { "no" } else { "yes" }
+    );
+    println!(
+        "Chunks per column: up to {} -> up to {}",
+        before.max_chunks(),
+        after.max_chunks()
+    );
+    println!(
+        "File size: {} -> {}",
+        format_bytes(before.file_size),
+        format_bytes(after.file_size)
+    );
+    println!("Elapsed: {:.2}s", elapsed.as_secs_f64());
+    println!();
+
+    println!(
+        "{:<30} {:>12} {:>12} {:>8} {:>14}  {}",
+        "Column", "Before", "After", "Change", "Chunks", "First chunk encoding"
+    );
+    println!("{}", "-".repeat(125));
+
+    for (name, before_chunks) in &before.chunks {
+        let before_bytes = before.column_bytes(name);
+        let after_bytes = after.column_bytes(name);
+
+        let change = match (before_bytes, after_bytes) {
+            (Some(b), Some(a)) if b > 0 => {
+                format!("{:+.1}%", (a as f64 - b as f64) * 100.0 / b as f64)
+            }
+            _ => "-".to_string(),
+        };
+        let chunks = match after.column_chunks(name) {
+            Some(after_chunks) => format!("{} -> {}", before_chunks, after_chunks),
+            None => format!("{} -> -", before_chunks),
+        };
+        let encodings = if after_bytes.is_some() {
+            format!(
+                "{} -> {}",
+                before.column_encodings(name),
+                after.column_encodings(name)
+            )
+        } else {
+            format!("{} -> dropped", before.column_encodings(name))
+        };
+
+        println!(
+            "{:<30} {:>12} {:>12} {:>8} {:>14}  {}",
+            truncate_string(name, 30),
+            before_bytes
+                .map(format_bytes)
+                .unwrap_or_else(|| "-".to_string()),
+            after_bytes
+                .map(format_bytes)
+                .unwrap_or_else(|| "-".to_string()),
+            change,
+            chunks,
+            encodings
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use futures::StreamExt;
+    use std::sync::Arc;
+    use vortex_array::Array;
+
+    use crate::testing::{chunked_write_options, write_vortex_file_with};
+
+    /// 1000 rows in chunks of 10 rows
+    async fn write_small_chunks(path: &Path) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("host", DataType::Utf8, true),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..1000)),
+                Arc::new(StringArray::from_iter(
+                    (0..1000).map(|i| (i % 10 != 0).then(|| format!("host-{}", i % 3))),
+                )),
+                Arc::new(Float64Array::from_iter_values((0..1000).map(|i| i as f64))),
+            ],
+        )
+        .unwrap();
+        write_vortex_file_with(path, vec![batch], &chunked_write_options(10))
+            .await
+            .unwrap();
+    }
+
+    /// Length of every chunk scanned from a Vortex file
+    async fn chunk_lens(vortex_file: &VortexFile) -> Vec<usize> {
+        let stream = vortex_file.scan().unwrap().into_array_stream().unwrap();
+        stream.map(|chunk| chunk.unwrap().len()).collect().await
+    }
+
+    #[tokio::test]
+    async fn rewrite_preserves_rows_and_dtype() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.vortex");
+        let output = dir.path().join("out.vortex");
+        write_small_chunks(&input).await;
+
+        run_rewrite(&input, &output, &[], chunked_write_options(250))
+            .await
+            .unwrap();
+
+        let before = open_vortex_file(&input).await.unwrap();
+        let after = open_vortex_file(&output).await.unwrap();
+        assert_eq!(after.row_count(), 1000);
+        assert_eq!(after.dtype(), before.dtype());
+        assert_eq!(chunk_lens(&after).await, vec![250; 4]);
+    }
+
+    #[tokio::test]
+    async fn rewrite_projects_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.vortex");
+        let output = dir.path().join("out.vortex");
+        write_small_chunks(&input).await;
+
+        let columns = ["value".to_string(), "id".to_string()];
+        run_rewrite(&input, &output, &columns, chunked_write_options(100))
+            .await
+            .unwrap();
+
+        let before = open_vortex_file(&input).await.unwrap();
+        let after = open_vortex_file(&output).await.unwrap();
+        assert_eq!(after.row_count(), 1000);
+
+        let fields = after.dtype().as_struct_fields_opt().unwrap();
+        let names: Vec<String> = fields.names().iter().map(|n| n.to_string()).collect();
+        assert_eq!(names, vec!["value", "id"]);
+
+        let before_fields = before.dtype().as_struct_fields_opt().unwrap();
+        for name in ["value", "id"] {
+            assert_eq!(fields.field(name), before_fields.field(name));
+        }
+
+        let missing = ["missing".to_string()];
+        assert!(
+            run_rewrite(&input, &output, &missing, chunked_write_options(100))
+                .await
+                .is_err()
+        );
+        assert!(
+            run_rewrite(&input, &input, &[], chunked_write_options(100))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use anyhow::{Context, Result};
+use arrow::array::{AsArray, RecordBatch};
+use arrow::datatypes::{Field, Schema};
+use arrow::util::pretty::pretty_format_batches;
+use futures::StreamExt;
+use std::io::{Stdout, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use vortex::expr::{root, select};
+use vortex_array::ArrayRef;
+use vortex_array::arrow::IntoArrowArray;
+use vortex_dtype::{FieldName, FieldNames};
+
+use crate::{OutputFormat, open_vortex_file};
+
+/// Parse a row range such as `1000..2000`, `1000..` or `..2000`
+pub fn parse_row_range(s: &str) -> std::result::Result<Range<u64>, String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("Invalid row range: {}. Use START..END", s))?;
+
+    let start = if start.is_empty() {
+        0
+    } else {
+        start
+            .parse()
+            .map_err(|_| format!("Invalid row range start: {}", start))?
+    };
+    let end = if end.is_empty() {
+        u64::MAX
+    } else {
+        end.parse()
+            .map_err(|_| format!("Invalid row range end: {}", end))?
+    };
+
+    if end < start {
+        return Err(format!(
+            "Invalid row range: {}. END must not be before START",
+            s
+        ));
+    }
+
+    Ok(start..end)
+}
+
+/// Convert a Vortex array into an Arrow record batch
+///
+/// Non-struct arrays become a single column named `value`.
+pub fn to_record_batch(array: ArrayRef) -> Result<RecordBatch> {
+    let arrow_array = array.into_arrow_preferred()?;
+
+    if let Some(struct_array) = arrow_array.as_struct_opt() {
+        return Ok(RecordBatch::from(struct_array));
+    }
+
+    let schema = Schema::new(vec![Field::new(
+        "value",
+        arrow_array.data_type().clone(),
+        true,
+    )]);
+    Ok(RecordBatch::try_new(Arc::new(schema), vec![arrow_array])?)
+}
+
+/// Number of rows aligned together in text output
+const TABLE_ROWS: usize = 1000;
+
+/// Writes record batches as text tables of at most `TABLE_ROWS` rows
+///
+/// Aligning the columns needs the rows of a table up front, so rows are
+/// buffered until a table is full and printed then, instead of keeping the
+/// whole output in memory.
+pub struct TableWriter<W: Write> {
+    out: W,
+    batches: Vec<RecordBatch>,
+    buffered: usize,
+    rows: usize,
+    tables: usize,
+}
+
+impl<W: Write> TableWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            batches: Vec::new(),
+            buffered: 0,
+            rows: 0,
+            tables: 0,
+        }
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        // Keep an empty batch so an empty result still prints its header
+        if batch.num_rows() == 0 && self.batches.is_empty() && self.tables == 0 {
+            self.batches.push(batch.clone());
+        }
+
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            let len = (TABLE_ROWS - self.buffered).min(batch.num_rows() - offset);
+            self.batches.push(batch.slice(offset, len));
+            self.buffered += len;
+            offset += len;
+
+            if self.buffered == TABLE_ROWS {
+                self.flush()?;
+            }
+        }
+        self.rows += batch.num_rows();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        writeln!(self.out, "{}", pretty_format_batches(&self.batches)?)?;
+        self.batches.clear();
+        self.buffered = 0;
+        self.tables += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        if self.buffered > 0 || self.tables == 0 {
+            self.flush()?;
+        }
+        writeln!(self.out, "\n{} row(s)", self.rows)?;
+        Ok(self.out)
+    }
+}
+
+/// Writes record batches to stdout as they arrive
+pub enum BatchWriter {
+    Table(TableWriter<Stdout>),
+    Json(arrow::json::ArrayWriter<Stdout>),
+    Ndjson(arrow::json::LineDelimitedWriter<Stdout>),
+    Csv(arrow::csv::Writer<Stdout>),
+}
+
+impl BatchWriter {
+    pub fn new(format: &OutputFormat) -> Self {
+        match format {
+            OutputFormat::Text => BatchWriter::Table(TableWriter::new(std::io::stdout())),
+            OutputFormat::Json => {
+                BatchWriter::Json(arrow::json::ArrayWriter::new(std::io::stdout()))
+            }
+            OutputFormat::Ndjson => {
+                BatchWriter::Ndjson(arrow::json::LineDelimitedWriter::new(std::io::stdout()))
+            }
+            OutputFormat::Csv => BatchWriter::Csv(arrow::csv::Writer::new(std::io::stdout())),
+        }
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self {
+            BatchWriter::Table(writer) => writer.write(batch)?,
+            BatchWriter::Json(writer) => writer.write(batch)?,
+            BatchWriter::Ndjson(writer) => writer.write(batch)?,
+            BatchWriter::Csv(writer) => writer.write(batch)?,
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self {
+            BatchWriter::Table(writer) => {
+                writer.finish()?;
+            }
+            BatchWriter::Json(mut writer) => {
+                writer.finish()?;
+                println!();
+            }
+            BatchWriter::Ndjson(mut writer) => writer.finish()?,
+            BatchWriter::Csv(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Print rows of a Vortex file, reading only the projected columns and row range
+pub async fn show_rows(
+    path: &Path,
+    columns: &[String],
+    row_range: Range<u64>,
+    format: OutputFormat,
+) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let row_count = vortex_file.row_count();
+    let row_range = row_range.start.min(row_count)..row_range.end.min(row_count);
+
+    let mut scan = vortex_file.scan()?.with_row_range(row_range);
+
+    if !columns.is_empty() {
+        let fields = vortex_file
+            .dtype()
+            .as_struct_fields_opt()
+            .ok_or_else(|| anyhow::anyhow!("Column projection requires a struct file"))?;
+
+        for column in columns {
+            if !fields
+                .names()
+                .iter()
+                .any(|name| name.as_ref() == column.as_str())
+            {
+                anyhow::bail!("Column not found: {}", column);
+            }
+        }
+
+        let names: FieldNames = columns
+            .iter()
+            .map(|c| FieldName::from(c.as_str()))
+            .collect();
+        scan = scan.with_projection(select(names, root()));
+    }
+
+    let stream = scan
+        .into_array_stream()
+        .context("Failed to scan Vortex file")?;
+    let mut stream = std::pin::pin!(stream);
+    let mut writer = BatchWriter::new(&format);
+
+    while let Some(chunk) = stream.next().await {
+        writer.write(&to_record_batch(chunk?)?)?;
+    }
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+    use arrow::datatypes::DataType;
+
+    fn batch(values: std::ops::Range<i64>) -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from_iter_values(values))],
+        )
+        .unwrap()
+    }
+
+    fn table_output(batches: &[RecordBatch]) -> String {
+        let mut writer = TableWriter::new(Vec::new());
+        for batch in batches {
+            writer.write(batch).unwrap();
+        }
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn row_ranges() {
+        assert_eq!(parse_row_range("10..20").unwrap(), 10..20);
+        assert_eq!(parse_row_range("10..").unwrap(), 10..u64::MAX);
+        assert_eq!(parse_row_range("..20").unwrap(), 0..20);
+        assert!(parse_row_range("20..10").is_err());
+        assert!(parse_row_range("10").is_err());
+    }
+
+    #[test]
+    fn tables_hold_at_most_table_rows() {
+        let output = table_output(&[batch(0..1500), batch(1500..2500)]);
+
+        assert_eq!(output.matches("| id ").count(), 3);
+        assert!(output.ends_with("\n2500 row(s)\n"));
+        for id in [0, 999, 1000, 2499] {
+            assert!(output.contains(&format!("| {} ", id)), "missing row {}", id);
+        }
+    }
+
+    #[test]
+    fn empty_output_prints_a_table() {
+        let output = table_output(&[]);
+        assert!(output.ends_with("\n0 row(s)\n"));
+    }
+}
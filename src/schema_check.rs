@@ -0,0 +1,598 @@
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use vortex_dtype::{DType, PType};
+
+use crate::dataset::{MAX_CONCURRENT_FILES, dataset_files};
+use crate::{DocumentFormat, model, open_vortex_file, print_json};
+
+/// Number of files listed per schema variant in text output without `--verbose`
+const LISTED_FILES: usize = 10;
+
+/// A field-level difference between two dtypes
+#[derive(Clone, Debug)]
+pub enum FieldChange {
+    Added {
+        field: String,
+        dtype: DType,
+    },
+    Removed {
+        field: String,
+        dtype: DType,
+    },
+    Nullability {
+        field: String,
+        from: bool,
+        to: bool,
+    },
+    Widened {
+        field: String,
+        from: DType,
+        to: DType,
+    },
+    TypeChanged {
+        field: String,
+        from: DType,
+        to: DType,
+    },
+}
+
+impl FieldChange {
+    pub fn field(&self) -> &str {
+        match self {
+            FieldChange::Added { field, .. }
+            | FieldChange::Removed { field, .. }
+            | FieldChange::Nullability { field, .. }
+            | FieldChange::Widened { field, .. }
+            | FieldChange::TypeChanged { field, .. } => field,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FieldChange::Added { .. } => "added",
+            FieldChange::Removed { .. } => "removed",
+            FieldChange::Nullability { .. } => "nullability",
+            FieldChange::Widened { .. } => "widened",
+            FieldChange::TypeChanged { .. } => "type_changed",
+        }
+    }
+
+    /// Whether readers expecting the old schema can fail on the new one
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            FieldChange::Added { .. } | FieldChange::Widened { .. } => false,
+            // Readers of a non-nullable field do not expect nulls
+            FieldChange::Nullability { to, .. } => *to,
+            FieldChange::Removed { .. } | FieldChange::TypeChanged { .. } => true,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut change_json = serde_json::json!({
+            "change": self.kind(),
+            "field": self.field(),
+            "breaking": self.is_breaking(),
+        });
+
+        match self {
+            FieldChange::Added { dtype, .. } | FieldChange::Removed { dtype, .. } => {
+                change_json["dtype"] = model::dtype_json(dtype);
+            }
+            FieldChange::Nullability { from, to, .. } => {
+                change_json["from_nullable"] = serde_json::json!(from);
+                change_json["to_nullable"] = serde_json::json!(to);
+            }
+            FieldChange::Widened { from, to, .. } | FieldChange::TypeChanged { from, to, .. } => {
+                change_json["from"] = model::dtype_json(from);
+                change_json["to"] = model::dtype_json(to);
+            }
+        }
+
+        change_json
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nullability = |nullable: &bool| {
+            if *nullable {
+                "nullable"
+            } else {
+                "non-nullable"
+            }
+        };
+
+        match self {
+            FieldChange::Added { field, dtype } => write!(f, "+ added        {}: {}", field, dtype),
+            FieldChange::Removed { field, dtype } => {
+                write!(f, "- removed      {}: {}", field, dtype)
+            }
+            FieldChange::Nullability { field, from, to } => write!(
+                f,
+                "~ nullability  {}: {} -> {}",
+                field,
+                nullability(from),
+                nullability(to)
+            ),
+            FieldChange::Widened { field, from, to } => {
+                write!(f, "^ widened      {}: {} -> {}", field, from, to)
+            }
+            FieldChange::TypeChanged { field, from, to } => {
+                write!(f, "! type changed {}: {} -> {}", field, from, to)
+            }
+        }
+    }
+}
+
+/// Whether every value of `from` can be represented by `to`
+fn ptype_widens(from: PType, to: PType) -> bool {
+    if from == to {
+        return true;
+    }
+
+    // Integers widen to floats whose mantissa holds all their values, which is the case
+    // whenever the float is wider
+    if to.is_float() {
+        return to.byte_width() > from.byte_width();
+    }
+    if from.is_float() || (from.is_signed_int() && to.is_unsigned_int()) {
+        return false;
+    }
+    to.byte_width() > from.byte_width()
+}
+
+/// Whether `to` can hold every value of `from`, ignoring nullability
+fn widens(from: &DType, to: &DType) -> bool {
+    match (from, to) {
+        (DType::Primitive(from, _), DType::Primitive(to, _)) => ptype_widens(*from, *to),
+        (DType::Decimal(from, _), DType::Decimal(to, _)) => {
+            let from_integer = from.precision() as i16 - from.scale() as i16;
+            let to_integer = to.precision() as i16 - to.scale() as i16;
+            to.scale() >= from.scale() && to_integer >= from_integer
+        }
+        (DType::Null, to) => to.is_nullable(),
+        _ => false,
+    }
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+fn diff_field(path: &str, from: &DType, to: &DType, changes: &mut Vec<FieldChange>) {
+    if from == to {
+        return;
+    }
+
+    if from.is_nullable() != to.is_nullable() {
+        changes.push(FieldChange::Nullability {
+            field: path.to_string(),
+            from: from.is_nullable(),
+            to: to.is_nullable(),
+        });
+    }
+
+    if from.as_struct_fields_opt().is_some() && to.as_struct_fields_opt().is_some() {
+        diff_struct(path, from, to, changes);
+    } else if from.as_nonnullable() != to.as_nonnullable() {
+        let (field, from, to) = (path.to_string(), from.clone(), to.clone());
+        changes.push(if widens(&from, &to) {
+            FieldChange::Widened { field, from, to }
+        } else {
+            FieldChange::TypeChanged { field, from, to }
+        });
+    }
+}
+
+fn diff_struct(path: &str, from: &DType, to: &DType, changes: &mut Vec<FieldChange>) {
+    let (Some(from_fields), Some(to_fields)) =
+        (from.as_struct_fields_opt(), to.as_struct_fields_opt())
+    else {
+        return;
+    };
+
+    for (name, from_dtype) in from_fields.names().iter().zip(from_fields.fields()) {
+        let field = join_path(path, name);
+        match to_fields.field(name.as_ref()) {
+            Some(to_dtype) => diff_field(&field, &from_dtype, &to_dtype, changes),
+            None => changes.push(FieldChange::Removed {
+                field,
+                dtype: from_dtype,
+            }),
+        }
+    }
+
+    for (name, to_dtype) in to_fields.names().iter().zip(to_fields.fields()) {
+        if from_fields.field(name.as_ref()).is_none() {
+            changes.push(FieldChange::Added {
+                field: join_path(path, name),
+                dtype: to_dtype,
+            });
+        }
+    }
+}
+
+/// Field-level differences going from the `from` dtype to the `to` dtype
+///
+/// Nested struct fields are compared recursively and named with dotted paths.
+pub fn diff_dtypes(from: &DType, to: &DType) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    if from.as_struct_fields_opt().is_some() && to.as_struct_fields_opt().is_some() {
+        if from.is_nullable() != to.is_nullable() {
+            changes.push(FieldChange::Nullability {
+                field: "<root>".to_string(),
+                from: from.is_nullable(),
+                to: to.is_nullable(),
+            });
+        }
+        diff_struct("", from, to, &mut changes);
+    } else {
+        diff_field("<root>", from, to, &mut changes);
+    }
+    changes
+}
+
+/// Read a baseline dtype from a Vortex file or a schema JSON file
+///
+/// The JSON file may be the output of `schema -f json`, of `metadata -f json`, or a bare dtype.
+async fn read_baseline(path: &Path) -> Result<DType> {
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .context(format!("Failed to read schema file: {}", path.display()))?;
+        let value: serde_json::Value = serde_json::from_str(&contents)
+            .context(format!("Invalid JSON in schema file: {}", path.display()))?;
+
+        let dtype = value
+            .get("vortex_dtype")
+            .or_else(|| value.get("dtype"))
+            .unwrap_or(&value);
+        return model::dtype_from_json(dtype)
+            .context(format!("Invalid dtype in schema file: {}", path.display()));
+    }
+
+    Ok(open_vortex_file(path).await?.dtype().clone())
+}
+
+/// Files sharing the same dtype
+#[derive(Debug)]
+struct SchemaGroup {
+    dtype: DType,
+    files: Vec<PathBuf>,
+    row_count: u64,
+}
+
+pub async fn run_schema_check(
+    input: &Path,
+    baseline: Option<&Path>,
+    format: DocumentFormat,
+    verbose: bool,
+) -> Result<()> {
+    let files = dataset_files(input).await?;
+    let file_count = files.len();
+
+    let mut results: Vec<(PathBuf, Result<(DType, u64)>)> = futures::stream::iter(files)
+        .map(|path| async move {
+            let result = open_vortex_file(&path)
+                .await
+                .map(|file| (file.dtype().clone(), file.row_count()));
+            (path, result)
+        })
+        .buffer_unordered(MAX_CONCURRENT_FILES)
+        .collect()
+        .await;
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut groups: Vec<SchemaGroup> = Vec::new();
+    let mut failures = Vec::new();
+    for (path, result) in results {
+        let (dtype, row_count) = match result {
+            Ok(file) => file,
+            Err(e) => {
+                failures.push((path, format!("{:#}", e)));
+                continue;
+            }
+        };
+        match groups.iter_mut().find(|g| g.dtype == dtype) {
+            Some(group) => {
+                group.files.push(path);
+                group.row_count += row_count;
+            }
+            None => groups.push(SchemaGroup {
+                dtype,
+                files: vec![path],
+                row_count,
+            }),
+        }
+    }
+
+    // Without an explicit baseline, compare against the schema used by most files
+    let (baseline_source, baseline_dtype) = match baseline {
+        Some(path) => (path.display().to_string(), read_baseline(path).await?),
+        None => {
+            let group = groups
+                .iter()
+                .enumerate()
+                .max_by_key(|(idx, g)| (g.files.len(), std::cmp::Reverse(*idx)))
+                .map(|(_, g)| g)
+                .ok_or_else(|| anyhow::anyhow!("No file could be read"))?;
+            ("most common schema".to_string(), group.dtype.clone())
+        }
+    };
+
+    let group_changes: Vec<Vec<FieldChange>> = groups
+        .iter()
+        .map(|g| diff_dtypes(&baseline_dtype, &g.dtype))
+        .collect();
+    let matching_files: usize = groups
+        .iter()
+        .zip(&group_changes)
+        .filter(|(_, changes)| changes.is_empty())
+        .map(|(g, _)| g.files.len())
+        .sum();
+    let breaking_files: usize = groups
+        .iter()
+        .zip(&group_changes)
+        .filter(|(_, changes)| changes.iter().any(FieldChange::is_breaking))
+        .map(|(g, _)| g.files.len())
+        .sum();
+    let compatible_files = file_count - failures.len() - matching_files - breaking_files;
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let check_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
+                "input": input.display().to_string(),
+                "files": file_count,
+                "baseline": {
+                    "source": baseline_source,
+                    "dtype": model::dtype_json(&baseline_dtype),
+                },
+                "variants": groups
+                    .iter()
+                    .zip(&group_changes)
+                    .enumerate()
+                    .map(|(idx, (group, changes))| serde_json::json!({
+                        "variant": idx + 1,
+                        "matches_baseline": changes.is_empty(),
+                        "row_count": group.row_count,
+                        "dtype": model::dtype_json(&group.dtype),
+                        "files": group
+                            .files
+                            .iter()
+                            .map(|p| p.display().to_string())
+                            .collect::<Vec<_>>(),
+                        "changes": changes.iter().map(FieldChange::to_json).collect::<Vec<_>>(),
+                    }))
+                    .collect::<Vec<_>>(),
+                "errors": failures
+                    .iter()
+                    .map(|(path, error)| serde_json::json!({
+                        "file": path.display().to_string(),
+                        "error": error,
+                    }))
+                    .collect::<Vec<_>>(),
+                "summary": {
+                    "matching_files": matching_files,
+                    "compatible_files": compatible_files,
+                    "breaking_files": breaking_files,
+                    "failed_files": failures.len(),
+                },
+            });
+            print_json(&check_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex Schema Check ===");
+            println!("Input: {}", input.display());
+            println!("Files: {}", file_count);
+            println!("Baseline: {}", baseline_source);
+
+            println!("\n--- Schema Variants ---");
+            for (idx, (group, changes)) in groups.iter().zip(&group_changes).enumerate() {
+                let status = if changes.is_empty() {
+                    "matches baseline".to_string()
+                } else if changes.iter().any(FieldChange::is_breaking) {
+                    format!("{} changes, breaking", changes.len())
+                } else {
+                    format!("{} changes, compatible", changes.len())
+                };
+                println!(
+                    "\nv{}: {} files, {} rows ({})",
+                    idx + 1,
+                    group.files.len(),
+                    group.row_count,
+                    status
+                );
+
+                let listed = if verbose {
+                    group.files.len()
+                } else {
+                    LISTED_FILES
+                };
+                for path in group.files.iter().take(listed) {
+                    println!("    {}", path.display());
+                }
+                if group.files.len() > listed {
+                    println!("    ... and {} more", group.files.len() - listed);
+                }
+
+                if !changes.is_empty() {
+                    println!("  Changes from baseline:");
+                    for change in changes {
+                        println!("    {}", change);
+                    }
+                }
+            }
+
+            if !failures.is_empty() {
+                println!("\n--- Errors ---");
+                for (path, error) in &failures {
+                    println!("{}: {}", path.display(), error);
+                }
+            }
+
+            println!("\n--- Summary ---");
+            println!("Schema variants: {}", groups.len());
+            println!("Files matching the baseline: {}", matching_files);
+            println!("Files with compatible changes: {}", compatible_files);
+            println!("Files with breaking changes: {}", breaking_files);
+            if !failures.is_empty() {
+                println!("Files that failed to open: {}", failures.len());
+            }
+        }
+    }
+
+    if breaking_files > 0 {
+        anyhow::bail!(
+            "{} of {} files have breaking schema changes",
+            breaking_files,
+            file_count
+        );
+    }
+    if !failures.is_empty() {
+        anyhow::bail!("Failed to read {} of {} files", failures.len(), file_count);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vortex_dtype::{DecimalDType, FieldName, FieldNames, StructFields};
+
+    fn primitive(ptype: PType, nullable: bool) -> DType {
+        DType::Primitive(ptype, nullable.into())
+    }
+
+    fn decimal(precision: u8, scale: i8) -> DType {
+        DType::Decimal(DecimalDType::new(precision, scale), false.into())
+    }
+
+    fn structure(fields: Vec<(&str, DType)>) -> DType {
+        let (names, dtypes): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+        DType::Struct(
+            StructFields::new(
+                names
+                    .into_iter()
+                    .map(FieldName::from)
+                    .collect::<FieldNames>(),
+                dtypes,
+            ),
+            false.into(),
+        )
+    }
+
+    fn summary(changes: &[FieldChange]) -> Vec<(&'static str, &str, bool)> {
+        changes
+            .iter()
+            .map(|c| (c.kind(), c.field(), c.is_breaking()))
+            .collect()
+    }
+
+    #[test]
+    fn ptype_widening() {
+        assert!(ptype_widens(PType::I32, PType::I32));
+        assert!(ptype_widens(PType::I32, PType::I64));
+        assert!(ptype_widens(PType::U8, PType::I16));
+        assert!(ptype_widens(PType::U32, PType::U64));
+        assert!(ptype_widens(PType::I32, PType::F64));
+        assert!(ptype_widens(PType::F32, PType::F64));
+
+        assert!(!ptype_widens(PType::I64, PType::I32));
+        assert!(!ptype_widens(PType::I32, PType::U64));
+        assert!(!ptype_widens(PType::U32, PType::I32));
+        assert!(!ptype_widens(PType::I32, PType::F32));
+        assert!(!ptype_widens(PType::F64, PType::I64));
+    }
+
+    #[test]
+    fn dtype_widening() {
+        assert!(widens(
+            &primitive(PType::I32, false),
+            &primitive(PType::I64, true)
+        ));
+        assert!(widens(&decimal(10, 2), &decimal(12, 2)));
+        assert!(widens(&decimal(10, 2), &decimal(12, 4)));
+        assert!(!widens(&decimal(10, 2), &decimal(12, 1)));
+        assert!(!widens(&decimal(10, 2), &decimal(10, 4)));
+        assert!(widens(&DType::Null, &DType::Utf8(true.into())));
+        assert!(!widens(&DType::Null, &DType::Utf8(false.into())));
+        assert!(!widens(
+            &DType::Utf8(false.into()),
+            &DType::Binary(false.into())
+        ));
+    }
+
+    #[test]
+    fn identical_dtypes() {
+        let dtype = structure(vec![("id", primitive(PType::I64, false))]);
+        assert!(diff_dtypes(&dtype, &dtype).is_empty());
+    }
+
+    #[test]
+    fn field_changes() {
+        let from = structure(vec![
+            ("id", primitive(PType::I32, false)),
+            ("name", DType::Utf8(false.into())),
+            ("score", primitive(PType::F64, false)),
+            ("gone", DType::Bool(false.into())),
+            ("tags", DType::Utf8(true.into())),
+        ]);
+        let to = structure(vec![
+            ("id", primitive(PType::I64, false)),
+            ("name", DType::Utf8(true.into())),
+            ("score", DType::Utf8(false.into())),
+            ("tags", DType::Utf8(false.into())),
+            ("added", DType::Bool(true.into())),
+        ]);
+
+        assert_eq!(
+            summary(&diff_dtypes(&from, &to)),
+            vec![
+                ("widened", "id", false),
+                ("nullability", "name", true),
+                ("type_changed", "score", true),
+                ("removed", "gone", true),
+                ("nullability", "tags", false),
+                ("added", "added", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_and_root_changes() {
+        let from = structure(vec![(
+            "point",
+            structure(vec![("x", primitive(PType::I32, false))]),
+        )]);
+        let to = structure(vec![(
+            "point",
+            structure(vec![
+                ("x", primitive(PType::I64, false)),
+                ("y", primitive(PType::I64, false)),
+            ]),
+        )]);
+        assert_eq!(
+            summary(&diff_dtypes(&from, &to)),
+            vec![("widened", "point.x", false), ("added", "point.y", false)]
+        );
+
+        assert_eq!(
+            summary(&diff_dtypes(
+                &primitive(PType::I32, false),
+                &primitive(PType::I64, true)
+            )),
+            vec![
+                ("nullability", "<root>", true),
+                ("widened", "<root>", false)
+            ]
+        );
+    }
+}
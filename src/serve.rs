@@ -0,0 +1,407 @@
+use anyhow::Result;
+use axum::extract::{Form, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use promql_parser::parser::{self, Expr};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+use crate::promql::{
+    MetricColumns, MetricStore, instant_query, now_ms, parse_query, parse_step, parse_time,
+    range_query, selector_matchers,
+};
+
+/// Form parameters of an API request, repeated keys such as `match[]` are kept
+type Params = Vec<(String, String)>;
+
+fn param<'a>(params: &'a Params, name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+fn required_param<'a>(params: &'a Params, name: &str) -> Result<&'a str, ApiError> {
+    param(params, name).ok_or_else(|| ApiError::bad_data(format!("missing parameter '{}'", name)))
+}
+
+/// Error response in the shape of the Prometheus HTTP API
+struct ApiError {
+    status: StatusCode,
+    error_type: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_data(message: impl ToString) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error_type: "bad_data",
+            message: message.to_string(),
+        }
+    }
+
+    fn execution(message: impl ToString) -> Self {
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            error_type: "execution",
+            message: message.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "status": "error",
+            "errorType": self.error_type,
+            "error": self.message,
+        });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+fn success(data: serde_json::Value) -> Response {
+    Json(serde_json::json!({
+        "status": "success",
+        "data": data,
+    }))
+    .into_response()
+}
+
+/// Time range of a request, defaulting to the whole history up to now
+fn time_range(params: &Params) -> Result<(i64, i64), ApiError> {
+    let start = param(params, "start")
+        .map(parse_time)
+        .transpose()
+        .map_err(ApiError::bad_data)?
+        .unwrap_or(i64::MIN / 2);
+    let end = param(params, "end")
+        .map(parse_time)
+        .transpose()
+        .map_err(ApiError::bad_data)?
+        .unwrap_or_else(now_ms);
+    Ok((start, end))
+}
+
+async fn query(
+    State(store): State<Arc<MetricStore>>,
+    Form(params): Form<Params>,
+) -> Result<Response, ApiError> {
+    let expr = parse_query(required_param(&params, "query")?).map_err(ApiError::bad_data)?;
+    let time = param(&params, "time")
+        .map(parse_time)
+        .transpose()
+        .map_err(ApiError::bad_data)?
+        .unwrap_or_else(now_ms);
+
+    let result = instant_query(&store, &expr, time)
+        .await
+        .map_err(ApiError::execution)?;
+    Ok(success(result.to_json()))
+}
+
+async fn query_range(
+    State(store): State<Arc<MetricStore>>,
+    Form(params): Form<Params>,
+) -> Result<Response, ApiError> {
+    let expr = parse_query(required_param(&params, "query")?).map_err(ApiError::bad_data)?;
+    let start = parse_time(required_param(&params, "start")?).map_err(ApiError::bad_data)?;
+    let end = parse_time(required_param(&params, "end")?).map_err(ApiError::bad_data)?;
+    let step = parse_step(required_param(&params, "step")?).map_err(ApiError::bad_data)?;
+
+    let result = range_query(&store, &expr, start, end, step)
+        .await
+        .map_err(ApiError::execution)?;
+    Ok(success(result.to_json()))
+}
+
+async fn labels(State(store): State<Arc<MetricStore>>) -> Result<Response, ApiError> {
+    Ok(success(serde_json::json!(store.label_names())))
+}
+
+async fn series(
+    State(store): State<Arc<MetricStore>>,
+    Form(params): Form<Params>,
+) -> Result<Response, ApiError> {
+    let (start, end) = time_range(&params)?;
+
+    let selectors: Vec<&str> = params
+        .iter()
+        .filter(|(k, _)| k == "match[]")
+        .map(|(_, v)| v.as_str())
+        .collect();
+    if selectors.is_empty() {
+        return Err(ApiError::bad_data("no match[] parameter provided"));
+    }
+
+    let mut result = Vec::new();
+    for selector in selectors {
+        let Ok(Expr::VectorSelector(vs)) = parser::parse(selector) else {
+            return Err(ApiError::bad_data(format!(
+                "invalid series selector: {}",
+                selector
+            )));
+        };
+
+        let series = store
+            .series(&selector_matchers(&vs), start, end)
+            .await
+            .map_err(ApiError::execution)?;
+        for labels in series {
+            if !result.contains(&labels) {
+                result.push(labels);
+            }
+        }
+    }
+
+    Ok(success(serde_json::json!(result)))
+}
+
+/// Routes of the Prometheus query API over a metric store
+///
+/// The store is shared by all requests, so the metric table is registered once.
+pub fn router(store: Arc<MetricStore>) -> Router {
+    Router::new()
+        .route("/api/v1/query", get(query).post(query))
+        .route("/api/v1/query_range", get(query_range).post(query_range))
+        .route("/api/v1/labels", get(labels).post(labels))
+        .route("/api/v1/series", get(series).post(series))
+        .with_state(store)
+}
+
+pub async fn run_serve(path: &Path, addr: SocketAddr, columns: MetricColumns) -> Result<()> {
+    let store = Arc::new(MetricStore::open(path, columns).await?);
+    let listener = TcpListener::bind(addr).await?;
+
+    println!("=== Vortex Prometheus API ===");
+    println!("Path: {}", path.display());
+    println!("Listening on http://{}", listener.local_addr()?);
+
+    axum::serve(listener, router(store)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use crate::testing::write_vortex_file;
+
+    /// Two counters sampled every 15 seconds from 0 to 60 seconds
+    async fn test_store(dir: &Path) -> Arc<MetricStore> {
+        let path = dir.join("metrics.vortex");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("__name__", DataType::Utf8, false),
+            Field::new("job", DataType::Utf8, false),
+            Field::new("_timestamp", DataType::Int64, false),
+            Field::new("value", DataType::Float64, false),
+        ]));
+
+        let mut jobs = Vec::new();
+        let mut timestamps = Vec::new();
+        let mut values = Vec::new();
+        for (job, increment) in [("api", 10.0), ("db", 5.0)] {
+            for step in 0..5 {
+                jobs.push(job);
+                timestamps.push(step * 15_000_000);
+                values.push(step as f64 * increment);
+            }
+        }
+
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["http_requests_total"; jobs.len()])),
+                Arc::new(StringArray::from(jobs)),
+                Arc::new(Int64Array::from(timestamps)),
+                Arc::new(Float64Array::from(values)),
+            ],
+        )
+        .unwrap();
+        write_vortex_file(&path, vec![batch]).await.unwrap();
+
+        let columns = MetricColumns {
+            timestamp_column: "_timestamp".to_string(),
+            timestamp_unit: TimeUnit::Microsecond,
+            value_column: "value".to_string(),
+            metric_column: "__name__".to_string(),
+        };
+        Arc::new(MetricStore::open(&path, columns).await.unwrap())
+    }
+
+    async fn test_router(dir: &Path) -> Router {
+        router(test_store(dir).await)
+    }
+
+    fn encode(value: &str) -> String {
+        value
+            .bytes()
+            .map(|b| {
+                if b.is_ascii_alphanumeric() || b"-_.".contains(&b) {
+                    (b as char).to_string()
+                } else {
+                    format!("%{:02X}", b)
+                }
+            })
+            .collect()
+    }
+
+    /// Send a GET request and return the status with the JSON body
+    async fn get(
+        router: &Router,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> (StatusCode, serde_json::Value) {
+        let query: Vec<String> = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", encode(k), encode(v)))
+            .collect();
+        let request = Request::builder()
+            .uri(format!("{}?{}", path, query.join("&")))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn query_returns_instant_vector() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = test_router(dir.path()).await;
+
+        let (status, body) = get(
+            &router,
+            "/api/v1/query",
+            &[
+                ("query", "http_requests_total{job=\"api\"}"),
+                ("time", "60"),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["data"]["resultType"], "vector");
+
+        let result = body["data"]["result"].as_array().unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0]["metric"]["job"], "api");
+        assert_eq!(result[0]["value"][1], "40");
+    }
+
+    #[tokio::test]
+    async fn invalid_query_is_bad_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = test_router(dir.path()).await;
+
+        for path in ["/api/v1/query", "/api/v1/query_range"] {
+            let (status, body) = get(
+                &router,
+                path,
+                &[
+                    ("query", "sum(http_requests_total"),
+                    ("start", "0"),
+                    ("end", "60"),
+                    ("step", "15"),
+                ],
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["errorType"], "bad_data");
+        }
+    }
+
+    #[tokio::test]
+    async fn query_range_returns_matrix() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = test_router(dir.path()).await;
+
+        let (status, body) = get(
+            &router,
+            "/api/v1/query_range",
+            &[
+                ("query", "http_requests_total"),
+                ("start", "0"),
+                ("end", "60"),
+                ("step", "15"),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["resultType"], "matrix");
+
+        let result = body["data"]["result"].as_array().unwrap();
+        assert_eq!(result.len(), 2);
+        for series in result {
+            assert_eq!(series["values"].as_array().unwrap().len(), 5);
+        }
+    }
+
+    #[tokio::test]
+    async fn labels_lists_label_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = test_router(dir.path()).await;
+
+        let (status, body) = get(&router, "/api/v1/labels", &[]).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let names = body["data"].as_array().unwrap();
+        assert!(names.contains(&serde_json::json!("__name__")));
+        assert!(names.contains(&serde_json::json!("job")));
+    }
+
+    #[tokio::test]
+    async fn series_matches_selectors() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = test_router(dir.path()).await;
+
+        let (status, body) = get(
+            &router,
+            "/api/v1/series",
+            &[("match[]", "http_requests_total{job=\"db\"}")],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["data"],
+            serde_json::json!([{ "__name__": "http_requests_total", "job": "db" }])
+        );
+
+        let (status, body) = get(&router, "/api/v1/series", &[]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["errorType"], "bad_data");
+    }
+
+    #[tokio::test]
+    async fn footers_are_read_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = test_store(dir.path()).await;
+        let router = router(store.clone());
+
+        for query in ["http_requests_total", "sum(http_requests_total)"] {
+            let (status, _) = get(
+                &router,
+                "/api/v1/query",
+                &[("query", query), ("time", "60")],
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        assert_eq!(store.footer_reads(), 1);
+    }
+}
@@ -0,0 +1,328 @@
+use anyhow::{Context, Result};
+use datafusion::prelude::SessionContext;
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::query::{print_batches, register_vortex_table};
+use crate::{OutputFormat, create_session, find_vortex_files, truncate_string};
+
+const PROMPT: &str = "vortex> ";
+const CONTINUATION_PROMPT: &str = "     -> ";
+
+/// State of an interactive shell session
+struct Shell {
+    ctx: SessionContext,
+    tables: Vec<(String, PathBuf)>,
+    timing: bool,
+}
+
+impl Shell {
+    fn print_help(&self) {
+        println!("Meta-commands:");
+        println!("  \\d <table>      Describe a table");
+        println!("  \\dt             List tables");
+        println!("  \\timing         Toggle query timing");
+        println!("  \\explain <sql>  Show the query plan");
+        println!("  \\?              Show this help");
+        println!("  \\q              Quit");
+        println!();
+        println!("SQL statements end with ';'");
+    }
+
+    fn list_tables(&self) {
+        println!("{:<30} {:<70}", "Table", "File");
+        println!("{}", "-".repeat(100));
+        for (name, path) in &self.tables {
+            println!("{:<30} {:<70}", truncate_string(name, 30), path.display());
+        }
+        println!("\n{} table(s)", self.tables.len());
+    }
+
+    async fn describe_table(&self, name: &str) -> Result<()> {
+        let table = self
+            .ctx
+            .table(name)
+            .await
+            .context(format!("Table not found: {}", name))?;
+        let schema = table.schema();
+
+        println!(
+            "{:<5} {:<60} {:<20} {:<10}",
+            "Index", "Field Name", "Data Type", "Nullable"
+        );
+        println!("{}", "-".repeat(100));
+
+        for (idx, field) in schema.fields().iter().enumerate() {
+            let data_type_str = format!("{:?}", field.data_type());
+            let nullable_str = if field.is_nullable() { "true" } else { "false" };
+
+            println!(
+                "{:<5} {:<60} {:<20} {:<10}",
+                idx,
+                truncate_string(field.name(), 60),
+                truncate_string(&data_type_str, 20),
+                nullable_str
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn run_sql(&self, sql: &str) -> Result<()> {
+        let start = Instant::now();
+        let batches = self.ctx.sql(sql).await?.collect().await?;
+        print_batches(sql, &batches, &OutputFormat::Text)?;
+
+        if self.timing {
+            println!("Time: {:.3} ms", start.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        Ok(())
+    }
+
+    /// Handle a meta-command, returns false when the shell should exit
+    async fn handle_meta(&mut self, line: &str) -> Result<bool> {
+        let (command, arg) = match line.split_once(char::is_whitespace) {
+            Some((command, arg)) => (command, arg.trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            "\\q" | "\\quit" => return Ok(false),
+            "\\?" | "\\h" | "\\help" => self.print_help(),
+            "\\dt" => self.list_tables(),
+            "\\d" => {
+                if arg.is_empty() {
+                    self.list_tables();
+                } else {
+                    self.describe_table(arg).await?;
+                }
+            }
+            "\\timing" => {
+                self.timing = !self.timing;
+                println!("Timing is {}", if self.timing { "on" } else { "off" });
+            }
+            "\\explain" => {
+                if arg.is_empty() {
+                    anyhow::bail!("Usage: \\explain <sql>");
+                }
+                let sql = arg.trim_end_matches(';');
+                self.run_sql(&format!("EXPLAIN {}", sql)).await?;
+            }
+            _ => anyhow::bail!("Unknown command: {}. Try \\?", command),
+        }
+
+        Ok(true)
+    }
+}
+
+/// SQL identifier for a file stem: lowercase, with other characters than letters, digits and
+/// `_` replaced by `_`, so the table can be queried without quoting
+fn table_identifier(stem: &str) -> String {
+    let mut name: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Table name of each file, from its stem, with `_2`, `_3`, ... appended to repeated names
+fn table_names(files: Vec<PathBuf>) -> Vec<(String, PathBuf)> {
+    let mut tables: Vec<(String, PathBuf)> = Vec::with_capacity(files.len());
+    for path in files {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let base = table_identifier(&stem);
+
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while tables.iter().any(|(existing, _)| existing == &name) {
+            suffix += 1;
+            name = format!("{}_{}", base, suffix);
+        }
+        tables.push((name, path));
+    }
+    tables
+}
+
+/// Register every Vortex file under a directory as a table
+async fn register_tables(ctx: &SessionContext, dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let session = create_session();
+    let mut tables = Vec::new();
+
+    for (name, path) in table_names(find_vortex_files(dir).await?) {
+        if path
+            .file_stem()
+            .is_none_or(|stem| stem.to_string_lossy() != name)
+        {
+            eprintln!("Note: {} is registered as table '{}'", path.display(), name);
+        }
+
+        match register_vortex_table(ctx, &session, &name, &path).await {
+            Ok(()) => tables.push((name, path)),
+            Err(e) => eprintln!("Warning: Failed to register {}: {}", path.display(), e),
+        }
+    }
+
+    tables.sort();
+    Ok(tables)
+}
+
+pub async fn run_shell(dir: &Path) -> Result<()> {
+    let ctx = SessionContext::new();
+    let tables = register_tables(&ctx, dir).await?;
+
+    println!("=== Vortex SQL Shell ===");
+    println!("Directory: {}", dir.display());
+    println!("Registered {} table(s). Type \\? for help.\n", tables.len());
+
+    let mut shell = Shell {
+        ctx,
+        tables,
+        timing: false,
+    };
+
+    let mut editor = DefaultEditor::new()?;
+    let mut buffer = String::new();
+
+    loop {
+        let prompt = if buffer.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        };
+
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        // Meta-commands are single-line and only accepted outside a statement
+        if buffer.is_empty() && trimmed.starts_with('\\') {
+            let _ = editor.add_history_entry(trimmed);
+            match shell.handle_meta(trimmed).await {
+                Ok(true) => continue,
+                Ok(false) => break,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    continue;
+                }
+            }
+        }
+
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(trimmed);
+
+        if !buffer.ends_with(';') {
+            continue;
+        }
+
+        let sql = std::mem::take(&mut buffer);
+        let _ = editor.add_history_entry(sql.as_str());
+
+        if let Err(e) = shell.run_sql(&sql).await {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{AsArray, Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Int64Type, Schema};
+    use std::sync::Arc;
+
+    use crate::testing::write_vortex_file;
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(table_identifier("metrics"), "metrics");
+        assert_eq!(table_identifier("Metrics-2024.01"), "metrics_2024_01");
+        assert_eq!(table_identifier("2024_metrics"), "_2024_metrics");
+        assert_eq!(table_identifier("my table"), "my_table");
+        assert_eq!(table_identifier(""), "_");
+    }
+
+    #[test]
+    fn repeated_stems_get_a_suffix() {
+        let names = table_names(vec![
+            PathBuf::from("a/metrics.vortex"),
+            PathBuf::from("b/metrics.vortex"),
+            PathBuf::from("c/Metrics.vortex"),
+            PathBuf::from("c/metrics_2.vortex"),
+        ]);
+        let names: Vec<&str> = names.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["metrics", "metrics_2", "metrics_3", "metrics_2_2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn registered_tables_are_queryable() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("a")).unwrap();
+        std::fs::create_dir(dir.path().join("b")).unwrap();
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        for (path, rows) in [
+            ("a/events.vortex", 3),
+            ("b/events.vortex", 5),
+            ("b/Daily-Events.vortex", 7),
+        ] {
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int64Array::from_iter_values(0..rows))],
+            )
+            .unwrap();
+            write_vortex_file(&dir.path().join(path), vec![batch])
+                .await
+                .unwrap();
+        }
+
+        let ctx = SessionContext::new();
+        let tables = register_tables(&ctx, dir.path()).await.unwrap();
+        let names: Vec<&str> = tables.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["daily_events", "events", "events_2"]);
+
+        for (name, rows) in [("daily_events", 7), ("events", 3), ("events_2", 5)] {
+            let batches = ctx
+                .sql(&format!("SELECT count(*) FROM {}", name))
+                .await
+                .unwrap()
+                .collect()
+                .await
+                .unwrap();
+            let count = batches[0].column(0).as_primitive::<Int64Type>().value(0);
+            assert_eq!(count, rows);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use anyhow::Result;
+use futures::StreamExt;
+use std::collections::BTreeSet;
+use std::path::Path;
+use vortex::expr::{root, select};
+use vortex_array::{Array, IntoArray};
+use vortex_dtype::{DType, FieldName, FieldNames};
+use vortex_file::{SegmentSpec, VortexFile};
+use vortex_layout::LayoutRef;
+
+use crate::{DocumentFormat, model, open_vortex_file, print_json, truncate_string};
+
+/// On-disk and in-memory size of a single column
+#[derive(Clone, Debug)]
+pub struct ColumnSize {
+    pub name: String,
+    pub compressed_bytes: u64,
+    pub uncompressed_bytes: Option<u64>,
+    pub segment_count: usize,
+}
+
+impl ColumnSize {
+    pub fn ratio(&self) -> Option<f64> {
+        self.uncompressed_bytes
+            .filter(|_| self.compressed_bytes > 0)
+            .map(|u| u as f64 / self.compressed_bytes as f64)
+    }
+}
+
+/// Format a byte count with a binary unit
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.2} {}", value, UNITS[unit])
+    }
+}
+
+/// Collect the ids of all segments referenced by a layout and its children
+pub fn collect_segment_ids(layout: &LayoutRef, segment_ids: &mut BTreeSet<u32>) {
+    for segment_id in layout.segment_ids() {
+        segment_ids.insert(*segment_id);
+    }
+
+    for idx in 0..layout.nchildren() {
+        if let Ok(child) = layout.child(idx) {
+            collect_segment_ids(&child, segment_ids);
+        }
+    }
+}
+
+/// Sum the on-disk length of a set of segments
+pub fn segments_bytes(segment_ids: &BTreeSet<u32>, segment_map: &[SegmentSpec]) -> u64 {
+    segment_ids
+        .iter()
+        .filter_map(|id| segment_map.get(*id as usize))
+        .map(|spec| spec.length as u64)
+        .sum()
+}
+
+/// Estimate the canonical in-memory size of `row_count` values of a dtype
+///
+/// Returns `None` for variable-width types, which need to be decoded to know their size.
+pub fn estimated_uncompressed_bytes(dtype: &DType, row_count: u64) -> Option<u64> {
+    let validity = if dtype.is_nullable() {
+        row_count.div_ceil(8)
+    } else {
+        0
+    };
+
+    let values = match dtype {
+        DType::Null => 0,
+        DType::Bool(_) => row_count.div_ceil(8),
+        DType::Primitive(ptype, _) => ptype.byte_width() as u64 * row_count,
+        DType::Decimal(decimal, _) => {
+            let width = match decimal.precision() {
+                0..=2 => 1,
+                3..=4 => 2,
+                5..=9 => 4,
+                10..=18 => 8,
+                19..=38 => 16,
+                _ => 32,
+            };
+            width * row_count
+        }
+        DType::Struct(fields, _) => {
+            let mut total = 0;
+            for field in fields.fields() {
+                total += estimated_uncompressed_bytes(&field, row_count)?;
+            }
+            total
+        }
+        DType::Extension(ext) => estimated_uncompressed_bytes(ext.storage_dtype(), row_count)?,
+        _ => return None,
+    };
+
+    Some(values + validity)
+}
+
+/// Attribute the file's segments to its top-level columns
+pub fn column_sizes(vortex_file: &VortexFile) -> Vec<ColumnSize> {
+    let layout = vortex_file.footer().layout();
+    let segment_map = vortex_file.footer().segment_map();
+    let row_count = vortex_file.row_count();
+
+    let column = |name: String, layout: &LayoutRef| {
+        let mut segment_ids = BTreeSet::new();
+        collect_segment_ids(layout, &mut segment_ids);
+
+        ColumnSize {
+            name,
+            compressed_bytes: segments_bytes(&segment_ids, segment_map),
+            uncompressed_bytes: estimated_uncompressed_bytes(layout.dtype(), row_count),
+            segment_count: segment_ids.len(),
+        }
+    };
+
+    if layout.encoding().to_string() != "vortex.struct" {
+        return vec![column("<root>".to_string(), layout)];
+    }
+
+    let mut columns: Vec<ColumnSize> = (0..layout.nchildren())
+        .filter_map(|idx| {
+            let child = layout.child(idx).ok()?;
+            Some(column(layout.child_type(idx).name().to_string(), &child))
+        })
+        .collect();
+
+    columns.sort_by(|a, b| b.compressed_bytes.cmp(&a.compressed_bytes));
+    columns
+}
+
+/// Replace estimated uncompressed sizes with the canonical size of the decoded columns
+///
+/// Each column is scanned on its own, one chunk at a time.
+async fn decode_column_sizes(vortex_file: &VortexFile, columns: &mut [ColumnSize]) -> Result<()> {
+    if vortex_file.dtype().as_struct_fields_opt().is_none() {
+        return Ok(());
+    }
+
+    for column in columns.iter_mut() {
+        let names: FieldNames = [FieldName::from(column.name.as_str())]
+            .into_iter()
+            .collect();
+        let stream = vortex_file
+            .scan()?
+            .with_projection(select(names, root()))
+            .into_array_stream()?;
+        let mut stream = std::pin::pin!(stream);
+
+        let mut bytes = 0;
+        while let Some(chunk) = stream.next().await {
+            bytes += chunk?.to_canonical()?.into_array().nbytes() as u64;
+        }
+        column.uncompressed_bytes = Some(bytes);
+    }
+
+    Ok(())
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio
+        .map(|r| format!("{:.2}x", r))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn percent_of(bytes: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        bytes as f64 * 100.0 / total as f64
+    }
+}
+
+/// Column size rows as JSON
+pub fn column_sizes_json(columns: &[ColumnSize], file_size: u64) -> serde_json::Value {
+    serde_json::Value::Array(
+        columns
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "name": c.name,
+                    "compressed_bytes": c.compressed_bytes,
+                    "uncompressed_bytes": c.uncompressed_bytes,
+                    "ratio": c.ratio(),
+                    "percent_of_file": percent_of(c.compressed_bytes, file_size),
+                    "segments": c.segment_count,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Print column sizes as a table
+pub fn print_column_sizes(columns: &[ColumnSize], file_size: u64) {
+    println!(
+        "{:<40} {:>14} {:>14} {:>8} {:>8} {:>9}",
+        "Column", "Compressed", "Uncompressed", "Ratio", "% File", "Segments"
+    );
+    println!("{}", "-".repeat(98));
+
+    for c in columns {
+        println!(
+            "{:<40} {:>14} {:>14} {:>8} {:>7.2}% {:>9}",
+            truncate_string(&c.name, 40),
+            format_bytes(c.compressed_bytes),
+            c.uncompressed_bytes
+                .map(format_bytes)
+                .unwrap_or_else(|| "-".to_string()),
+            format_ratio(c.ratio()),
+            percent_of(c.compressed_bytes, file_size),
+            c.segment_count
+        );
+    }
+
+    let data_bytes: u64 = columns.iter().map(|c| c.compressed_bytes).sum();
+    let metadata_bytes = file_size.saturating_sub(data_bytes);
+
+    println!("{}", "-".repeat(98));
+    println!(
+        "{:<40} {:>14} {:>14} {:>8} {:>7.2}%",
+        "Footer and metadata",
+        format_bytes(metadata_bytes),
+        "",
+        "",
+        percent_of(metadata_bytes, file_size)
+    );
+    println!("{:<40} {:>14}", "Total file size", format_bytes(file_size));
+}
+
+pub async fn show_size(path: &Path, format: DocumentFormat, decode: bool) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let file_size = tokio::fs::metadata(path).await?.len();
+
+    let mut columns = column_sizes(&vortex_file);
+    if decode {
+        decode_column_sizes(&vortex_file, &mut columns).await?;
+    }
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let size_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
+                "file": path.display().to_string(),
+                "file_size": file_size,
+                "row_count": vortex_file.row_count(),
+                "columns": column_sizes_json(&columns, file_size),
+            });
+            print_json(&size_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex Column Sizes ===");
+            println!("File: {}", path.display());
+            println!("Row Count: {}", vortex_file.row_count());
+            println!();
+
+            print_column_sizes(&columns, file_size);
+
+            if !decode && columns.iter().any(|c| c.uncompressed_bytes.is_none()) {
+                println!(
+                    "\nUse --decode to compute the uncompressed size of variable-width columns"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
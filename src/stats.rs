@@ -0,0 +1,340 @@
+use anyhow::Result;
+use arrow::util::display::array_value_to_string;
+use std::path::Path;
+use vortex_array::IntoArray;
+use vortex_array::arrays::ConstantArray;
+use vortex_array::arrow::IntoArrowArray;
+use vortex_array::stats::{Precision, Stat, StatsSet};
+use vortex_dtype::DType;
+use vortex_file::VortexFile;
+use vortex_scalar::Scalar;
+
+use crate::size::format_bytes;
+use crate::{DocumentFormat, model, open_vortex_file, print_json, truncate_string};
+
+/// Statistics reported for each column, with their JSON name
+///
+/// The true count of a boolean column is its sum.
+const REPORTED_STATS: [(&str, Stat); 8] = [
+    ("min", Stat::Min),
+    ("max", Stat::Max),
+    ("null_count", Stat::NullCount),
+    ("nan_count", Stat::NaNCount),
+    ("is_sorted", Stat::IsSorted),
+    ("is_constant", Stat::IsConstant),
+    ("true_count", Stat::Sum),
+    ("uncompressed_size", Stat::UncompressedSizeInBytes),
+];
+
+/// Width of the min and max columns of the text table
+const VALUE_WIDTH: usize = 24;
+
+/// Display a scalar by its dtype
+///
+/// Temporal values are shown as ISO-8601 and decimals with their scale, using Arrow's formatting.
+pub fn format_scalar(scalar: &Scalar) -> String {
+    if scalar.is_null() {
+        return "null".to_string();
+    }
+
+    match scalar.dtype() {
+        DType::Extension(_) | DType::Decimal(..) => {
+            let arrow = ConstantArray::new(scalar.clone(), 1)
+                .into_array()
+                .into_arrow_preferred();
+            if let Ok(arrow) = arrow
+                && let Ok(display) = array_value_to_string(&arrow, 0)
+            {
+                return display;
+            }
+            scalar.to_string()
+        }
+        DType::Utf8(_) => scalar
+            .as_utf8()
+            .value()
+            .map(|v| v.as_str().to_string())
+            .unwrap_or_else(|| "null".to_string()),
+        _ => scalar.to_string(),
+    }
+}
+
+/// A statistic of a column as stored in the file
+#[derive(Clone, Debug)]
+pub enum StatEntry {
+    Present {
+        scalar: Scalar,
+        display: String,
+        exact: bool,
+    },
+    /// The statistic applies to the column but was not written
+    Missing,
+    /// The statistic does not apply to the column's dtype
+    NotApplicable,
+}
+
+impl StatEntry {
+    pub fn new(stats: &StatsSet, dtype: &DType, name: &str, stat: Stat) -> Self {
+        if name == "true_count" && !matches!(dtype, DType::Bool(_)) {
+            return StatEntry::NotApplicable;
+        }
+        let Some(stat_dtype) = stat.dtype(dtype) else {
+            return StatEntry::NotApplicable;
+        };
+        let Some(precision) = stats.get(stat) else {
+            return StatEntry::Missing;
+        };
+
+        let (value, exact) = match precision {
+            Precision::Exact(value) => (value, true),
+            Precision::Inexact(value) => (value, false),
+        };
+        let scalar = Scalar::new(stat_dtype, value);
+        let display = match stat {
+            Stat::UncompressedSizeInBytes => scalar
+                .as_primitive()
+                .as_::<u64>()
+                .map(format_bytes)
+                .unwrap_or_else(|| format_scalar(&scalar)),
+            _ => format_scalar(&scalar),
+        };
+
+        StatEntry::Present {
+            scalar,
+            display,
+            exact,
+        }
+    }
+
+    /// Text table cell, with inexact values marked by `~`
+    pub fn cell(&self) -> String {
+        match self {
+            StatEntry::Present { display, exact, .. } => {
+                if *exact {
+                    display.clone()
+                } else {
+                    format!("~{}", display)
+                }
+            }
+            StatEntry::Missing => "missing".to_string(),
+            StatEntry::NotApplicable => "n/a".to_string(),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            StatEntry::Present {
+                scalar,
+                display,
+                exact,
+            } => serde_json::json!({
+                "status": "present",
+                "value": model::scalar_json(scalar),
+                "display": display,
+                "exact": exact,
+            }),
+            StatEntry::Missing => serde_json::json!({ "status": "missing" }),
+            StatEntry::NotApplicable => serde_json::json!({ "status": "not_applicable" }),
+        }
+    }
+}
+
+/// The reported statistics of a top-level column
+#[derive(Clone, Debug)]
+pub struct ColumnStats {
+    pub name: String,
+    pub dtype: DType,
+    /// One entry per element of `REPORTED_STATS`
+    pub entries: Vec<StatEntry>,
+}
+
+/// File-level statistics of each top-level column, if the file has any
+pub fn column_stats(vortex_file: &VortexFile) -> Option<Vec<ColumnStats>> {
+    let stats = vortex_file.file_stats()?;
+    let dtype = vortex_file.dtype();
+    let columns: Vec<(String, DType)> = match dtype.as_struct_fields_opt() {
+        Some(fields) => fields
+            .names()
+            .iter()
+            .zip(fields.fields())
+            .map(|(name, field)| (name.to_string(), field))
+            .collect(),
+        None => vec![("<root>".to_string(), dtype.clone())],
+    };
+
+    Some(
+        columns
+            .into_iter()
+            .zip(stats.iter())
+            .map(|((name, dtype), stats_set)| ColumnStats {
+                entries: REPORTED_STATS
+                    .iter()
+                    .map(|(stat_name, stat)| StatEntry::new(stats_set, &dtype, stat_name, *stat))
+                    .collect(),
+                name,
+                dtype,
+            })
+            .collect(),
+    )
+}
+
+pub fn column_stats_json(columns: &[ColumnStats]) -> serde_json::Value {
+    serde_json::Value::Array(
+        columns
+            .iter()
+            .map(|column| {
+                let mut stats_json = serde_json::Map::new();
+                for ((stat_name, _), entry) in REPORTED_STATS.iter().zip(&column.entries) {
+                    stats_json.insert(stat_name.to_string(), entry.to_json());
+                }
+                serde_json::json!({
+                    "name": column.name,
+                    "dtype": model::dtype_json(&column.dtype),
+                    "stats": stats_json,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Print one row per column with its statistics
+pub fn print_column_stats(columns: &[ColumnStats]) {
+    println!(
+        "{:<24} {:<14} {:<w$} {:<w$} {:>10} {:>10} {:<8} {:<8} {:>10} {:>12}",
+        "Column",
+        "Type",
+        "Min",
+        "Max",
+        "Nulls",
+        "NaNs",
+        "Sorted",
+        "Constant",
+        "True",
+        "Uncompressed",
+        w = VALUE_WIDTH
+    );
+    println!("{}", "-".repeat(80 + 2 * VALUE_WIDTH + 10));
+
+    for column in columns {
+        let cells: Vec<String> = column.entries.iter().map(StatEntry::cell).collect();
+        println!(
+            "{:<24} {:<14} {:<w$} {:<w$} {:>10} {:>10} {:<8} {:<8} {:>10} {:>12}",
+            truncate_string(&column.name, 24),
+            truncate_string(&column.dtype.to_string(), 14),
+            truncate_string(&cells[0], VALUE_WIDTH),
+            truncate_string(&cells[1], VALUE_WIDTH),
+            cells[2],
+            cells[3],
+            cells[4],
+            cells[5],
+            cells[6],
+            cells[7],
+            w = VALUE_WIDTH
+        );
+    }
+
+    println!("\n~ marks inexact values (bounds), missing: not written, n/a: not applicable");
+}
+
+pub async fn show_stats(path: &Path, format: DocumentFormat) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let columns = column_stats(&vortex_file);
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let stats_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
+                "file": path.display().to_string(),
+                "row_count": vortex_file.row_count(),
+                "available": columns.is_some(),
+                "columns": columns.as_deref().map(column_stats_json).unwrap_or_else(|| serde_json::json!([])),
+            });
+            print_json(&stats_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex Column Statistics ===");
+            println!("File: {}", path.display());
+            println!("Row Count: {}", vortex_file.row_count());
+            println!();
+
+            match &columns {
+                Some(columns) => print_column_stats(columns),
+                None => println!("No statistics available"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+    use std::sync::Arc;
+
+    use crate::testing::write_vortex_file;
+
+    fn entry<'a>(column: &'a ColumnStats, name: &str) -> &'a StatEntry {
+        let idx = REPORTED_STATS
+            .iter()
+            .position(|(stat_name, _)| *stat_name == name)
+            .unwrap();
+        &column.entries[idx]
+    }
+
+    fn display(entry: &StatEntry) -> &str {
+        match entry {
+            StatEntry::Present { display, .. } => display,
+            other => panic!("Statistic not present: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn non_ascii_strings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("strings.vortex");
+        let ids: ArrayRef = Arc::new(Int64Array::from_iter_values(0..100));
+        let names: ArrayRef = Arc::new(StringArray::from_iter_values((0..100).map(|i| {
+            if i % 2 == 0 {
+                format!("äöü — ein längerer Wert mit Umlauten {:03}", i)
+            } else {
+                format!("日本語のとても長いテキストの値ですね、さらに長く {:03}", i)
+            }
+        })));
+        let batch = RecordBatch::try_from_iter([("id", ids), ("name", names)]).unwrap();
+        write_vortex_file(&path, vec![batch]).await.unwrap();
+
+        let vortex_file = open_vortex_file(&path).await.unwrap();
+        let columns = column_stats(&vortex_file).unwrap();
+        let name = columns.iter().find(|c| c.name == "name").unwrap();
+
+        let min = display(entry(name, "min"));
+        let max = display(entry(name, "max"));
+        assert!(min.starts_with("äöü"), "min: {}", min);
+        assert!(max.starts_with("日本語"), "max: {}", max);
+        assert!(matches!(
+            entry(name, "true_count"),
+            StatEntry::NotApplicable
+        ));
+
+        // Table cells are cut on character boundaries
+        for stat in ["min", "max"] {
+            let cell = truncate_string(&entry(name, stat).cell(), VALUE_WIDTH);
+            assert!(cell.chars().count() <= VALUE_WIDTH);
+        }
+        assert!(truncate_string(min, VALUE_WIDTH).ends_with("..."));
+        print_column_stats(&columns);
+
+        let json = column_stats_json(&columns);
+        let name_json = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == "name")
+            .unwrap();
+        assert_eq!(name_json["stats"]["min"]["display"], min);
+        assert_eq!(name_json["stats"]["max"]["display"], max);
+
+        show_stats(&path, DocumentFormat::Json).await.unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+use vortex_layout::LayoutStrategy;
+use vortex_layout::layouts::buffered::BufferedStrategy;
+use vortex_layout::layouts::chunked::writer::ChunkedLayoutStrategy;
+use vortex_layout::layouts::collect::CollectStrategy;
+use vortex_layout::layouts::compact::CompactCompressor;
+use vortex_layout::layouts::compressed::CompressingStrategy;
+use vortex_layout::layouts::dict::writer::DictStrategy;
+use vortex_layout::layouts::flat::writer::FlatLayoutStrategy;
+use vortex_layout::layouts::repartition::{RepartitionStrategy, RepartitionWriterOptions};
+use vortex_layout::layouts::table::TableStrategy;
+use vortex_layout::layouts::zoned::writer::{ZonedLayoutOptions, ZonedStrategy};
+
+const ONE_MEG: u64 = 1 << 20;
+
+/// Rows per zone, and step in which chunks grow, when `--chunk-rows` is not given
+const DEFAULT_BLOCK_ROWS: usize = 8192;
+
+/// Compression applied to each chunk when writing a Vortex file
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// BtrBlocks-style sampling compressor, the Vortex default
+    Default,
+    /// Zstd-heavy compressor trading scan speed for smaller files
+    Compact,
+}
+
+/// Options controlling how arrays are laid out and compressed in a Vortex file
+#[derive(clap::Args, Clone, Debug)]
+pub struct WriteOptions {
+    /// Compression strategy for each chunk
+    #[arg(long, value_enum, default_value = "default")]
+    pub compression: Compression,
+
+    /// Rows per chunk and per zone, every chunk but the last holds exactly this many rows
+    ///
+    /// Without it, chunks grow in steps of 8192 rows until they reach `--chunk-bytes`.
+    #[arg(long, value_parser = clap::value_parser!(usize).range(1..), conflicts_with = "chunk_bytes")]
+    pub chunk_rows: Option<usize>,
+
+    /// Minimum size in bytes of a chunk before it is compressed
+    #[arg(long, default_value = "1048576")]
+    pub chunk_bytes: u64,
+
+    /// Do not write per-column statistics (zone map) layouts
+    #[arg(long)]
+    pub no_stats: bool,
+}
+
+impl WriteOptions {
+    /// Largest number of rows to feed the writer at once
+    pub fn input_chunk_rows(&self) -> usize {
+        self.chunk_rows.unwrap_or(DEFAULT_BLOCK_ROWS)
+    }
+}
+
+/// Build the layout strategy for the given write options
+///
+/// Mirrors the default Vortex write strategy, with the compressor and the
+/// statistics layout made configurable.
+///
+/// Repartitioning only merges and cuts at block boundaries, so with `--chunk-rows`
+/// the input must arrive in pieces of at most that many rows for every chunk to
+/// hold exactly `chunk_rows` rows, see [`WriteOptions::input_chunk_rows`].
+pub fn build_write_strategy(options: &WriteOptions) -> Arc<dyn LayoutStrategy> {
+    // Write each chunk as a flat layout
+    let chunked = ChunkedLayoutStrategy::new(FlatLayoutStrategy::default());
+
+    // Buffer chunks so segments of a column end up close together on disk
+    let buffered = BufferedStrategy::new(chunked, 2 * ONE_MEG);
+
+    // Compress each chunk
+    let compressing = match options.compression {
+        Compression::Default => CompressingStrategy::new_btrblocks(buffered, true),
+        Compression::Compact => {
+            CompressingStrategy::new_opaque(buffered, CompactCompressor::default())
+        }
+    };
+
+    // Coalesce chunks to exactly `chunk_rows` rows, or in steps of the default block
+    // up to a minimum size, before compression
+    let (block_rows, block_size_minimum) = match options.chunk_rows {
+        Some(chunk_rows) => (chunk_rows, 0),
+        None => (DEFAULT_BLOCK_ROWS, options.chunk_bytes),
+    };
+    let coalescing = RepartitionStrategy::new(
+        compressing,
+        RepartitionWriterOptions {
+            block_size_minimum,
+            block_len_multiple: block_rows,
+            canonicalize: true,
+        },
+    );
+
+    // Statistics tables and dictionary values are compressed into flat layouts
+    let compress_then_flat =
+        CompressingStrategy::new_btrblocks(FlatLayoutStrategy::default(), false);
+
+    // Apply dictionary encoding, falling back to the plain chunks
+    let dict = DictStrategy::new(
+        coalescing.clone(),
+        compress_then_flat.clone(),
+        coalescing,
+        Default::default(),
+    );
+
+    // Compute a zone map for each block of rows
+    let column: Arc<dyn LayoutStrategy> = if options.no_stats {
+        Arc::new(dict)
+    } else {
+        Arc::new(ZonedStrategy::new(
+            dict,
+            compress_then_flat.clone(),
+            ZonedLayoutOptions {
+                block_size: block_rows,
+                ..Default::default()
+            },
+        ))
+    };
+
+    // Repartition each column to multiples of the block rows
+    let repartition = RepartitionStrategy::new(
+        column,
+        RepartitionWriterOptions {
+            block_size_minimum: 0,
+            block_len_multiple: block_rows,
+            canonicalize: false,
+        },
+    );
+
+    // Split struct arrays into one layout per column
+    let validity = CollectStrategy::new(compress_then_flat);
+    Arc::new(TableStrategy::new(
+        Arc::new(validity),
+        Arc::new(repartition),
+    ))
+}
@@ -0,0 +1,70 @@
+//! Helpers shared by the unit tests
+
+use anyhow::Result;
+use arrow::array::RecordBatch;
+use std::path::Path;
+use std::sync::Arc;
+use vortex_array::ArrayRef;
+use vortex_array::arrow::FromArrowArray;
+use vortex_array::stream::ArrayStreamAdapter;
+use vortex_dtype::DType;
+use vortex_dtype::arrow::FromArrowType;
+use vortex_error::VortexResult;
+use vortex_file::WriteOptionsSessionExt;
+use vortex_layout::LayoutStrategy;
+
+use crate::create_session;
+use crate::strategy::{Compression, WriteOptions, build_write_strategy};
+
+/// Write record batches as a Vortex file with the default write strategy
+pub async fn write_vortex_file(path: &Path, batches: Vec<RecordBatch>) -> Result<()> {
+    write_batches(path, batches, None).await
+}
+
+/// Write record batches as a Vortex file with the strategy of the given write options
+pub async fn write_vortex_file_with(
+    path: &Path,
+    batches: Vec<RecordBatch>,
+    options: &WriteOptions,
+) -> Result<()> {
+    write_batches(path, batches, Some(build_write_strategy(options))).await
+}
+
+/// Write options cutting every column into chunks of `chunk_rows` rows
+pub fn chunked_write_options(chunk_rows: usize) -> WriteOptions {
+    WriteOptions {
+        compression: Compression::Default,
+        chunk_rows: Some(chunk_rows),
+        chunk_bytes: 1 << 20,
+        no_stats: false,
+    }
+}
+
+async fn write_batches(
+    path: &Path,
+    batches: Vec<RecordBatch>,
+    strategy: Option<Arc<dyn LayoutStrategy>>,
+) -> Result<()> {
+    let Some(first) = batches.first() else {
+        anyhow::bail!("At least one batch is required");
+    };
+    let dtype = DType::from_arrow(first.schema().as_ref());
+    let arrays = batches
+        .into_iter()
+        .map(|batch| VortexResult::Ok(ArrayRef::from_arrow(batch, false)));
+
+    let mut write_options = create_session().write_options();
+    if let Some(strategy) = strategy {
+        write_options = write_options.with_strategy(strategy);
+    }
+
+    let mut output_file = tokio::fs::File::create(path).await?;
+    write_options
+        .write(
+            &mut output_file,
+            ArrayStreamAdapter::new(dtype, futures::stream::iter(arrays)),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,484 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use vortex_buffer::{Alignment, ByteBuffer};
+use vortex_file::{OpenOptionsSessionExt, VortexFile};
+use vortex_io::{PerformanceHint, TokioFile, VortexReadAt};
+use vortex_session::VortexSession;
+
+use crate::bytemap::segment_tags;
+use crate::size::format_bytes;
+
+/// Options to trace the I/O issued by a command
+#[derive(clap::Args, Clone, Copy, Debug)]
+pub struct TraceArgs {
+    /// Record every byte range read from the file and print an I/O summary to stderr
+    #[arg(long)]
+    pub trace_io: bool,
+}
+
+/// `--trace-io` for commands that read files through DataFusion
+///
+/// Their reads go through DataFusion's object store rather than a traced
+/// reader, so the flag is accepted only to reject it with an explanation.
+#[derive(clap::Args, Clone, Copy, Debug)]
+pub struct UntracedArgs {
+    #[arg(long, hide = true)]
+    pub trace_io: bool,
+}
+
+impl UntracedArgs {
+    /// Fail if `--trace-io` was given to `command`
+    pub fn reject(self, command: &str) -> Result<()> {
+        if self.trace_io {
+            anyhow::bail!(
+                "--trace-io is not supported by {}: it reads files through DataFusion, which is not traced",
+                command
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A single read issued against a traced file
+#[derive(Clone, Debug)]
+struct ReadRecord {
+    range: Range<u64>,
+    started: Duration,
+    elapsed: Duration,
+}
+
+/// A data segment of a traced file with the column it belongs to
+#[derive(Clone, Debug)]
+struct TracedSegment {
+    id: u32,
+    range: Range<u64>,
+    column: String,
+}
+
+#[derive(Debug)]
+struct TracedFile {
+    path: PathBuf,
+    file_size: u64,
+    segments: Vec<TracedSegment>,
+    reads: Vec<ReadRecord>,
+}
+
+/// Reads recorded for every file opened while tracing is enabled
+#[derive(Debug)]
+pub struct IoTrace {
+    start: Instant,
+    files: Mutex<Vec<TracedFile>>,
+}
+
+tokio::task_local! {
+    /// Trace of the command running in the current task, set by `IoTrace::run`
+    static TRACE: Arc<IoTrace>;
+}
+
+/// The trace of the running command, if it is traced
+///
+/// The trace is a task-local, so files opened in a task spawned by the command
+/// are not traced. Reads of a traced file are recorded from any task, as its
+/// reader holds the trace itself.
+pub fn active() -> Option<Arc<IoTrace>> {
+    TRACE.try_with(Arc::clone).ok()
+}
+
+impl IoTrace {
+    pub fn new() -> Arc<Self> {
+        Arc::new(IoTrace {
+            start: Instant::now(),
+            files: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Run a command, recording in this trace the reads of every file it opens
+    pub async fn run<F: Future>(self: &Arc<Self>, command: F) -> F::Output {
+        TRACE.scope(Arc::clone(self), command).await
+    }
+
+    fn register(&self, path: &Path, file_size: u64) -> usize {
+        let mut files = self.files.lock().expect("trace lock poisoned");
+        files.push(TracedFile {
+            path: path.to_path_buf(),
+            file_size,
+            segments: Vec::new(),
+            reads: Vec::new(),
+        });
+        files.len() - 1
+    }
+
+    fn record(&self, file_id: usize, record: ReadRecord) {
+        let mut files = self.files.lock().expect("trace lock poisoned");
+        files[file_id].reads.push(record);
+    }
+
+    fn set_segments(&self, file_id: usize, segments: Vec<TracedSegment>) {
+        let mut files = self.files.lock().expect("trace lock poisoned");
+        files[file_id].segments = segments;
+    }
+}
+
+/// File reader recording every byte range it serves
+#[derive(Clone)]
+struct TracingReader {
+    inner: TokioFile,
+    trace: Arc<IoTrace>,
+    file_id: usize,
+}
+
+impl VortexReadAt for TracingReader {
+    fn read_byte_range(
+        &self,
+        range: Range<u64>,
+        alignment: Alignment,
+    ) -> impl Future<Output = io::Result<ByteBuffer>> + Send {
+        let inner = self.inner.clone();
+        let trace = Arc::clone(&self.trace);
+        let file_id = self.file_id;
+
+        async move {
+            let started = trace.start.elapsed();
+            let start = Instant::now();
+            let result = inner.read_byte_range(range.clone(), alignment).await;
+            trace.record(
+                file_id,
+                ReadRecord {
+                    range,
+                    started,
+                    elapsed: start.elapsed(),
+                },
+            );
+            result
+        }
+    }
+
+    fn performance_hint(&self) -> PerformanceHint {
+        self.inner.performance_hint()
+    }
+
+    fn size(&self) -> impl Future<Output = io::Result<u64>> + Send {
+        let inner = self.inner.clone();
+        async move { inner.size().await }
+    }
+}
+
+/// Open a Vortex file through a reader that records its reads in the trace
+pub async fn open_traced(
+    session: &VortexSession,
+    path: &Path,
+    trace: Arc<IoTrace>,
+) -> Result<VortexFile> {
+    let file_size = tokio::fs::metadata(path).await?.len();
+    let file_id = trace.register(path, file_size);
+    let reader = TracingReader {
+        inner: TokioFile::open(path)?,
+        trace: Arc::clone(&trace),
+        file_id,
+    };
+
+    let vortex_file = session
+        .open_options()
+        .open_read_at(reader)
+        .await
+        .context(format!("Failed to open Vortex file: {}", path.display()))?;
+
+    let tags = segment_tags(vortex_file.footer().layout());
+    let mut segments: Vec<TracedSegment> = vortex_file
+        .footer()
+        .segment_map()
+        .iter()
+        .enumerate()
+        .map(|(idx, spec)| TracedSegment {
+            id: idx as u32,
+            range: spec.offset..spec.offset + spec.length as u64,
+            column: tags
+                .get(&(idx as u32))
+                .map(|tag| tag.column.clone())
+                .unwrap_or_else(|| "<unknown>".to_string()),
+        })
+        .collect();
+    segments.sort_by_key(|s| s.range.start);
+    trace.set_segments(file_id, segments);
+
+    Ok(vortex_file)
+}
+
+fn overlap(a: &Range<u64>, b: &Range<u64>) -> u64 {
+    a.end.min(b.end).saturating_sub(a.start.max(b.start))
+}
+
+/// Segments overlapping a byte range, found by binary search in segments sorted by offset
+fn segments_in<'a>(
+    segments: &'a [TracedSegment],
+    range: &Range<u64>,
+) -> impl Iterator<Item = &'a TracedSegment> {
+    // Segments don't overlap each other, so their ends are sorted as well
+    let first = segments.partition_point(|s| s.range.end <= range.start);
+    let last = segments.partition_point(|s| s.range.start < range.end);
+    segments[first..last.max(first)]
+        .iter()
+        .filter(move |s| overlap(&s.range, range) > 0)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn print_file_trace(file: &TracedFile) {
+    eprintln!("\n=== I/O Trace ===");
+    eprintln!("File: {}", file.path.display());
+
+    eprintln!(
+        "\n{:>5} {:>14} {:>12} {:>10} {:>10}  {}",
+        "Read", "Offset", "Length", "Start (ms)", "Time (ms)", "Segments (column)"
+    );
+    eprintln!("{}", "-".repeat(100));
+
+    let mut segments_read = BTreeSet::new();
+    let mut bytes_by_column: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut segment_bytes = 0;
+
+    for (idx, read) in file.reads.iter().enumerate() {
+        let segments: Vec<&TracedSegment> = segments_in(&file.segments, &read.range).collect();
+
+        let mut read_segment_bytes = 0;
+        for segment in &segments {
+            let bytes = overlap(&segment.range, &read.range);
+            read_segment_bytes += bytes;
+            *bytes_by_column.entry(segment.column.as_str()).or_default() += bytes;
+            segments_read.insert(segment.id);
+        }
+        segment_bytes += read_segment_bytes;
+
+        let metadata_bytes = (read.range.end - read.range.start) - read_segment_bytes;
+        if metadata_bytes > 0 {
+            *bytes_by_column.entry("<metadata and gaps>").or_default() += metadata_bytes;
+        }
+
+        let description = if segments.is_empty() {
+            "footer / metadata".to_string()
+        } else {
+            segments
+                .iter()
+                .map(|s| format!("{} ({})", s.id, s.column))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        eprintln!(
+            "{:>5} {:>14} {:>12} {:>10.2} {:>10.2}  {}",
+            idx,
+            read.range.start,
+            read.range.end - read.range.start,
+            millis(read.started),
+            millis(read.elapsed),
+            description
+        );
+    }
+
+    let bytes_read: u64 = file.reads.iter().map(|r| r.range.end - r.range.start).sum();
+    let data_requests = file
+        .reads
+        .iter()
+        .filter(|r| segments_in(&file.segments, &r.range).next().is_some())
+        .count();
+    let total_segment_bytes: u64 = file
+        .segments
+        .iter()
+        .map(|s| s.range.end - s.range.start)
+        .sum();
+    let read_time: Duration = file.reads.iter().map(|r| r.elapsed).sum();
+
+    eprintln!("\n--- Summary ---");
+    eprintln!(
+        "Bytes read: {} of {} ({:.2}% of file)",
+        format_bytes(bytes_read),
+        format_bytes(file.file_size),
+        bytes_read as f64 * 100.0 / file.file_size.max(1) as f64
+    );
+    eprintln!(
+        "Requests: {} ({} for data segments)",
+        file.reads.len(),
+        data_requests
+    );
+    eprintln!(
+        "Segments read: {} of {} ({} of {} segment bytes)",
+        segments_read.len(),
+        file.segments.len(),
+        format_bytes(segment_bytes),
+        format_bytes(total_segment_bytes)
+    );
+    if data_requests > 0 {
+        eprintln!(
+            "Coalescing: {:.2} segments per data request",
+            segments_read.len() as f64 / data_requests as f64
+        );
+    }
+    eprintln!("Total read time: {:.2} ms", millis(read_time));
+
+    eprintln!("\n--- Bytes Read by Column ---");
+    for (column, bytes) in &bytes_by_column {
+        eprintln!("{:<40} {:>14}", column, format_bytes(*bytes));
+    }
+}
+
+/// Run a command, tracing the reads of every Vortex file it opens when enabled
+pub async fn traced<F>(args: TraceArgs, command: F) -> Result<()>
+where
+    F: Future<Output = Result<()>>,
+{
+    if !args.trace_io {
+        return command.await;
+    }
+
+    let trace = IoTrace::new();
+    let result = trace.run(command).await;
+
+    let files = trace.files.lock().expect("trace lock poisoned");
+    for file in files.iter() {
+        print_file_trace(file);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use futures::StreamExt;
+
+    use crate::open_vortex_file;
+    use crate::testing::write_vortex_file;
+
+    fn segment(id: u32, range: Range<u64>) -> TracedSegment {
+        TracedSegment {
+            id,
+            range,
+            column: format!("c{}", id),
+        }
+    }
+
+    fn ids(segments: &[TracedSegment], range: Range<u64>) -> Vec<u32> {
+        segments_in(segments, &range).map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn segments_in_range() {
+        let segments = vec![
+            segment(2, 0..10),
+            segment(0, 16..32),
+            segment(3, 32..32),
+            segment(1, 40..100),
+        ];
+
+        assert_eq!(ids(&segments, 0..10), vec![2]);
+        assert_eq!(ids(&segments, 5..20), vec![2, 0]);
+        assert_eq!(ids(&segments, 10..16), Vec::<u32>::new());
+        assert_eq!(ids(&segments, 31..41), vec![0, 1]);
+        assert_eq!(ids(&segments, 0..200), vec![2, 0, 1]);
+        assert_eq!(ids(&segments, 100..200), Vec::<u32>::new());
+    }
+
+    async fn write_traced_file(path: &Path) {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int64Array::from_iter_values(0..1000))],
+        )
+        .unwrap();
+        write_vortex_file(path, vec![batch]).await.unwrap();
+    }
+
+    /// Number of reads of a traced file that cover data segments
+    fn data_reads(file: &TracedFile) -> usize {
+        file.reads
+            .iter()
+            .filter(|read| segments_in(&file.segments, &read.range).next().is_some())
+            .count()
+    }
+
+    #[tokio::test]
+    async fn traces_are_independent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("traced.vortex");
+        write_traced_file(&path).await;
+
+        // Files opened outside of a traced run are not recorded
+        open_vortex_file(&path).await.unwrap();
+        assert!(active().is_none());
+
+        for _ in 0..2 {
+            let trace = IoTrace::new();
+            trace
+                .run(async {
+                    open_vortex_file(&path).await?;
+                    open_vortex_file(&path).await
+                })
+                .await
+                .unwrap();
+
+            let files = trace.files.lock().unwrap();
+            assert_eq!(files.len(), 2);
+            for file in files.iter() {
+                assert_eq!(file.file_size, std::fs::metadata(&path).unwrap().len());
+                assert!(!file.reads.is_empty());
+                assert!(!file.segments.is_empty());
+                assert!(
+                    file.segments
+                        .windows(2)
+                        .all(|pair| pair[0].range.start <= pair[1].range.start)
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn scan_reads_are_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("traced.vortex");
+        write_traced_file(&path).await;
+
+        let trace = IoTrace::new();
+        let vortex_file = trace.run(open_vortex_file(&path)).await.unwrap();
+        let opened = {
+            let files = trace.files.lock().unwrap();
+            assert_eq!(data_reads(&files[0]), 0);
+            files[0].reads.len()
+        };
+
+        // The scan runs in a spawned task, outside of the traced run
+        let rows = tokio::spawn(async move {
+            let stream = vortex_file.scan().unwrap().into_array_stream().unwrap();
+            stream
+                .map(|chunk| chunk.unwrap().len())
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .sum::<usize>()
+        })
+        .await
+        .unwrap();
+        assert_eq!(rows, 1000);
+
+        let files = trace.files.lock().unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].reads.len() > opened);
+        assert!(data_reads(&files[0]) > 0);
+    }
+
+    #[test]
+    fn untraced_commands_reject_trace_io() {
+        assert!(UntracedArgs { trace_io: false }.reject("query").is_ok());
+        let err = UntracedArgs { trace_io: true }.reject("query").unwrap_err();
+        assert!(err.to_string().contains("query"));
+    }
+}
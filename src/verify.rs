@@ -0,0 +1,600 @@
+use anyhow::Result;
+use futures::StreamExt;
+use std::cmp::Ordering;
+use std::path::Path;
+use vortex::expr::{root, select};
+use vortex_array::compute::min_max;
+use vortex_array::stats::{Precision, Stat, StatsSet};
+use vortex_array::{Array, ArrayRef, IntoArray};
+use vortex_dtype::{DType, FieldName, FieldNames};
+use vortex_file::{SegmentSpec, VortexFile};
+use vortex_scalar::{Scalar, ScalarValue};
+
+use crate::footer::{PostscriptSegment, VerifierLimits, read_raw_footer};
+use crate::{DocumentFormat, open_vortex_file, print_json};
+
+/// Outcome of a single verification check
+#[derive(Debug)]
+struct Check {
+    name: String,
+    passed: bool,
+    detail: String,
+}
+
+#[derive(Debug, Default)]
+struct Verification {
+    checks: Vec<Check>,
+}
+
+impl Verification {
+    fn pass(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name: name.into(),
+            passed: true,
+            detail: detail.into(),
+        });
+    }
+
+    fn fail(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name: name.into(),
+            passed: false,
+            detail: detail.into(),
+        });
+    }
+
+    fn failures(&self) -> usize {
+        self.checks.iter().filter(|c| !c.passed).count()
+    }
+}
+
+/// Check that data segments lie before the postscript and that no two regions overlap
+fn check_segments(
+    verification: &mut Verification,
+    segment_map: &[SegmentSpec],
+    flatbuffers: &[PostscriptSegment],
+    data_end: u64,
+) {
+    // (offset, end, description) of every region referenced by the file
+    let mut regions: Vec<(u64, u64, String)> = Vec::new();
+    let mut errors = Vec::new();
+
+    for segment in flatbuffers {
+        match segment.end() {
+            Ok(end) => regions.push((segment.offset, end, format!("{} flatbuffer", segment.name))),
+            Err(e) => errors.push(e.to_string()),
+        }
+
+        match 1u64.checked_shl(segment.alignment_exponent as u32) {
+            Some(alignment) if segment.offset % alignment != 0 => errors.push(format!(
+                "{} flatbuffer at offset {} is not aligned to {} bytes",
+                segment.name, segment.offset, alignment
+            )),
+            Some(_) => {}
+            None => errors.push(format!(
+                "{} flatbuffer has an invalid alignment exponent {}",
+                segment.name, segment.alignment_exponent
+            )),
+        }
+    }
+
+    for (idx, spec) in segment_map.iter().enumerate() {
+        let Some(end) = spec.offset.checked_add(spec.length as u64) else {
+            errors.push(format!(
+                "segment {} at offset {} with length {} overflows",
+                idx, spec.offset, spec.length
+            ));
+            continue;
+        };
+        if end > data_end {
+            errors.push(format!(
+                "segment {} ({}..{}) extends beyond the data region ending at {}",
+                idx, spec.offset, end, data_end
+            ));
+        }
+
+        let alignment = *spec.alignment as u64;
+        if alignment > 0 && spec.offset % alignment != 0 {
+            errors.push(format!(
+                "segment {} at offset {} is not aligned to {} bytes",
+                idx, spec.offset, alignment
+            ));
+        }
+
+        regions.push((spec.offset, end, format!("segment {}", idx)));
+    }
+
+    regions.sort();
+    for pair in regions.windows(2) {
+        let (_, prev_end, prev) = &pair[0];
+        let (offset, _, next) = &pair[1];
+        if offset < prev_end {
+            errors.push(format!(
+                "{} overlaps {} ({} < {})",
+                next, prev, offset, prev_end
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        verification.pass(
+            "segments",
+            format!(
+                "{} segments within bounds and non-overlapping",
+                segment_map.len()
+            ),
+        );
+    } else {
+        verification.fail("segments", errors.join("; "));
+    }
+}
+
+/// Statistics recomputed from the decoded chunks of a column
+#[derive(Debug, Default)]
+struct ComputedStats {
+    rows: u64,
+    chunks: usize,
+    null_count: u64,
+    min: Option<Scalar>,
+    max: Option<Scalar>,
+}
+
+impl ComputedStats {
+    fn add(&mut self, array: &ArrayRef) -> Result<()> {
+        self.rows += array.len() as u64;
+        self.chunks += 1;
+        self.null_count += array.invalid_count()? as u64;
+
+        // Types without an ordering have no min/max statistics
+        if let Ok(Some(result)) = min_max(array) {
+            if self
+                .min
+                .as_ref()
+                .is_none_or(|min| result.min.partial_cmp(min) == Some(Ordering::Less))
+            {
+                self.min = Some(result.min);
+            }
+            if self
+                .max
+                .as_ref()
+                .is_none_or(|max| result.max.partial_cmp(max) == Some(Ordering::Greater))
+            {
+                self.max = Some(result.max);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Decode every chunk of a column and recompute its statistics
+async fn decode_column(vortex_file: &VortexFile, name: Option<&str>) -> Result<ComputedStats> {
+    let mut scan = vortex_file.scan()?;
+    if let Some(name) = name {
+        let names: FieldNames = [FieldName::from(name)].into_iter().collect();
+        scan = scan.with_projection(select(names, root()));
+    }
+
+    let stream = scan.into_array_stream()?;
+    let mut stream = std::pin::pin!(stream);
+    let mut stats = ComputedStats::default();
+
+    while let Some(chunk) = stream.next().await {
+        let canonical = chunk?.to_canonical()?;
+        let column = match name {
+            Some(_) => canonical.into_struct().fields()[0].clone(),
+            None => canonical.into_array(),
+        };
+        stats.add(&column.to_canonical()?.into_array())?;
+    }
+
+    Ok(stats)
+}
+
+/// Compare a stored min or max statistic against the recomputed value
+///
+/// Exact statistics must match, inexact ones only need to bound the actual value.
+fn compare_bound(
+    stat: Stat,
+    stored: Option<Precision<ScalarValue>>,
+    computed: Option<&Scalar>,
+    dtype: &DType,
+) -> Result<Option<String>> {
+    let (Some(stored), Some(computed), Some(stat_dtype)) = (stored, computed, stat.dtype(dtype))
+    else {
+        return Ok(None);
+    };
+
+    let (value, exact) = match stored {
+        Precision::Exact(value) => (value, true),
+        Precision::Inexact(value) => (value, false),
+    };
+    let stored = Scalar::new(stat_dtype.clone(), value);
+    let computed = computed.cast(&stat_dtype)?;
+
+    let ordering = stored.partial_cmp(&computed);
+    let valid = match (exact, stat) {
+        (true, _) => ordering == Some(Ordering::Equal),
+        (false, Stat::Min) => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        (false, _) => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    };
+
+    Ok((!valid).then(|| {
+        format!(
+            "stored {} {}{} does not match computed {}",
+            stat,
+            stored,
+            if exact { "" } else { " (inexact)" },
+            computed
+        )
+    }))
+}
+
+fn compare_stats(
+    stored: &StatsSet,
+    computed: &ComputedStats,
+    dtype: &DType,
+) -> Result<Vec<String>> {
+    let mut errors = Vec::new();
+
+    if let Some(precision) = stored.get(Stat::NullCount)
+        && let Some(stat_dtype) = Stat::NullCount.dtype(dtype)
+    {
+        let (value, exact) = match precision {
+            Precision::Exact(value) => (value, true),
+            Precision::Inexact(value) => (value, false),
+        };
+        let null_count = Scalar::new(stat_dtype, value).as_primitive().as_::<u64>();
+        if exact && null_count != Some(computed.null_count) {
+            errors.push(format!(
+                "stored null_count {:?} does not match computed {}",
+                null_count, computed.null_count
+            ));
+        }
+    }
+
+    for (stat, value) in [
+        (Stat::Min, computed.min.as_ref()),
+        (Stat::Max, computed.max.as_ref()),
+    ] {
+        if let Some(error) = compare_bound(stat, stored.get(stat), value, dtype)? {
+            errors.push(error);
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Expected row count of a column from the layout tree
+fn layout_row_count(vortex_file: &VortexFile, name: Option<&str>) -> u64 {
+    let layout = vortex_file.footer().layout();
+
+    name.and_then(|name| {
+        (0..layout.nchildren())
+            .find(|idx| layout.child_type(*idx).name().as_ref() == name)
+            .and_then(|idx| layout.child(idx).ok())
+            .map(|child| child.row_count())
+    })
+    .unwrap_or_else(|| layout.row_count())
+}
+
+async fn check_columns(verification: &mut Verification, vortex_file: &VortexFile) {
+    let dtype = vortex_file.dtype();
+    let columns: Vec<(Option<String>, DType)> = match dtype.as_struct_fields_opt() {
+        Some(fields) => fields
+            .names()
+            .iter()
+            .zip(fields.fields())
+            .map(|(name, field)| (Some(name.to_string()), field))
+            .collect(),
+        None => vec![(None, dtype.clone())],
+    };
+    let file_stats = vortex_file.file_stats();
+
+    if vortex_file.row_count() == vortex_file.footer().layout().row_count() {
+        verification.pass("row count", format!("{} rows", vortex_file.row_count()));
+    } else {
+        verification.fail(
+            "row count",
+            format!(
+                "file reports {} rows but the root layout has {}",
+                vortex_file.row_count(),
+                vortex_file.footer().layout().row_count()
+            ),
+        );
+    }
+
+    for (idx, (name, field_dtype)) in columns.iter().enumerate() {
+        let label = name.as_deref().unwrap_or("<root>");
+        let computed = match decode_column(vortex_file, name.as_deref()).await {
+            Ok(computed) => computed,
+            Err(e) => {
+                verification.fail(format!("decode {}", label), format!("{:#}", e));
+                continue;
+            }
+        };
+
+        let expected_rows = layout_row_count(vortex_file, name.as_deref());
+        if computed.rows == expected_rows {
+            verification.pass(
+                format!("decode {}", label),
+                format!("{} chunks, {} rows", computed.chunks, computed.rows),
+            );
+        } else {
+            verification.fail(
+                format!("decode {}", label),
+                format!(
+                    "decoded {} rows but the layout has {}",
+                    computed.rows, expected_rows
+                ),
+            );
+        }
+
+        let Some(stored) = file_stats.as_ref().and_then(|stats| stats.get(idx)) else {
+            continue;
+        };
+        match compare_stats(stored, &computed, field_dtype) {
+            Ok(errors) if errors.is_empty() => verification.pass(
+                format!("stats {}", label),
+                "stored statistics match the data",
+            ),
+            Ok(errors) => verification.fail(format!("stats {}", label), errors.join("; ")),
+            Err(e) => verification.fail(format!("stats {}", label), format!("{:#}", e)),
+        }
+    }
+}
+
+/// Run every check against a file, stopping early only when it cannot be opened
+async fn verify(path: &Path, limits: &VerifierLimits) -> Verification {
+    let mut verification = Verification::default();
+
+    match read_raw_footer(path, limits).await {
+        Ok(raw) => {
+            verification.pass(
+                "footer",
+                format!(
+                    "version {}, postscript {} bytes, file {} bytes, flatbuffers verified",
+                    raw.eof.version, raw.eof.postscript_size, raw.file_size
+                ),
+            );
+
+            match open_vortex_file(path).await {
+                Ok(vortex_file) => {
+                    check_segments(
+                        &mut verification,
+                        vortex_file.footer().segment_map(),
+                        &raw.segments,
+                        raw.postscript_offset,
+                    );
+                    check_columns(&mut verification, &vortex_file).await;
+                }
+                Err(e) => verification.fail("open", format!("{:#}", e)),
+            }
+        }
+        Err(e) => verification.fail("footer", e.to_string()),
+    }
+
+    verification
+}
+
+/// Fully decode and validate a Vortex file, failing if any check fails
+pub async fn run_verify(
+    path: &Path,
+    format: DocumentFormat,
+    limits: &VerifierLimits,
+) -> Result<()> {
+    let verification = verify(path, limits).await;
+    let failures = verification.failures();
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let verify_json = serde_json::json!({
+                "file": path.display().to_string(),
+                "passed": failures == 0,
+                "checks": verification
+                    .checks
+                    .iter()
+                    .map(|c| serde_json::json!({
+                        "check": c.name,
+                        "passed": c.passed,
+                        "detail": c.detail,
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            print_json(&verify_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex File Verification ===");
+            println!("File: {}", path.display());
+            println!();
+
+            for check in &verification.checks {
+                println!(
+                    "[{}] {:<30} {}",
+                    if check.passed { "PASS" } else { "FAIL" },
+                    check.name,
+                    check.detail
+                );
+            }
+
+            println!();
+            if failures == 0 {
+                println!("All {} checks passed", verification.checks.len());
+            }
+        }
+    }
+
+    if failures > 0 {
+        anyhow::bail!(
+            "Verification failed: {} of {} checks failed",
+            failures,
+            verification.checks.len()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::io::{Seek, SeekFrom, Write};
+    use std::sync::Arc;
+
+    use crate::testing::write_vortex_file;
+
+    async fn valid_file(path: &Path) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("host", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..1000)),
+                Arc::new(StringArray::from_iter(
+                    (0..1000).map(|i| (i % 10 != 0).then(|| format!("host-{}", i))),
+                )),
+            ],
+        )
+        .unwrap();
+        write_vortex_file(path, vec![batch]).await.unwrap();
+    }
+
+    fn failed_checks(verification: &Verification) -> Vec<&str> {
+        verification
+            .checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn valid_file_passes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("valid.vortex");
+        valid_file(&path).await;
+
+        let verification = verify(&path, &VerifierLimits::default()).await;
+        assert_eq!(failed_checks(&verification), Vec::<&str>::new());
+        assert!(
+            run_verify(&path, DocumentFormat::Json, &VerifierLimits::default())
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn corrupted_data_segments_fail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrupted.vortex");
+        valid_file(&path).await;
+
+        // Overwrite every data segment, the footer is left intact
+        let segments: Vec<SegmentSpec> = open_vortex_file(&path)
+            .await
+            .unwrap()
+            .footer()
+            .segment_map()
+            .to_vec();
+        let mut file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        for segment in &segments {
+            file.seek(SeekFrom::Start(segment.offset)).unwrap();
+            file.write_all(&vec![0xA5; segment.length as usize])
+                .unwrap();
+        }
+        drop(file);
+
+        let verification = verify(&path, &VerifierLimits::default()).await;
+        assert!(verification.failures() > 0);
+        assert!(
+            run_verify(&path, DocumentFormat::Json, &VerifierLimits::default())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn overlapping_and_out_of_range_segments_fail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("valid.vortex");
+        valid_file(&path).await;
+
+        let limits = VerifierLimits::default();
+        let raw = read_raw_footer(&path, &limits).await.unwrap();
+        let vortex_file = open_vortex_file(&path).await.unwrap();
+        let segments = vortex_file.footer().segment_map().to_vec();
+        let check = |segments: &[SegmentSpec]| {
+            let mut verification = Verification::default();
+            check_segments(
+                &mut verification,
+                segments,
+                &raw.segments,
+                raw.postscript_offset,
+            );
+            failed_checks(&verification) == vec!["segments"]
+        };
+
+        assert!(!check(&segments));
+
+        let mut overlapping = segments.clone();
+        overlapping.push(segments[0].clone());
+        assert!(check(&overlapping));
+
+        let mut out_of_range = segments.clone();
+        out_of_range[0].offset = raw.postscript_offset;
+        assert!(check(&out_of_range));
+    }
+
+    #[test]
+    fn stats_mismatches_fail() {
+        let dtype = DType::Primitive(vortex_dtype::PType::I64, false.into());
+        let computed = ComputedStats {
+            rows: 10,
+            chunks: 1,
+            null_count: 0,
+            min: Some(Scalar::from(0i64)),
+            max: Some(Scalar::from(9i64)),
+        };
+        let stats = |entries: &[(Stat, Precision<i64>)]| {
+            let mut stats = StatsSet::default();
+            for (stat, value) in entries {
+                let value = value.clone().map(|v| {
+                    if *stat == Stat::NullCount {
+                        Scalar::from(v as u64).value().clone()
+                    } else {
+                        Scalar::from(v).value().clone()
+                    }
+                });
+                stats.set(*stat, value);
+            }
+            stats
+        };
+        let errors = |entries: &[(Stat, Precision<i64>)]| {
+            compare_stats(&stats(entries), &computed, &dtype)
+                .unwrap()
+                .len()
+        };
+
+        assert_eq!(errors(&[]), 0);
+        assert_eq!(
+            errors(&[
+                (Stat::Min, Precision::Exact(0)),
+                (Stat::Max, Precision::Exact(9)),
+                (Stat::NullCount, Precision::Exact(0)),
+            ]),
+            0
+        );
+        assert_eq!(errors(&[(Stat::Min, Precision::Exact(1))]), 1);
+        assert_eq!(errors(&[(Stat::Max, Precision::Exact(10))]), 1);
+        assert_eq!(errors(&[(Stat::NullCount, Precision::Exact(3))]), 1);
+
+        // Inexact bounds only need to contain the data
+        assert_eq!(errors(&[(Stat::Min, Precision::Inexact(-5))]), 0);
+        assert_eq!(errors(&[(Stat::Max, Precision::Inexact(20))]), 0);
+        assert_eq!(errors(&[(Stat::Max, Precision::Inexact(5))]), 1);
+    }
+}
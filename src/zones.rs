@@ -0,0 +1,406 @@
+use anyhow::{Context, Result};
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::path::Path;
+use vortex::expr::root;
+use vortex::mask::Mask;
+use vortex_array::Array;
+use vortex_file::VortexFile;
+use vortex_layout::layouts::zoned::ZonedVTable;
+use vortex_layout::{LayoutReader, LayoutRef};
+use vortex_scalar::Scalar;
+
+use crate::stats::format_scalar;
+use crate::{DocumentFormat, model, open_vortex_file, print_json, truncate_string};
+
+/// Width of the min and max columns of the text table
+const VALUE_WIDTH: usize = 28;
+
+fn is_zoned(layout: &LayoutRef) -> bool {
+    matches!(
+        layout.encoding().to_string().as_str(),
+        "vortex.stats" | "vortex.zoned"
+    )
+}
+
+/// A zone map found in a column's layout tree
+struct ZoneMap {
+    /// Layout path of the zoned layout within the column
+    path: String,
+    /// First row of the column covered by the zoned layout
+    row_offset: u64,
+    row_count: u64,
+    zone_len: u64,
+    /// The zones table, one row per zone and one field per statistic
+    zones: LayoutRef,
+}
+
+/// Rows per zone of a zoned layout
+fn zone_len(layout: &LayoutRef) -> Result<u64> {
+    let zoned = layout.as_opt::<ZonedVTable>().ok_or_else(|| {
+        anyhow::anyhow!(
+            "{} layout is not a zoned layout known to this build",
+            layout.encoding()
+        )
+    })?;
+    match zoned.zone_len() {
+        0 => anyhow::bail!("Zoned layout has zones of 0 rows"),
+        zone_len => Ok(zone_len as u64),
+    }
+}
+
+/// Collect the zoned layouts of a column, tracking the rows each one covers
+fn find_zone_maps(
+    layout: &LayoutRef,
+    path: &str,
+    row_offset: u64,
+    zone_maps: &mut Vec<ZoneMap>,
+) -> Result<()> {
+    if is_zoned(layout)
+        && layout.nchildren() > 1
+        && let Ok(zones) = layout.child(1)
+    {
+        let zone_len = zone_len(layout).context(format!(
+            "Failed to read the zone length at {}",
+            if path.is_empty() { "/" } else { path }
+        ))?;
+
+        zone_maps.push(ZoneMap {
+            path: if path.is_empty() {
+                "/".to_string()
+            } else {
+                path.to_string()
+            },
+            row_offset,
+            row_count: layout.row_count(),
+            zone_len,
+            zones,
+        });
+    }
+
+    let is_chunked = layout.encoding().to_string() == "vortex.chunked";
+    let mut child_offset = row_offset;
+    for idx in 0..layout.nchildren() {
+        // The zones table of a zoned layout describes its data, it holds no zone map itself
+        if is_zoned(layout) && idx > 0 {
+            break;
+        }
+        let Ok(child) = layout.child(idx) else {
+            continue;
+        };
+
+        let child_path = format!("{}/{}", path, layout.child_type(idx).name());
+        find_zone_maps(&child, &child_path, child_offset, zone_maps)?;
+        if is_chunked {
+            child_offset += child.row_count();
+        }
+    }
+
+    Ok(())
+}
+
+/// Statistics of a single zone
+struct Zone {
+    rows: Range<u64>,
+    /// Value of each statistic of the zones table, in field order
+    stats: Vec<(String, Scalar)>,
+}
+
+impl Zone {
+    fn stat(&self, name: &str) -> Option<&Scalar> {
+        self.stats
+            .iter()
+            .find(|(stat, _)| stat == name)
+            .map(|(_, value)| value)
+    }
+
+    fn cell(&self, name: &str) -> String {
+        self.stat(name)
+            .map(format_scalar)
+            .unwrap_or_else(|| "missing".to_string())
+    }
+}
+
+/// Read the zones table of a zone map
+async fn read_zones(vortex_file: &VortexFile, zone_map: &ZoneMap) -> Result<Vec<Zone>> {
+    let zone_count = zone_map.zones.row_count();
+    let reader = zone_map
+        .zones
+        .new_reader("zones".into(), vortex_file.segment_source())?;
+    let table = reader
+        .projection_evaluation(
+            &(0..zone_count),
+            &root(),
+            Mask::new_true(zone_count as usize),
+        )?
+        .await?
+        .to_canonical()?
+        .into_struct();
+
+    let names: Vec<String> = table.names().iter().map(|n| n.to_string()).collect();
+    let end = zone_map.row_offset + zone_map.row_count;
+
+    (0..zone_count as usize)
+        .map(|idx| {
+            let start = (zone_map.row_offset + idx as u64 * zone_map.zone_len).min(end);
+            let stats = names
+                .iter()
+                .zip(table.fields().iter())
+                .map(|(name, field)| Ok((name.clone(), field.scalar_at(idx)?)))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Zone {
+                rows: start..(start + zone_map.zone_len).min(end),
+                stats,
+            })
+        })
+        .collect()
+}
+
+/// Whether each zone overlaps the zones before it: its min is below the max of all previous zones
+fn overlapping_zones(zones: &[Zone]) -> Vec<bool> {
+    let mut running_max: Option<&Scalar> = None;
+    zones
+        .iter()
+        .map(|zone| {
+            let overlaps = match (running_max, zone.stat("min")) {
+                (Some(max), Some(min)) if !min.is_null() => {
+                    min.partial_cmp(max) == Some(Ordering::Less)
+                }
+                _ => false,
+            };
+            if let Some(max) = zone.stat("max").filter(|max| !max.is_null())
+                && running_max
+                    .is_none_or(|current| max.partial_cmp(current) == Some(Ordering::Greater))
+            {
+                running_max = Some(max);
+            }
+            overlaps
+        })
+        .collect()
+}
+
+/// Whether the non-null values of a statistic never decrease from zone to zone
+fn is_non_decreasing(zones: &[Zone], stat: &str) -> bool {
+    let values: Vec<&Scalar> = zones
+        .iter()
+        .filter_map(|z| z.stat(stat))
+        .filter(|v| !v.is_null())
+        .collect();
+    values
+        .windows(2)
+        .all(|pair| pair[0].partial_cmp(pair[1]) != Some(Ordering::Greater))
+}
+
+/// Print the zone map of a column: each zone's row range with its min, max and null count
+pub async fn show_zones(path: &Path, column: &str, format: DocumentFormat) -> Result<()> {
+    let vortex_file = open_vortex_file(path).await?;
+    let layout = vortex_file.footer().layout();
+
+    if layout.encoding().to_string() != "vortex.struct" {
+        anyhow::bail!("Zone maps are looked up by column, which requires a struct file");
+    }
+    let column_layout = (0..layout.nchildren())
+        .find(|idx| layout.child_type(*idx).name().as_ref() == column)
+        .map(|idx| layout.child(idx))
+        .ok_or_else(|| anyhow::anyhow!("Column not found: {}", column))??;
+
+    let mut zone_maps = Vec::new();
+    find_zone_maps(&column_layout, "", 0, &mut zone_maps)?;
+
+    let mut results = Vec::with_capacity(zone_maps.len());
+    for zone_map in &zone_maps {
+        let zones = read_zones(&vortex_file, zone_map)
+            .await
+            .context(format!("Failed to read the zone map at {}", zone_map.path))?;
+        let overlaps = overlapping_zones(&zones);
+        results.push((zones, overlaps));
+    }
+
+    match format {
+        DocumentFormat::Json | DocumentFormat::Ndjson => {
+            let zones_json = serde_json::json!({
+                "schema_version": model::JSON_SCHEMA_VERSION,
+                "file": path.display().to_string(),
+                "column": column,
+                "zone_maps": zone_maps
+                    .iter()
+                    .zip(&results)
+                    .map(|(zone_map, (zones, overlaps))| serde_json::json!({
+                        "path": zone_map.path,
+                        "row_offset": zone_map.row_offset,
+                        "row_count": zone_map.row_count,
+                        "zone_len": zone_map.zone_len,
+                        "sorted_by_min": is_non_decreasing(zones, "min"),
+                        "sorted_by_max": is_non_decreasing(zones, "max"),
+                        "overlapping_zones": overlaps.iter().filter(|o| **o).count(),
+                        "zones": zones
+                            .iter()
+                            .zip(overlaps)
+                            .map(|(zone, overlap)| {
+                                let mut stats_json = serde_json::Map::new();
+                                for (name, value) in &zone.stats {
+                                    stats_json.insert(name.clone(), model::scalar_json(value));
+                                }
+                                serde_json::json!({
+                                    "row_start": zone.rows.start,
+                                    "row_end": zone.rows.end,
+                                    "overlaps_previous": overlap,
+                                    "stats": stats_json,
+                                })
+                            })
+                            .collect::<Vec<_>>(),
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            print_json(&zones_json, &format)?;
+        }
+        DocumentFormat::Text => {
+            println!("=== Vortex Zone Map ===");
+            println!("File: {}", path.display());
+            println!("Column: {}", column);
+
+            if zone_maps.is_empty() {
+                println!(
+                    "\nNo zone map: the column's layout has no vortex.stats or vortex.zoned layout"
+                );
+            }
+
+            for (zone_map, (zones, overlaps)) in zone_maps.iter().zip(&results) {
+                println!("\n--- Zones at {} ---", zone_map.path);
+                println!(
+                    "Rows {}..{}, {} zones of {} rows",
+                    zone_map.row_offset,
+                    zone_map.row_offset + zone_map.row_count,
+                    zones.len(),
+                    zone_map.zone_len
+                );
+                println!(
+                    "Statistics: {}",
+                    zones
+                        .first()
+                        .map(|z| z
+                            .stats
+                            .iter()
+                            .map(|(n, _)| n.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", "))
+                        .unwrap_or_default()
+                );
+                println!();
+
+                println!(
+                    "{:>6} {:>24} {:<w$} {:<w$} {:>10}  {}",
+                    "Zone",
+                    "Rows",
+                    "Min",
+                    "Max",
+                    "Nulls",
+                    "",
+                    w = VALUE_WIDTH
+                );
+                println!("{}", "-".repeat(50 + 2 * VALUE_WIDTH));
+
+                for (idx, (zone, overlap)) in zones.iter().zip(overlaps).enumerate() {
+                    println!(
+                        "{:>6} {:>24} {:<w$} {:<w$} {:>10}  {}",
+                        idx,
+                        format!("{}..{}", zone.rows.start, zone.rows.end),
+                        truncate_string(&zone.cell("min"), VALUE_WIDTH),
+                        truncate_string(&zone.cell("max"), VALUE_WIDTH),
+                        zone.cell("null_count"),
+                        if *overlap {
+                            "overlaps earlier zones"
+                        } else {
+                            ""
+                        },
+                        w = VALUE_WIDTH
+                    );
+                }
+
+                println!();
+                println!(
+                    "Sorted by min: {}, sorted by max: {}",
+                    if is_non_decreasing(zones, "min") {
+                        "yes"
+                    } else {
+                        "no"
+                    },
+                    if is_non_decreasing(zones, "max") {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                );
+                println!(
+                    "Zones overlapping earlier zones: {} of {}",
+                    overlaps.iter().filter(|o| **o).count(),
+                    zones.len()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(min: impl Into<Scalar>, max: impl Into<Scalar>) -> Zone {
+        Zone {
+            rows: 0..1,
+            stats: vec![
+                ("min".to_string(), min.into()),
+                ("max".to_string(), max.into()),
+            ],
+        }
+    }
+
+    #[test]
+    fn overlaps_use_the_max_of_all_previous_zones() {
+        let zones = vec![zone(0, 100), zone(10, 20), zone(30, 40), zone(100, 120)];
+        assert_eq!(overlapping_zones(&zones), vec![false, true, true, false]);
+    }
+
+    #[test]
+    fn sorted_zones_do_not_overlap() {
+        let zones = vec![zone(0, 9), zone(10, 19), zone(19, 25)];
+        assert_eq!(overlapping_zones(&zones), vec![false, false, false]);
+        assert!(is_non_decreasing(&zones, "min"));
+        assert!(is_non_decreasing(&zones, "max"));
+    }
+
+    #[test]
+    fn non_ascii_zones() {
+        let zones = vec![
+            zone(
+                "äpfel und birnen aus österreich",
+                "öl und essig für den salat",
+            ),
+            zone(
+                "日本語のテキスト",
+                "日本語のテキストの最大値です。とても長い値",
+            ),
+            zone("日本", "日本語"),
+        ];
+        assert_eq!(overlapping_zones(&zones), vec![false, false, true]);
+        assert!(!is_non_decreasing(&zones, "min"));
+
+        // Cells are cut on character boundaries
+        for zone in &zones {
+            for stat in ["min", "max"] {
+                let cell = truncate_string(&zone.cell(stat), VALUE_WIDTH);
+                assert!(cell.chars().count() <= VALUE_WIDTH);
+            }
+        }
+        assert_eq!(
+            truncate_string(&zones[0].cell("min"), VALUE_WIDTH),
+            "äpfel und birnen aus öste..."
+        );
+        assert_eq!(
+            truncate_string(&zones[1].cell("max"), VALUE_WIDTH),
+            "日本語のテキストの最大値です。とても長い値"
+        );
+    }
+}